@@ -30,6 +30,50 @@ pub enum MagnetError {
     #[error("There must be at least 3 vertices")]
     PolygonSideError(),
 
+    /// Polygon edge of zero length, between vertex {0} and the next vertex
+    #[error("Polygon edge {0} has zero length")]
+    PolygonZeroLengthEdgeError(usize),
+
+    /// Two non-consecutive polygon vertices coincide
+    #[error("Polygon vertices {0} and {1} coincide")]
+    PolygonDuplicateVertexError(usize, usize),
+
+    /// Polygon vertex lying on the line through its neighbours
+    #[error("Polygon vertex {0} is collinear with its neighbours")]
+    PolygonCollinearVertexError(usize),
+
+    /// Two non-adjacent polygon edges cross or touch
+    #[error("Polygon edges {0} and {1} intersect")]
+    PolygonSelfIntersectionError(usize, usize),
+
+    /// Polygon hole outside the polygon, or overlapping another hole
+    #[error("Hole {0} must lie inside the polygon without touching its edges or other holes")]
+    PolygonHoleError(usize),
+
+    /// Polygon with no ear left to clip, with {0} vertices remaining
+    #[error("Polygon could not be triangulated, {0} vertices remain with no ear")]
+    PolygonTriangulationError(usize),
+
+    /// Polygon hole with no vertex of the outer ring visible from it
+    #[error("Hole {0} could not be bridged to the outer ring of the polygon")]
+    PolygonBridgeError(usize),
+
+    /// Magnet index error
+    #[error("There is no magnet at index {0}")]
+    MagnetIndexError(usize),
+
+    /// Polyhedron face error
+    #[error("Polyhedron faces must be triangles of existing vertices, enclosing a volume")]
+    PolyhedronFaceError(),
+
+    /// Iterative solver failed to converge
+    #[error("Solver did not converge after {0} iterations, residual {1:e}")]
+    ConvergenceError(usize, f64),
+
+    /// Unrecognised value of a string option in a config file
+    #[error("Unrecognised {0} \"{1}\" in config")]
+    ConfigValueError(String, String),
+
     // #[error("Could not parse float: {0}")]
     // StackError(),
     // /// Generic custom errors, string is passed to it
@@ -62,6 +62,9 @@ pub const I_2PI: f64 = 1.0 / M2_PI;
 /// 1/(2*PI)
 pub const I_4PI: f64 = 1.0 / M4_PI;
 
+/// Permeability of free space, 4*PI*1e-7 H/m
+pub const MU0: f64 = 4e-7 * PI;
+
 /// Floating point cutoff for vector alignment 1e-6
 pub const FP_CUTOFF: f64 = 1e-6;
 
@@ -3,7 +3,7 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 Copyright 2021 Peter Dunne */
 
-//! The magnets module contains the 2D magnet objects
+//! The magnets module contains the 2D and 3D magnet objects
 //! and their methods for calculating magnetic fields
 //!
 //! # Magnet Structs
@@ -15,10 +15,24 @@ Copyright 2021 Peter Dunne */
 
 mod base;
 mod magnet2d;
-// mod magnet3d;
+mod magnet3d;
 pub use base::*;
 
 pub use magnet2d::{
-    get_field_2d, loop_field_2d, sheet_field, Circle, Magnet2D, MagnetTrait2D, PolyDimension,
-    Polygon, Rectangle, Vertices,
+    alternating_array_2d, charge_elements_2d, circular_halbach_2d, current_elements_2d,
+    get_energy_2d, get_field_2d, get_field_arc_segment, get_field_boundary_2d, get_field_ellipse,
+    get_field_kind_2d, get_field_ring, get_field_wire, get_flux_2d, get_force_2d,
+    get_force_torque_2d, get_gradient_2d, get_gradient_arc_segment, get_gradient_ellipse,
+    get_gradient_ring, get_gradient_wire, get_potential_2d, get_potential_arc_segment,
+    get_potential_ellipse, get_potential_ring, get_potential_wire, get_torque_2d, image_magnets_2d,
+    interaction_energy_2d, linear_halbach_2d, loop_field_2d, loop_field_boundary_2d,
+    loop_field_kind_2d, loop_gradient_2d, loop_magnetisation_2d, loop_potential_2d,
+    point_in_polygon, self_energy_2d, sheet_field, sheet_gradient, sheet_potential,
+    solve_magnetisation_2d, surface_elements_2d, validate_holes, validate_vertices, ArcSegment,
+    BoundaryKind, ChargeElement, Circle, CurrentElement, Ellipse, Energy2D, Gradient2,
+    LinearMagnet2D, Magnet2D, MagnetTrait2D, PlaneBoundary, PolyDimension, Polygon, Rectangle,
+    Ring, RingMagnetisation, RotationSense, SegmentShape, SolverSettings2D, SurfaceElement,
+    Vertices, Wire, WireSection,
 };
+
+pub use magnet3d::*;
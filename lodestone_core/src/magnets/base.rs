@@ -1,6 +1,9 @@
 use crate::parse::MagnetKind;
+use crate::points::{Point2, Point3};
+use crate::MagnetError;
+use serde_derive::{Deserialize, Serialize};
 
-use super::Magnet2D;
+use super::{loop_field_2d, loop_field_3d, Magnet2D, Magnet3D};
 
 /// Convenience type alias for a Vec of MagnetType enum. It is more general than
 /// `MagnetVec2D` and `MagnetVec3D`, but involves more boilerplate code.
@@ -9,6 +12,9 @@ pub type MagnetVec = Vec<Magnet>;
 /// Convenience type alias for a Vec of MagnetType2D enum
 pub type MagnetVec2D = Vec<Magnet2D>;
 
+/// Convenience type alias for a Vec of MagnetType3D enum
+pub type MagnetVec3D = Vec<Magnet3D>;
+
 /// Convenience type alias for a Vec of MagnetKind enum used for serializing/deserializing.
 pub type MagnetList = Vec<MagnetKind>;
 
@@ -17,8 +23,47 @@ pub type MagnetList = Vec<MagnetKind>;
 pub enum Magnet {
     /// 2D Magnet
     Magnet2D(Magnet2D),
-    // /// 3D Magnet
-    // Magnet3D(Magnet3D),
+    /// 3D Magnet
+    Magnet3D(Magnet3D),
+}
+
+impl From<Magnet2D> for Magnet {
+    fn from(magnet: Magnet2D) -> Self {
+        Magnet::Magnet2D(magnet)
+    }
+}
+
+impl From<Magnet3D> for Magnet {
+    fn from(magnet: Magnet3D) -> Self {
+        Magnet::Magnet3D(magnet)
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Magnet {
+    /// Returns the magnetic field of a 2D or 3D magnet at a Point3 struct {x,y,z}.
+    ///
+    /// 2D magnets are infinitely long in z, so their field is evaluated at (x, y)
+    /// and has no z component.
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        match self {
+            Magnet::Magnet2D(magnet) => {
+                let field =
+                    loop_field_2d(std::slice::from_ref(magnet), &Point2::new(point.x, point.y))?;
+                Ok(Point3::new(field.x, field.y, 0.0))
+            }
+            Magnet::Magnet3D(magnet) => loop_field_3d(std::slice::from_ref(magnet), point),
+        }
+    }
+}
+
+/// Loops over a mixed list of 2D and 3D magnets and gets the field at a point `point`.
+pub fn loop_field(magnet_list: &[Magnet], point: &Point3) -> Result<Point3, MagnetError> {
+    let mut local_field = Point3::new(0.0, 0.0, 0.0);
+
+    for mag in magnet_list {
+        local_field += mag.field(point)?;
+    }
+    Ok(local_field)
 }
 
 /// Return center trait. It must implement the `center()` method
@@ -35,6 +80,54 @@ pub trait GetField<INPUT, OUTPUT> {
     fn field(&self, point: INPUT) -> OUTPUT;
 }
 
+/// Generic trait for returning the magnetic field gradient due to an input
+/// point. This must be implemented for each magnet type and each input type.
+pub trait GetGradient<INPUT, OUTPUT> {
+    /// Returns the gradient tensor of the magnetic field, $`\partial B_i / \partial x_j`$,
+    /// due to a generic input which must contain an x and y coordinate.
+    fn gradient(&self, point: INPUT) -> OUTPUT;
+}
+
+/// Generic trait for returning the out-of-plane magnetic vector potential due
+/// to an input point. This must be implemented for each magnet type and each
+/// input type.
+pub trait GetPotential<INPUT, OUTPUT> {
+    /// Returns the vector potential $`A_z`$, where $`\mathbf{B} = \nabla \times A_z \mathbf{\hat{z}}`$,
+    /// due to a generic input which must contain an x and y coordinate.
+    fn potential(&self, point: INPUT) -> OUTPUT;
+}
+
+/// Generic trait for testing whether a point lies inside a magnet. Points lying
+/// exactly on the surface of a magnet are treated as outside.
+pub trait Contains<INPUT> {
+    /// Returns true if the point lies inside the magnet
+    fn contains(&self, point: INPUT) -> bool;
+}
+
+/// Enum for selecting which magnetic field is returned.
+///
+/// The magnetisation of each magnet is stored as $`\mathbf{J} = \mu_0 \mathbf{M}`$
+/// in tesla. The magnetic flux density B is returned in tesla, and the
+/// magnetic field strength $`\mathbf{H} = \mathbf{B}/\mu_0 - \mathbf{M}`$ and
+/// magnetisation M are returned in A/m. H and B/μ0 only differ inside magnets.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    /// Magnetic flux density
+    B,
+    /// Magnetic field strength
+    H,
+    /// Magnetisation
+    M,
+}
+
+impl Default for FieldKind {
+    /// Default method for FieldKind, returning B
+    fn default() -> Self {
+        FieldKind::B
+    }
+}
+
 /// Magnet Trait for standard methods for all magnet types
 pub trait MagnetTrait<POINT, CENTER, SIZE, MAG> {
     /// Returns the magnetic field at a point
@@ -65,3 +158,29 @@ pub trait MagnetTrait<POINT, CENTER, SIZE, MAG> {
     /// This method also updates self.jx and self.jy
     fn set_magnetisation(&mut self, magnetisation: MAG);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::{Circle, Sphere};
+    use crate::utils::comparison::nearly_equal;
+
+    #[test]
+    fn test_loop_field_mixed() {
+        let circle = Circle::default();
+        let sphere = Sphere::default();
+        let magnet_list: MagnetVec = vec![
+            Magnet2D::Circle(circle).into(),
+            Magnet3D::Sphere(sphere).into(),
+        ];
+
+        let point = Point3::new(2.0, 0.5, 1.0);
+        let field = loop_field(&magnet_list, &point).unwrap();
+
+        let field_2d = circle.field(&Point2::new(2.0, 0.5)).unwrap();
+        let field_3d = sphere.field(&point).unwrap();
+        assert!(nearly_equal(field.x, field_2d.x + field_3d.x));
+        assert!(nearly_equal(field.y, field_2d.y + field_3d.y));
+        assert!(nearly_equal(field.z, field_3d.z));
+    }
+}
@@ -7,25 +7,55 @@ Copyright 2021 Peter Dunne */
 //! objects in 2D, including:
 //!
 
+mod arc_segment;
+mod array_2d;
+mod boundary_2d;
 mod circle;
+mod ellipse;
+mod energy_2d;
 mod field_loop_2d;
+mod force_2d;
+mod gradient_2d;
 mod line;
 mod line_field;
+mod linear_2d;
 mod polygon;
+mod potential_2d;
 mod rectangle;
+mod ring;
+mod wire;
 
+mod arc_segment_field;
 mod circle_field;
+mod ellipse_field;
 mod rectangle_field;
+mod ring_field;
+mod wire_field;
 
+#[cfg(test)]
+mod test_utils;
+
+pub use arc_segment::*;
+pub use array_2d::*;
+pub use boundary_2d::*;
 pub use circle::*;
+pub use ellipse::*;
+pub use energy_2d::*;
 pub use field_loop_2d::*;
+pub use force_2d::*;
+pub use gradient_2d::*;
 pub use line::*;
 pub use line_field::*;
+pub use linear_2d::*;
 pub use polygon::*;
+pub use potential_2d::*;
 pub use rectangle::*;
+pub use ring::*;
+pub use wire::*;
 
-use crate::magnets::MagnetTrait;
+use crate::magnets::{Contains, GetField, MagnetTrait};
 use crate::parse::{magnet2d_to_toml, MagnetKind};
+use crate::points::{Point2, Points, Points2};
 use crate::MagnetError;
 
 use serde_derive::{Deserialize, Serialize};
@@ -37,7 +67,7 @@ pub trait MagnetTrait2D<POINT, CENTER, SIZE, MAG>: MagnetTrait<POINT, CENTER, SI
 /// This allows us to create a Vec<MagnetType2D>, and access the fields of the magnet
 /// struct with a match routine.
 ///
-#[derive(Debug, Deserialize, Serialize, PartialEq)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum Magnet2D {
     /// 2D rectangular magnet
     Rectangle(Rectangle),
@@ -45,6 +75,14 @@ pub enum Magnet2D {
     Circle(Circle),
     /// 2D Arbitrary Polygon
     Polygon(Polygon),
+    /// Infinite current carrying wire
+    Wire(Wire),
+    /// 2D ring (annulus) magnet
+    Ring(Ring),
+    /// 2D radially magnetised arc segment
+    ArcSegment(ArcSegment),
+    /// 2D elliptical magnet
+    Ellipse(Ellipse),
 }
 
 impl Magnet2D {
@@ -52,4 +90,67 @@ impl Magnet2D {
     pub fn to_toml_struct(&self) -> Result<MagnetKind, MagnetError> {
         magnet2d_to_toml(self)
     }
+
+    /// Returns the magnetisation vector $`\mathbf{J} = \mu_0 \mathbf{M}`$ of the
+    /// magnet in tesla, in the global frame.
+    ///
+    /// This is zero for magnets without a uniform magnetisation, see `magnetisation_at`.
+    pub fn magnetisation_vector(&self) -> Point2 {
+        match self {
+            Magnet2D::Rectangle(mag) => mag.global_magnetisation(),
+            Magnet2D::Circle(mag) => {
+                let angle = mag.phi.to_radians() + mag.alpha.to_radians();
+                Point2::new(angle.cos(), angle.sin()).scale(mag.jr)
+            }
+            Magnet2D::Polygon(mag) => Point2::new(mag.jx, mag.jy),
+            // Wires carry a current, but are not magnetised
+            Magnet2D::Wire(_) => Point2::zero(),
+            Magnet2D::Ring(mag) => match mag.magnetisation {
+                RingMagnetisation::Diametric => mag.magnetisation_at(&mag.center),
+                _ => Point2::zero(),
+            },
+            Magnet2D::ArcSegment(_) => Point2::zero(),
+            Magnet2D::Ellipse(mag) => mag.global_magnetisation(),
+        }
+    }
+
+    /// Returns the magnetisation vector $`\mathbf{J} = \mu_0 \mathbf{M}`$ in
+    /// tesla at a point within the magnet, in the global frame
+    pub fn magnetisation_at(&self, point: &Point2) -> Point2 {
+        match self {
+            Magnet2D::Ring(mag) => mag.magnetisation_at(point),
+            Magnet2D::ArcSegment(mag) => mag.magnetisation_at(point),
+            _ => self.magnetisation_vector(),
+        }
+    }
+}
+
+impl GetField<&Point2, Result<Point2, MagnetError>> for Magnet2D {
+    /// Returns the magnetic field of a 2D magnet at a Point2 struct {x,y}
+    fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
+        match self {
+            Magnet2D::Rectangle(mag) => mag.field(point),
+            Magnet2D::Circle(mag) => mag.field(point),
+            Magnet2D::Polygon(mag) => mag.field(point),
+            Magnet2D::Wire(mag) => mag.field(point),
+            Magnet2D::Ring(mag) => mag.field(point),
+            Magnet2D::ArcSegment(mag) => mag.field(point),
+            Magnet2D::Ellipse(mag) => mag.field(point),
+        }
+    }
+}
+
+impl Contains<&Point2> for Magnet2D {
+    /// Returns true if a point lies inside the magnet
+    fn contains(&self, point: &Point2) -> bool {
+        match self {
+            Magnet2D::Rectangle(mag) => mag.contains(point),
+            Magnet2D::Circle(mag) => mag.contains(point),
+            Magnet2D::Polygon(mag) => mag.contains(point),
+            Magnet2D::Wire(mag) => mag.contains(point),
+            Magnet2D::Ring(mag) => mag.contains(point),
+            Magnet2D::ArcSegment(mag) => mag.contains(point),
+            Magnet2D::Ellipse(mag) => mag.contains(point),
+        }
+    }
 }
@@ -0,0 +1,258 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::arc_segment_field::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::utils::conversions::Angle;
+
+use crate::magnets::magnet2d::{Gradient2, Line};
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential};
+use crate::points::{Point2, Points, Points2};
+use crate::{MagnetError, M2_PI, PI};
+use std::fmt;
+
+/// A 2D radially magnetised arc segment, as used in the rotors of motors and
+/// magnetic couplings
+///
+/// Arguments using `new()` method:
+///     - inner_radius (f64): inner radius of the segment
+///     - outer_radius (f64): outer radius of the segment
+///     - center (Point2): center of curvature of the segment
+///     - alpha (f64): angle of the mid-line of the segment w.r.t. x-axis
+///     - span (f64): angle subtended by the segment at its center of curvature
+///     - jr (f64): remnant magnetisation in T, outwards for a positive jr
+///
+/// The default method generates a segment of radii 0.5 and 1.0, spanning 60°
+/// about the y-axis, centred at (0.0, 0.0), with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::ArcSegment;
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = ArcSegment::default();
+/// println!("Magnet 1:{}", magnet_1);
+/// let magnet_2 = ArcSegment::new(0.5, 1.0, (0.0, 0.0), Angle::Degrees(0.0), Angle::Degrees(45.0), -1.0);
+/// println!("Magnet 2:{}", magnet_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ArcSegment {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub center: Point2,
+    pub alpha: Angle,
+    pub span: Angle,
+    pub jr: f64,
+}
+
+impl Default for ArcSegment {
+    /// Default method for ArcSegment.
+    ///
+    /// Generates an arc segment of radii 0.5 and 1.0, spanning 60° about the
+    /// y-axis, centred at (0,0), with an outward magnetisation of 1 tesla
+    fn default() -> Self {
+        ArcSegment {
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            center: Point2::new(0.0, 0.0),
+            alpha: Angle::Degrees(90.0),
+            span: Angle::Degrees(60.0),
+            jr: 1.0,
+        }
+    }
+}
+
+impl ArcSegment {
+    /// Constructor for 2D ArcSegment magnet
+    pub fn new<C>(
+        inner_radius: f64,
+        outer_radius: f64,
+        center: C,
+        alpha: Angle,
+        span: Angle,
+        jr: f64,
+    ) -> ArcSegment
+    where
+        C: GetCenter<Point2>,
+    {
+        ArcSegment {
+            inner_radius,
+            outer_radius,
+            center: center.center(),
+            alpha,
+            span,
+            jr,
+        }
+    }
+
+    /// Returns the angles of the two flanks of the segment, in radians
+    pub fn flank_angles(&self) -> (f64, f64) {
+        let half_span = self.span.to_radians() / 2.0;
+        (
+            self.alpha.to_radians() - half_span,
+            self.alpha.to_radians() + half_span,
+        )
+    }
+
+    /// Returns the equivalent current sheets on the two flanks of the segment.
+    ///
+    /// The flanks are ordered as edges of a clockwise polygon, so that the
+    /// sheets match those of a Polygon with the same radial magnetisation.
+    pub fn flanks(&self) -> [Line; 2] {
+        let (start_angle, end_angle) = self.flank_angles();
+        let flank = |start: f64, end: f64, angle: f64| {
+            let radial = Point2::new(angle.cos(), angle.sin());
+            let normal = Point2::new(-radial.y, radial.x).scale((end - start).signum());
+            let mid_radius = (self.inner_radius + self.outer_radius) / 2.0;
+            // Surface current density of the radial magnetisation, as for a Line
+            let kr = self.jr * (radial.x * normal.y - radial.y * normal.x);
+            Line::new(
+                (end - start).abs(),
+                self.center + radial.scale(mid_radius),
+                Angle::Radians(normal.y.atan2(normal.x)),
+                kr,
+            )
+        };
+        [
+            flank(self.outer_radius, self.inner_radius, start_angle),
+            flank(self.inner_radius, self.outer_radius, end_angle),
+        ]
+    }
+
+    /// Returns the area of the segment
+    pub fn area(&self) -> f64 {
+        self.span.to_radians() * (self.outer_radius.powi(2) - self.inner_radius.powi(2)) / 2.0
+    }
+
+    /// Returns the magnetisation vector $`\mathbf{J}`$ in tesla at a point
+    /// within the segment, in the global frame
+    pub fn magnetisation_at(&self, point: &Point2) -> Point2 {
+        (*point - self.center).unit().scale(self.jr)
+    }
+}
+
+/// Implements Display for ArcSegment magnets.
+///
+/// Example:
+///
+impl fmt::Display for ArcSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: ({}, {})\tc: {},\talpha:{}\tspan:{}\tJ: {:.3}]",
+            self.inner_radius,
+            self.outer_radius,
+            self.center,
+            self.alpha.to_degrees(),
+            self.span.to_degrees(),
+            self.jr,
+        )
+    }
+}
+
+impl Contains<&Point2> for ArcSegment {
+    /// Returns true if a point lies inside the segment
+    fn contains(&self, point: &Point2) -> bool {
+        let local_point = *point - self.center;
+        let dist_sq = local_point.magnitude_squared();
+        if dist_sq <= self.inner_radius.powi(2) || dist_sq >= self.outer_radius.powi(2) {
+            return false;
+        }
+        // Angle from the mid-line, wrapped to [-pi, pi)
+        let angle = local_point.y.atan2(local_point.x) - self.alpha.to_radians();
+        let angle = (angle + PI).rem_euclid(M2_PI) - PI;
+        angle.abs() < self.span.to_radians() / 2.0
+    }
+}
+
+impl GetField<&Point2, Result<Point2, MagnetError>> for ArcSegment {
+    /// Returns the magnetic field of an arc segment at a Point2 struct {x,y}
+    fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
+        get_field_arc_segment(self, point)
+    }
+}
+
+impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for ArcSegment {
+    /// Returns the magnetic field of an arc segment at a 2-element tuple (x,y)
+    fn field(&self, point: &(f64, f64)) -> Result<(f64, f64), MagnetError> {
+        let field_vec = get_field_arc_segment(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok((field_vec.x, field_vec.y))
+    }
+}
+
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for ArcSegment {
+    /// Returns the field gradient of an arc segment at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_arc_segment(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for ArcSegment {
+    /// Returns the field gradient of an arc segment at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_arc_segment(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for ArcSegment {
+    /// Returns the vector potential of an arc segment at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_arc_segment(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for ArcSegment {
+    /// Returns the vector potential of an arc segment at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_arc_segment(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arc_segment_contains() {
+        let magnet = ArcSegment::new(
+            0.5,
+            1.0,
+            (0.2, 0.1),
+            Angle::Degrees(170.0),
+            Angle::Degrees(40.0),
+            1.0,
+        );
+        let point = |r: f64, angle: f64| {
+            magnet.center + Point2::new(angle.to_radians().cos(), angle.to_radians().sin()).scale(r)
+        };
+        // The segment straddles the negative x-axis
+        assert!(magnet.contains(&point(0.75, 170.0)));
+        assert!(magnet.contains(&point(0.75, -171.0)));
+        assert!(!magnet.contains(&point(0.75, -169.0)));
+        assert!(!magnet.contains(&point(0.4, 170.0)));
+        assert!(!magnet.contains(&point(1.1, 170.0)));
+        assert!((magnet.area() - PI * 0.75 / 9.0).abs() < 1e-12);
+    }
+}
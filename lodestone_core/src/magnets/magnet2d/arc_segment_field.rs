@@ -0,0 +1,161 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Fields of radially magnetised arc segments
+//!
+//! In the charge model, a segment magnetised as $`\mathbf{J} = J \mathbf{\hat{r}}`$
+//! carries surface charges $`\sigma = \mathbf{J} \cdot \mathbf{\hat{n}}`$ of
+//! $`+J`$ and $`-J`$ on its outer and inner curved faces, and a volume charge
+//! $`-\nabla \cdot \mathbf{J} = -J / r`$. As the magnetisation is parallel to
+//! the two straight flanks, their surface charge vanishes.
+//!
+//! The radial magnetisation is also curl free, so the equivalent currents are
+//! confined to the surfaces, $`K_z = (\mathbf{J} \times \mathbf{\hat{n}})_z`$.
+//! These vanish on the curved faces and are $`\pm J`$ on the flanks, so
+//! $`\mathbf{B} = \mu_0 \mathbf{H} + \mathbf{J}`$ is exactly the field of two
+//! current sheets, which is used for the field, its gradient, and the vector
+//! potential. The charges are used for the forces on the segment.
+
+use crate::magnets::magnet2d::{ArcSegment, Gradient2};
+use crate::magnets::{GetField, GetGradient, GetPotential};
+use crate::points::{Point2, Points2};
+use crate::MagnetError;
+
+/// Returns the magnetic field vector due to an arc segment at a point `point`
+pub fn get_field_arc_segment(magnet: &ArcSegment, point: &Point2) -> Result<Point2, MagnetError> {
+    let mut field = Point2::zero();
+    for line in &magnet.flanks() {
+        field += line.field(point)?;
+    }
+    Ok(field)
+}
+
+/// Returns the field gradient tensor due to an arc segment at a point `point`
+pub fn get_gradient_arc_segment(
+    magnet: &ArcSegment,
+    point: &Point2,
+) -> Result<Gradient2, MagnetError> {
+    let mut gradient = Gradient2::zero();
+    for line in &magnet.flanks() {
+        gradient += line.gradient(point)?;
+    }
+    Ok(gradient)
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to an arc segment at
+/// a point `point`
+pub fn get_potential_arc_segment(magnet: &ArcSegment, point: &Point2) -> Result<f64, MagnetError> {
+    let mut potential = 0.0;
+    for line in &magnet.flanks() {
+        potential += line.potential(point)?;
+    }
+    Ok(potential)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::test_utils::{assert_derivatives, polygon_from_ring};
+    use crate::magnets::{charge_elements_2d, loop_field_2d, surface_elements_2d, Magnet2D};
+    use crate::points::Points;
+    use crate::utils::conversions::Angle;
+    use crate::I_2PI;
+
+    fn segment() -> ArcSegment {
+        ArcSegment::new(
+            0.5,
+            1.0,
+            (0.2, -0.1),
+            Angle::Degrees(150.0),
+            Angle::Degrees(50.0),
+            1.2,
+        )
+    }
+
+    #[test]
+    fn test_segment_matches_polygons() {
+        // Approximates the segment by thin trapezoids, each uniformly
+        // magnetised as at its center
+        let magnet = segment();
+        let (start_angle, end_angle) = magnet.flank_angles();
+        let count = 500;
+        let d_theta = (end_angle - start_angle) / count as f64;
+        let vertex =
+            |r: f64, angle: f64| magnet.center + Point2::new(angle.cos(), angle.sin()).scale(r);
+        let segments: Vec<Magnet2D> = (0..count)
+            .map(|i| {
+                let theta = start_angle + (i as f64 + 0.5) * d_theta;
+                let vertices = [
+                    vertex(magnet.inner_radius, theta - d_theta / 2.0),
+                    vertex(magnet.inner_radius, theta + d_theta / 2.0),
+                    vertex(magnet.outer_radius, theta + d_theta / 2.0),
+                    vertex(magnet.outer_radius, theta - d_theta / 2.0),
+                ];
+                Magnet2D::Polygon(polygon_from_ring(
+                    &vertices,
+                    vertex(0.75, theta),
+                    magnet.jr,
+                    theta,
+                ))
+            })
+            .collect();
+
+        for point in &[
+            Point2::new(1.5, 0.5),
+            Point2::new(-0.4, 0.3),
+            magnet.center + Point2::new(-0.6, 0.4),
+        ] {
+            let field = get_field_arc_segment(&magnet, point).unwrap();
+            let comp_field = loop_field_2d(&segments, point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-3 * magnet.jr);
+        }
+    }
+
+    #[test]
+    fn test_field_of_charges() {
+        // Outside the magnet, B = mu0 H is the field of the magnetic charges
+        let magnet = segment();
+        let magnet_2d = Magnet2D::ArcSegment(magnet);
+        let mut charges: Vec<(Point2, f64)> = surface_elements_2d(&magnet_2d)
+            .iter()
+            .map(|element| (element.position, element.sigma() * element.length))
+            .collect();
+        charges.extend(
+            charge_elements_2d(&magnet_2d)
+                .iter()
+                .map(|element| (element.position, element.charge)),
+        );
+
+        for point in &[Point2::new(1.5, 0.5), Point2::new(-1.0, -1.2)] {
+            let mut comp_field = Point2::zero();
+            for (position, charge) in &charges {
+                let delta = *point - *position;
+                comp_field += delta.scale(charge * I_2PI / delta.magnitude_squared());
+            }
+            let field = get_field_arc_segment(&magnet, point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-6 * field.magnitude());
+        }
+
+        // The total charge of the segment is zero
+        let total: f64 = charges.iter().map(|(_, charge)| charge).sum();
+        assert!(total.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_segment_derivatives() {
+        let magnet = segment();
+        for point in &[
+            Point2::new(1.5, 0.5),
+            magnet.center + Point2::new(-0.6, 0.4),
+        ] {
+            assert_derivatives(
+                |p| get_field_arc_segment(&magnet, p),
+                |p| get_gradient_arc_segment(&magnet, p),
+                |p| get_potential_arc_segment(&magnet, p),
+                point,
+            );
+        }
+    }
+}
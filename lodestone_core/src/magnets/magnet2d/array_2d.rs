@@ -0,0 +1,400 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Patterned arrays of 2D magnets
+//!
+//! Helper functions for generating linear and circular Halbach arrays, and
+//! arrays of alternating poles, as a `MagnetVec2D`.
+//!
+//! In a linear Halbach array with $`k`$ segments per wavelength, the
+//! magnetisation of segment $`i`$ is at an angle $`\phi_i = \pi / 2 \pm 2 \pi i / k`$
+//! to the axis of the array. In a circular Halbach cylinder with $`p`$ pole
+//! pairs, the magnetisation of a segment at angular position $`\theta`$ is at
+//! $`\phi = (1 \pm p) \theta`$, where the upper sign concentrates the flux in
+//! the bore and the lower sign outside the cylinder.
+
+#![allow(clippy::too_many_arguments)]
+use crate::magnets::magnet2d::{Polygon, Rectangle, Vertices};
+use crate::magnets::{GetCenter, Magnet2D, MagnetVec2D};
+use crate::points::{Point2, PointVec2, Points};
+use crate::utils::conversions::Angle;
+use crate::{MagnetError, M2_PI, PI_2};
+use serde_derive::{Deserialize, Serialize};
+
+/// Enum for the sense in which the magnetisation rotates from one segment to
+/// the next
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationSense {
+    /// Magnetisation rotates anti-clockwise along the array
+    AntiClockwise,
+    /// Magnetisation rotates clockwise along the array
+    Clockwise,
+}
+
+impl RotationSense {
+    /// Returns +1.0 for anti-clockwise, and -1.0 for clockwise rotation
+    pub fn sign(&self) -> f64 {
+        match self {
+            RotationSense::AntiClockwise => 1.0,
+            RotationSense::Clockwise => -1.0,
+        }
+    }
+}
+
+/// Enum for the shape of the segments of a circular Halbach cylinder
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum SegmentShape {
+    /// Trapezoidal polygons filling the annulus
+    Polygon,
+    /// Rectangles centred on the mean radius
+    Rectangle,
+}
+
+/// Returns `count` rectangles spaced by `pitch` along an axis through `center`
+/// at an angle `alpha` to the x-axis, with magnetisation angles `phi(i)`
+/// relative to that axis
+fn linear_array<F>(
+    center: &Point2,
+    alpha: &Angle,
+    count: usize,
+    pitch: f64,
+    size: [f64; 2],
+    jr: f64,
+    phi: F,
+) -> MagnetVec2D
+where
+    F: Fn(usize) -> f64,
+{
+    let alpha = alpha.to_radians();
+    let axis = Point2::new(alpha.cos(), alpha.sin());
+    let offset = (count as f64 - 1.0) / 2.0;
+
+    (0..count)
+        .map(|i| {
+            let angle = alpha + phi(i);
+            Magnet2D::Rectangle(Rectangle::from_global_magnetisation(
+                size[0],
+                size[1],
+                *center + axis.scale((i as f64 - offset) * pitch),
+                Angle::Radians(-alpha),
+                &Point2::new(angle.cos(), angle.sin()).scale(jr),
+            ))
+        })
+        .collect()
+}
+
+/// Returns a linear Halbach array of `count` rectangular segments of `size`
+/// [width, height], spaced by `pitch` along an axis through `center` at an
+/// angle `alpha` to the x-axis, with `segments` segments per wavelength.
+///
+/// The first segment is magnetised normal to the axis, and the magnetisation
+/// of each subsequent segment is rotated by $`2 \pi / k`$ in the sense `sense`.
+/// An anti-clockwise rotation gives the strong side of the array above the
+/// axis, i.e. on its anti-clockwise normal, and a clockwise rotation below it.
+pub fn linear_halbach_2d<C>(
+    center: C,
+    alpha: Angle,
+    count: usize,
+    segments: usize,
+    pitch: f64,
+    size: [f64; 2],
+    jr: f64,
+    sense: RotationSense,
+) -> MagnetVec2D
+where
+    C: GetCenter<Point2>,
+{
+    let step = sense.sign() * M2_PI / segments.max(1) as f64;
+    linear_array(&center.center(), &alpha, count, pitch, size, jr, |i| {
+        PI_2 + step * i as f64
+    })
+}
+
+/// Returns an array of `count` rectangular segments of `size` [width, height],
+/// spaced by `pitch` along an axis through `center` at an angle `alpha` to the
+/// x-axis, magnetised alternately normal to and against the normal to the axis.
+///
+/// The first segment is magnetised along the anti-clockwise normal for
+/// `RotationSense::AntiClockwise`, and along the clockwise normal otherwise.
+pub fn alternating_array_2d<C>(
+    center: C,
+    alpha: Angle,
+    count: usize,
+    pitch: f64,
+    size: [f64; 2],
+    jr: f64,
+    sense: RotationSense,
+) -> MagnetVec2D
+where
+    C: GetCenter<Point2>,
+{
+    linear_array(&center.center(), &alpha, count, pitch, size, jr, |i| {
+        sense.sign() * PI_2 * if i % 2 == 0 { 1.0 } else { -1.0 }
+    })
+}
+
+/// Returns a circular Halbach cylinder of `count` segments about `center`,
+/// with `pole_pairs` pole pairs.
+///
+/// The segments are centred on a circle of `radius`, and have a `size` of
+/// [radial thickness, tangential width]. Polygon segments span the full
+/// angular pitch between the inner and outer radii, so only use the thickness.
+///
+/// An anti-clockwise rotation of the magnetisation concentrates the flux in
+/// the bore, and a clockwise rotation outside the cylinder.
+///
+/// Returns a MagnetError if the polygon segments are degenerate, e.g. for a
+/// single segment, or a thickness exceeding the diameter.
+pub fn circular_halbach_2d<C>(
+    center: C,
+    count: usize,
+    pole_pairs: usize,
+    radius: f64,
+    size: [f64; 2],
+    jr: f64,
+    sense: RotationSense,
+    shape: SegmentShape,
+) -> Result<MagnetVec2D, MagnetError>
+where
+    C: GetCenter<Point2>,
+{
+    let center = center.center();
+    let d_theta = M2_PI / count.max(1) as f64;
+    let rotation = 1.0 + sense.sign() * pole_pairs as f64;
+
+    (0..count)
+        .map(|i| {
+            let theta = i as f64 * d_theta;
+            let phi = rotation * theta;
+            let radial = Point2::new(theta.cos(), theta.sin());
+
+            Ok(match shape {
+                SegmentShape::Rectangle => {
+                    Magnet2D::Rectangle(Rectangle::from_global_magnetisation(
+                        size[0],
+                        size[1],
+                        center + radial.scale(radius),
+                        Angle::Radians(-theta),
+                        &Point2::new(phi.cos(), phi.sin()).scale(jr),
+                    ))
+                }
+                SegmentShape::Polygon => {
+                    let inner = radius - size[0] / 2.0;
+                    let outer = radius + size[0] / 2.0;
+                    let vertex = |r: f64, angle: f64| {
+                        center + Point2::new(angle.cos(), angle.sin()).scale(r)
+                    };
+
+                    // Vertices are listed clockwise
+                    let start = theta - d_theta / 2.0;
+                    let end = theta + d_theta / 2.0;
+                    let (x, y): (Vec<f64>, Vec<f64>) = [
+                        vertex(inner, start),
+                        vertex(inner, end),
+                        vertex(outer, end),
+                        vertex(outer, start),
+                    ]
+                    .iter()
+                    .map(|vertex| vertex.as_tuple())
+                    .unzip();
+
+                    Magnet2D::Polygon(Polygon::new(
+                        center + radial.scale(radius),
+                        Angle::Radians(0.0),
+                        jr,
+                        Angle::Radians(phi),
+                        Vertices::Custom(PointVec2::new(x, y)),
+                    )?)
+                }
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::loop_field_2d;
+    use crate::points::Points2;
+    use crate::PI;
+
+    #[test]
+    fn test_linear_halbach_one_sided() {
+        for &(sense, strong) in &[
+            (RotationSense::AntiClockwise, 1.0),
+            (RotationSense::Clockwise, -1.0),
+        ] {
+            let magnet_list = linear_halbach_2d(
+                (0.0, 0.0),
+                Angle::Degrees(0.0),
+                16,
+                4,
+                1.0,
+                [1.0, 1.0],
+                1.0,
+                sense,
+            );
+            let strong_field = loop_field_2d(&magnet_list, &Point2::new(0.0, strong)).unwrap();
+            let weak_field = loop_field_2d(&magnet_list, &Point2::new(0.0, -strong)).unwrap();
+            assert!(strong_field.magnitude() > 10.0 * weak_field.magnitude());
+        }
+    }
+
+    #[test]
+    fn test_rotated_linear_arrays() {
+        // Rotating an array rotates its field
+        let alpha = Angle::Degrees(30.0);
+        let point = Point2::new(0.3, -1.2);
+        let rotated_point = point.rotate(&alpha.to_radians());
+        for &sense in &[RotationSense::AntiClockwise, RotationSense::Clockwise] {
+            let arrays = [
+                (
+                    linear_halbach_2d(
+                        (0.0, 0.0),
+                        Angle::Degrees(0.0),
+                        6,
+                        3,
+                        0.5,
+                        [0.5, 0.2],
+                        1.0,
+                        sense,
+                    ),
+                    linear_halbach_2d((0.0, 0.0), alpha, 6, 3, 0.5, [0.5, 0.2], 1.0, sense),
+                ),
+                (
+                    alternating_array_2d(
+                        (0.0, 0.0),
+                        Angle::Degrees(0.0),
+                        5,
+                        0.6,
+                        [0.4, 0.2],
+                        1.0,
+                        sense,
+                    ),
+                    alternating_array_2d((0.0, 0.0), alpha, 5, 0.6, [0.4, 0.2], 1.0, sense),
+                ),
+            ];
+            for (array, rotated_array) in &arrays {
+                let field = loop_field_2d(array, &point).unwrap();
+                let rotated_field = loop_field_2d(rotated_array, &rotated_point).unwrap();
+                let comp_field = field.rotate(&alpha.to_radians());
+                assert!((rotated_field - comp_field).magnitude() < 1e-10 * field.magnitude());
+            }
+        }
+    }
+
+    #[test]
+    fn test_alternating_array() {
+        let magnet_list = alternating_array_2d(
+            (1.0, 0.0),
+            Angle::Degrees(0.0),
+            4,
+            1.0,
+            [1.0, 1.0],
+            1.0,
+            RotationSense::Clockwise,
+        );
+        assert_eq!(magnet_list.len(), 4);
+        assert_eq!(magnet_list[0].magnetisation_vector().y, -1.0);
+        assert_eq!(magnet_list[1].magnetisation_vector().y, 1.0);
+        match &magnet_list[3] {
+            Magnet2D::Rectangle(mag) => assert_eq!(mag.center, Point2::new(2.5, 0.0)),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_circular_halbach_bore_field() {
+        // An ideal dipolar Halbach cylinder has a uniform bore field
+        // B = Jr ln(ro / ri), reduced by sin(2 pi / n) / (2 pi / n) when segmented
+        let count = 16;
+        let magnet_list = circular_halbach_2d(
+            (0.0, 0.0),
+            count,
+            1,
+            1.5,
+            [1.0, 0.0],
+            1.0,
+            RotationSense::AntiClockwise,
+            SegmentShape::Polygon,
+        )
+        .unwrap();
+        let field = loop_field_2d(&magnet_list, &Point2::zero()).unwrap();
+        let d_theta = 2.0 * PI / count as f64;
+        let comp_field = 2.0_f64.ln() * d_theta.sin() / d_theta;
+        assert!((field.magnitude() - comp_field).abs() < 0.02 * comp_field);
+
+        let offset_field = loop_field_2d(&magnet_list, &Point2::new(0.2, 0.1)).unwrap();
+        assert!((offset_field - field).magnitude() < 0.01 * field.magnitude());
+
+        // Reversing the sense moves the flux outside of the cylinder
+        let magnet_list = circular_halbach_2d(
+            (0.0, 0.0),
+            count,
+            1,
+            1.5,
+            [1.0, 0.0],
+            1.0,
+            RotationSense::Clockwise,
+            SegmentShape::Polygon,
+        )
+        .unwrap();
+        let field = loop_field_2d(&magnet_list, &Point2::zero()).unwrap();
+        assert!(field.magnitude() < 1e-10);
+    }
+
+    #[test]
+    fn test_circular_halbach_rectangles() {
+        // Square segments placed by rotation match the same squares as polygons
+        let rectangles = circular_halbach_2d(
+            (0.5, -0.5),
+            8,
+            2,
+            2.0,
+            [0.5, 0.5],
+            1.0,
+            RotationSense::AntiClockwise,
+            SegmentShape::Rectangle,
+        )
+        .unwrap();
+        let point = Point2::new(0.7, 0.2);
+        let field = loop_field_2d(&rectangles, &point).unwrap();
+
+        let mut comp_field = Point2::zero();
+        for mag in &rectangles {
+            if let Magnet2D::Rectangle(rect) = mag {
+                let corners: Vec<Point2> = [(-1.0, -1.0), (-1.0, 1.0), (1.0, 1.0), (1.0, -1.0)]
+                    .iter()
+                    .map(|(sx, sy)| {
+                        rect.center
+                            + Point2::new(sx * rect.a, sy * rect.b)
+                                .rotate(&(-rect.alpha.to_radians()))
+                    })
+                    .collect();
+                let polygon = Magnet2D::Polygon(
+                    Polygon::new(
+                        rect.center,
+                        Angle::Radians(0.0),
+                        rect.jr,
+                        Angle::Radians(
+                            mag.magnetisation_vector()
+                                .y
+                                .atan2(mag.magnetisation_vector().x),
+                        ),
+                        Vertices::Custom(PointVec2::new(
+                            corners.iter().map(|c| c.x).collect(),
+                            corners.iter().map(|c| c.y).collect(),
+                        )),
+                    )
+                    .unwrap(),
+                );
+                comp_field += loop_field_2d(&[polygon], &point).unwrap();
+            }
+        }
+        assert!((field - comp_field).magnitude() < 1e-10 * field.magnitude());
+    }
+}
@@ -0,0 +1,368 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Planar boundaries by the method of images
+//!
+//! A magnet next to an infinite plane of soft iron or superconductor has the
+//! same field, on its side of the plane, as the magnet together with its
+//! mirror image. Reflecting a magnet reflects its equivalent surface currents
+//! $`K = (\mathbf{J} \times \mathbf{\hat{n}})_z`$ with a change of sign, so:
+//!
+//! - An infinitely permeable soft iron plane, where the tangential H vanishes,
+//!   needs image currents of the same sign, $`\mathbf{J}' = -\mathrm{R}\mathbf{J}`$
+//! - A perfectly diamagnetic (superconducting) plane, where the normal B
+//!   vanishes, needs image currents of the opposite sign, $`\mathbf{J}' = \mathrm{R}\mathbf{J}`$
+//!
+//! where R is the reflection in the plane. Likewise the image of a wire
+//! carries the same current in soft iron, and the opposite current in a
+//! superconductor. Only a single plane is supported.
+
+use crate::magnets::magnet2d::{
+    ArcSegment, Circle, Ellipse, Polygon, Rectangle, Ring, RingMagnetisation, Vertices, Wire,
+};
+use crate::magnets::{loop_field_2d, GetCenter, Magnet2D};
+use crate::points::{Point2, PointVec2, Points, Points2};
+use crate::utils::conversions::Angle;
+use crate::{MagnetError, ERR_CUTOFF, PI};
+use serde_derive::{Deserialize, Serialize};
+
+/// Enum for the material of a planar boundary
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BoundaryKind {
+    /// Infinitely permeable soft iron
+    SoftIron,
+    /// Perfectly diamagnetic superconductor
+    Superconductor,
+}
+
+/// An infinite planar boundary along the line through `center` at an angle
+/// `alpha` to the x-axis.
+///
+/// The boundary material fills the half-plane to the right of the line, i.e.
+/// for `alpha = 0` the region y < 0, and the magnets should lie on the left.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct PlaneBoundary {
+    /// A point on the surface of the boundary
+    pub center: Point2,
+    /// Angle between the surface and the x-axis
+    pub alpha: Angle,
+    /// Boundary material
+    pub kind: BoundaryKind,
+}
+
+impl Default for PlaneBoundary {
+    /// Default method for PlaneBoundary.
+    ///
+    /// Generates a soft iron plane filling the region y < 0
+    fn default() -> Self {
+        PlaneBoundary {
+            center: Point2::zero(),
+            alpha: Angle::Degrees(0.0),
+            kind: BoundaryKind::SoftIron,
+        }
+    }
+}
+
+impl PlaneBoundary {
+    /// Generates a new PlaneBoundary
+    pub fn new<C>(center: C, alpha: Angle, kind: BoundaryKind) -> PlaneBoundary
+    where
+        C: GetCenter<Point2>,
+    {
+        PlaneBoundary {
+            center: center.center(),
+            alpha,
+            kind,
+        }
+    }
+
+    /// Returns the unit normal pointing out of the boundary, into free space
+    pub fn normal(&self) -> Point2 {
+        let (sin, cos) = self.alpha.to_radians().sin_cos();
+        Point2::new(-sin, cos)
+    }
+
+    /// Returns true if a point lies on the free space side of the boundary, or
+    /// on its surface
+    pub fn is_free_space(&self, point: &Point2) -> bool {
+        (*point - self.center).dot(&self.normal()) > -ERR_CUTOFF
+    }
+
+    /// Returns the mirror image of a point in the plane
+    pub fn reflect_point(&self, point: &Point2) -> Point2 {
+        let normal = self.normal();
+        let local_point = *point - self.center;
+        *point - normal.scale(2.0 * local_point.dot(&normal))
+    }
+
+    /// Returns the angle of the magnetisation of an image, given the angle
+    /// `phi` of the original magnetisation
+    fn image_angle(&self, phi: f64) -> f64 {
+        let reflected = 2.0 * self.alpha.to_radians() - phi;
+        match self.kind {
+            BoundaryKind::SoftIron => reflected + PI,
+            BoundaryKind::Superconductor => reflected,
+        }
+    }
+
+    /// Returns the magnetisation vector of an image, given the magnetisation
+    /// vector `magnetisation` of the original
+    fn image_magnetisation(&self, magnetisation: &Point2) -> Point2 {
+        let phi = self.image_angle(magnetisation.y.atan2(magnetisation.x));
+        Point2::new(phi.cos(), phi.sin()).scale(magnetisation.magnitude())
+    }
+
+    /// Returns the image of a magnet in the plane
+    pub fn image(&self, magnet: &Magnet2D) -> Result<Magnet2D, MagnetError> {
+        let theta = self.alpha.to_radians();
+
+        Ok(match magnet {
+            Magnet2D::Rectangle(mag) => Magnet2D::Rectangle(Rectangle::from_global_magnetisation(
+                mag.width,
+                mag.height,
+                self.reflect_point(&mag.center),
+                Angle::Radians(-2.0 * theta - mag.alpha.to_radians()),
+                &self.image_magnetisation(&mag.global_magnetisation()),
+            )),
+            Magnet2D::Circle(mag) => {
+                // The field of the circle is that of a magnetisation at phi + alpha
+                let alpha = -mag.alpha.to_radians();
+                let phi = self.image_angle(mag.phi.to_radians() + mag.alpha.to_radians()) - alpha;
+                Magnet2D::Circle(Circle::new(
+                    mag.radius,
+                    self.reflect_point(&mag.center),
+                    Angle::Radians(alpha),
+                    mag.jr,
+                    Angle::Radians(phi),
+                ))
+            }
+            Magnet2D::Polygon(mag) => {
+                // Reflection reverses the winding order, so the vertices are
+                // also reversed to keep the edge normals pointing outwards
+                let reflect = |vertices: &PointVec2| {
+                    let (x, y): (Vec<f64>, Vec<f64>) = vertices
+                        .x
+                        .iter()
+                        .zip(vertices.y.iter())
+                        .rev()
+                        .map(|(x, y)| self.reflect_point(&Point2::new(*x, *y)).as_tuple())
+                        .unzip();
+                    PointVec2::new(x, y)
+                };
+
+                // alpha is only used to generate regular vertices
+                Magnet2D::Polygon(Polygon::with_holes(
+                    self.reflect_point(&mag.center),
+                    mag.alpha,
+                    mag.jr,
+                    Angle::Radians(self.image_angle(mag.phi.to_radians())),
+                    Vertices::Custom(reflect(&mag.vertices)),
+                    mag.holes.iter().map(reflect).collect(),
+                )?)
+            }
+            Magnet2D::Wire(mag) => Magnet2D::Wire(Wire::new(
+                self.reflect_point(&mag.center),
+                Angle::Radians(-2.0 * theta - mag.alpha.to_radians()),
+                match self.kind {
+                    BoundaryKind::SoftIron => mag.current,
+                    BoundaryKind::Superconductor => -mag.current,
+                },
+                mag.section,
+            )),
+            Magnet2D::Ring(mag) => {
+                // Reflection preserves radial magnetisation, and reverses the
+                // sense of azimuthal magnetisation
+                let alpha = -mag.alpha.to_radians();
+                let (jr, phi) = match (mag.magnetisation, self.kind) {
+                    (RingMagnetisation::Diametric, _) => (
+                        mag.jr,
+                        self.image_angle(mag.phi.to_radians() + mag.alpha.to_radians()) - alpha,
+                    ),
+                    (RingMagnetisation::Radial, BoundaryKind::SoftIron)
+                    | (RingMagnetisation::Azimuthal, BoundaryKind::Superconductor) => {
+                        (-mag.jr, mag.phi.to_radians())
+                    }
+                    _ => (mag.jr, mag.phi.to_radians()),
+                };
+                Magnet2D::Ring(Ring::new(
+                    mag.inner_radius,
+                    mag.outer_radius,
+                    self.reflect_point(&mag.center),
+                    Angle::Radians(alpha),
+                    jr,
+                    Angle::Radians(phi),
+                    mag.magnetisation,
+                ))
+            }
+            Magnet2D::Ellipse(mag) => Magnet2D::Ellipse(Ellipse::from_global_magnetisation(
+                mag.a,
+                mag.b,
+                self.reflect_point(&mag.center),
+                Angle::Radians(-2.0 * theta - mag.alpha.to_radians()),
+                &self.image_magnetisation(&mag.global_magnetisation()),
+            )),
+            Magnet2D::ArcSegment(mag) => {
+                // The segment is symmetric about its mid-line, which is
+                // reflected like a direction
+                Magnet2D::ArcSegment(ArcSegment::new(
+                    mag.inner_radius,
+                    mag.outer_radius,
+                    self.reflect_point(&mag.center),
+                    Angle::Radians(2.0 * theta - mag.alpha.to_radians()),
+                    mag.span,
+                    match self.kind {
+                        BoundaryKind::SoftIron => -mag.jr,
+                        BoundaryKind::Superconductor => mag.jr,
+                    },
+                ))
+            }
+        })
+    }
+}
+
+/// Returns the images of every magnet in the list
+pub fn image_magnets_2d(
+    magnet_list: &[Magnet2D],
+    boundary: &PlaneBoundary,
+) -> Result<Vec<Magnet2D>, MagnetError> {
+    magnet_list.iter().map(|mag| boundary.image(mag)).collect()
+}
+
+/// Loops over every 2D Magnet and its image in a planar boundary, and gets the
+/// field at a point `point`.
+///
+/// The field inside the boundary material is not modelled, and is returned as zero.
+pub fn loop_field_boundary_2d(
+    magnet_list: &[Magnet2D],
+    boundary: &PlaneBoundary,
+    point: &Point2,
+) -> Result<Point2, MagnetError> {
+    if !boundary.is_free_space(point) {
+        return Ok(Point2::zero());
+    }
+
+    let images = image_magnets_2d(magnet_list, boundary)?;
+    Ok(loop_field_2d(magnet_list, point)? + loop_field_2d(&images, point)?)
+}
+
+/// Returns the magnetic field due to an array of magnets `magnet_list` next to
+/// a planar boundary, at a point `point`
+pub fn get_field_boundary_2d(
+    magnet_list: &[Magnet2D],
+    boundary: &PlaneBoundary,
+    point: (&f64, &f64),
+) -> Result<(f64, f64), MagnetError> {
+    let point = Point2::new(*point.0, *point.1);
+    Ok(loop_field_boundary_2d(magnet_list, boundary, &point)?.as_tuple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::{PolyDimension, WireSection};
+
+    /// Magnets above a plane through (0.5, -0.5) at 30 degrees
+    fn magnet_list() -> Vec<Magnet2D> {
+        vec![
+            Magnet2D::Rectangle(Rectangle::new(
+                1.0,
+                0.5,
+                (0.0, 1.0),
+                Angle::Degrees(10.0),
+                1.0,
+                Angle::Degrees(70.0),
+            )),
+            Magnet2D::Circle(Circle::new(
+                0.3,
+                (-1.0, 0.5),
+                Angle::Degrees(15.0),
+                1.0,
+                Angle::Degrees(-40.0),
+            )),
+            Magnet2D::Polygon(
+                Polygon::new(
+                    (1.5, 1.5),
+                    Angle::Degrees(0.0),
+                    1.0,
+                    Angle::Degrees(200.0),
+                    Vertices::Regular(5, PolyDimension::Side(0.5)),
+                )
+                .unwrap(),
+            ),
+            Magnet2D::Wire(Wire::new(
+                (-0.2, 2.0),
+                Angle::Degrees(40.0),
+                1e5,
+                WireSection::Rectangle(0.2, 0.1),
+            )),
+            Magnet2D::Ring(Ring::new(
+                0.2,
+                0.4,
+                (-1.5, 1.8),
+                Angle::Degrees(10.0),
+                1.0,
+                Angle::Degrees(120.0),
+                RingMagnetisation::Diametric,
+            )),
+            Magnet2D::ArcSegment(ArcSegment::new(
+                0.2,
+                0.5,
+                (0.6, 2.6),
+                Angle::Degrees(-100.0),
+                Angle::Degrees(70.0),
+                1.0,
+            )),
+            Magnet2D::Ellipse(Ellipse::new(
+                0.4,
+                0.15,
+                (2.0, 3.0),
+                Angle::Degrees(60.0),
+                1.0,
+                Angle::Degrees(-30.0),
+            )),
+        ]
+    }
+
+    fn boundary(kind: BoundaryKind) -> PlaneBoundary {
+        PlaneBoundary::new((0.5, -0.5), Angle::Degrees(30.0), kind)
+    }
+
+    #[test]
+    fn test_soft_iron_boundary() {
+        // The field is normal to the surface of soft iron
+        let magnet_list = magnet_list();
+        let boundary = boundary(BoundaryKind::SoftIron);
+        let normal = boundary.normal();
+        let tangent = Point2::new(normal.y, -normal.x);
+        for &s in &[-2.0, -0.5, 0.3, 1.7] {
+            let point = boundary.center + tangent.scale(s);
+            let field = loop_field_boundary_2d(&magnet_list, &boundary, &point).unwrap();
+            assert!(field.dot(&tangent).abs() < 1e-10 * field.magnitude());
+        }
+    }
+
+    #[test]
+    fn test_superconducting_boundary() {
+        // The field is tangential to the surface of a superconductor
+        let magnet_list = magnet_list();
+        let boundary = boundary(BoundaryKind::Superconductor);
+        let normal = boundary.normal();
+        for &s in &[-2.0, -0.5, 0.3, 1.7] {
+            let point = boundary.center + Point2::new(normal.y, -normal.x).scale(s);
+            let field = loop_field_boundary_2d(&magnet_list, &boundary, &point).unwrap();
+            assert!(field.dot(&normal).abs() < 1e-10 * field.magnitude());
+        }
+    }
+
+    #[test]
+    fn test_field_inside_boundary() {
+        let magnet_list = magnet_list();
+        let boundary = boundary(BoundaryKind::SoftIron);
+        let point = boundary.center - boundary.normal();
+        let field = loop_field_boundary_2d(&magnet_list, &boundary, &point).unwrap();
+        assert_eq!(field, Point2::zero());
+    }
+}
@@ -8,8 +8,9 @@ use serde_derive::{Deserialize, Serialize};
 
 use crate::utils::conversions::Angle;
 
-use crate::magnets::{GetCenter, GetField, MagnetTrait};
-use crate::points::{Point2, PolarPoint};
+use crate::magnets::magnet2d::Gradient2;
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential, MagnetTrait};
+use crate::points::{Point2, Points2, PolarPoint};
 use crate::MagnetError;
 use std::fmt;
 
@@ -147,6 +148,13 @@ impl MagnetTrait<[f64; 2], Point2, f64, PolarPoint> for Circle {
     }
 }
 
+impl Contains<&Point2> for Circle {
+    /// Returns true if a point lies inside the circle
+    fn contains(&self, point: &Point2) -> bool {
+        (*point - self.center).magnitude_squared() < self.radius.powi(2)
+    }
+}
+
 impl GetField<&Point2, Result<Point2, MagnetError>> for Circle {
     /// Returns the magnetic field of a circular magnet at a Point2 struct {x,y}
     fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
@@ -168,6 +176,48 @@ impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Circle {
     }
 }
 
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Circle {
+    /// Returns the field gradient of a circular magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_circle(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Circle {
+    /// Returns the field gradient of a circular magnet at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_circle(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Circle {
+    /// Returns the vector potential of a circular magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_circle(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Circle {
+    /// Returns the vector potential of a circular magnet at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_circle(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::comparison::nearly_equal;
@@ -7,8 +7,8 @@ Copyright 2021 Peter Dunne */
 //! Contains magnet field routines for calculating the magnetic field in 2D
 //! for this structure
 //!
-use crate::magnets::magnet2d::Circle;
-use crate::points::{Point2, Points2, PolarPoint, PolarPoints};
+use crate::magnets::magnet2d::{Circle, Gradient2};
+use crate::points::{Point2, Points, Points2, PolarPoint, PolarPoints};
 use crate::utils::conversions::vector_pol2cart;
 use crate::{MagnetError, FP_CUTOFF, M2_PI};
 
@@ -27,9 +27,18 @@ pub fn get_polar_field_circle(
     Ok(field)
 }
 
+/// Calculates the 2D magnetic field of an infinitely long bipolar rod (circle).
+///
+/// Inside the rod the field is uniform, $`\mathbf{B} = \mathbf{J}/2`$.
 pub fn get_field_circle(magnet: &Circle, point: &Point2) -> Result<Point2, MagnetError> {
     let polar_val = (*point - magnet.center).to_polar();
 
+    if polar_val.rho < magnet.radius {
+        // Rotating the field of the rod by -alpha rotates its magnetisation by alpha
+        let angle = magnet.phi.to_radians() + magnet.alpha.to_radians();
+        return Ok(Point2::new(angle.cos(), angle.sin()).scale(magnet.jr / 2.0));
+    }
+
     let local_polar = polar_val - PolarPoint::new(0.0, magnet.phi.to_radians());
 
     let polar_field = get_polar_field_circle(magnet, &local_polar)?;
@@ -43,13 +52,70 @@ pub fn get_field_circle(magnet: &Circle, point: &Point2) -> Result<Point2, Magne
     Ok(field)
 }
 
+/// Calculates the field gradient tensor of an infinitely long bipolar rod (circle).
+///
+/// Outside the rod $`B_x - i B_y = K / z^2`$, with $`K = J R^2 e^{i\phi} / 2`$
+/// and $`z = x + iy`$ relative to its center. This is analytic, so the gradient
+/// follows from $`\partial_x (B_x - i B_y) = -2K/z^3`$. The gradient inside
+/// the rod is zero.
+pub fn get_gradient_circle(magnet: &Circle, point: &Point2) -> Result<Gradient2, MagnetError> {
+    let polar_val = (*point - magnet.center).to_polar();
+
+    // The field inside the rod is uniform
+    if polar_val.rho < magnet.radius {
+        return Ok(Gradient2::zero());
+    }
+
+    // -2K / z^3 = -(J R^2 / rho^3) exp(i(phi - 3 theta))
+    let prefac = -magnet.jr * magnet.radius.powi(2) / polar_val.rho.powi(3);
+    let angle = magnet.phi.to_radians() - 3.0 * polar_val.phi;
+    let d_x = prefac * angle.cos();
+    let d_y = -prefac * angle.sin();
+
+    let mut gradient = Gradient2::new(d_x, d_y, d_y, -d_x);
+
+    if magnet.alpha.to_radians().abs() > FP_CUTOFF {
+        let reverse_angle = M2_PI - magnet.alpha.to_radians();
+        gradient = gradient.rotate_field(&reverse_angle);
+    }
+    Ok(gradient.bind_singular())
+}
+
+/// Calculates the out-of-plane vector potential $`A_z`$ of an infinitely long
+/// bipolar rod (circle).
+///
+/// Outside the rod this is the potential of a 2D dipole,
+/// $`A_z = \frac{J R^2}{2 \rho^2} (\hat{m}_x y - \hat{m}_y x)`$, and inside
+/// the rod, where the field is uniform and equal to $`\mathbf{J}/2`$,
+/// $`A_z = \frac{J}{2} (\hat{m}_x y - \hat{m}_y x)`$.
+pub fn get_potential_circle(magnet: &Circle, point: &Point2) -> Result<f64, MagnetError> {
+    let local_point = *point - magnet.center;
+
+    // Rotating the field of the rod by -alpha rotates its magnetisation by alpha
+    let angle = magnet.phi.to_radians() + magnet.alpha.to_radians();
+    let (sin, cos) = angle.sin_cos();
+    let moment = cos * local_point.y - sin * local_point.x;
+
+    let rho_sq = local_point.magnitude_squared();
+    let radius_sq = magnet.radius.powi(2);
+    let prefac = if rho_sq < radius_sq {
+        magnet.jr / 2.0
+    } else {
+        magnet.jr * radius_sq / rho_sq / 2.0
+    };
+
+    Ok(prefac * moment)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{get_field_circle, get_polar_field_circle, Circle};
-    use crate::points::{Point2, PolarPoint};
+    use super::{
+        get_field_circle, get_gradient_circle, get_polar_field_circle, get_potential_circle, Circle,
+    };
+    use crate::points::{Point2, Points, PolarPoint};
     use crate::utils::comparison::nearly_equal;
     use crate::utils::conversions::Angle;
-    use crate::{PI_2, PI_4};
+    use crate::{PI_2, PI_4, PI_6};
 
     #[test]
     fn surface_field_x() {
@@ -148,4 +214,99 @@ mod tests {
         assert!(nearly_equal(field.x, comp_field.x));
         assert!(nearly_equal(field.y, comp_field.y));
     }
+
+    #[test]
+    fn gradient_matches_field() {
+        let magnet = Circle::new(
+            0.5,
+            (0.2, -0.3),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(110.0),
+        );
+        let point = Point2::new(-0.4, 0.6);
+        let gradient = get_gradient_circle(&magnet, &point).unwrap();
+
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        let field_dx = (get_field_circle(&magnet, &(point + d_x)).unwrap()
+            - get_field_circle(&magnet, &(point - d_x)).unwrap())
+        .scale(0.5 / delta);
+        let field_dy = (get_field_circle(&magnet, &(point + d_y)).unwrap()
+            - get_field_circle(&magnet, &(point - d_y)).unwrap())
+        .scale(0.5 / delta);
+
+        assert!((gradient.xx - field_dx.x).abs() < 1e-8);
+        assert!((gradient.yx - field_dx.y).abs() < 1e-8);
+        assert!((gradient.xy - field_dy.x).abs() < 1e-8);
+        assert!((gradient.yy - field_dy.y).abs() < 1e-8);
+    }
+
+    #[test]
+    fn potential_curl_matches_field() {
+        let magnet = Circle::new(
+            0.5,
+            (0.2, -0.3),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(110.0),
+        );
+        let point = Point2::new(-0.4, 0.6);
+        let field = get_field_circle(&magnet, &point).unwrap();
+
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        let potential_dx = (get_potential_circle(&magnet, &(point + d_x)).unwrap()
+            - get_potential_circle(&magnet, &(point - d_x)).unwrap())
+            / (2.0 * delta);
+        let potential_dy = (get_potential_circle(&magnet, &(point + d_y)).unwrap()
+            - get_potential_circle(&magnet, &(point - d_y)).unwrap())
+            / (2.0 * delta);
+
+        assert!((field.x - potential_dy).abs() < 1e-8);
+        assert!((field.y + potential_dx).abs() < 1e-8);
+    }
+
+    #[test]
+    fn potential_is_continuous_at_surface() {
+        let magnet = Circle::new(
+            0.5,
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(45.0),
+        );
+        let inside = get_potential_circle(&magnet, &Point2::new(0.0, 0.5 - 1e-9)).unwrap();
+        let outside = get_potential_circle(&magnet, &Point2::new(0.0, 0.5 + 1e-9)).unwrap();
+        assert!((inside - outside).abs() < 1e-8);
+    }
+
+    #[test]
+    fn interior_field_is_uniform() {
+        let magnet = Circle::new(
+            0.5,
+            (0.2, -0.3),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+        );
+        let field = get_field_circle(&magnet, &Point2::new(0.3, -0.1)).unwrap();
+        let comp_field = Point2::new(PI_6.cos(), PI_6.sin()).scale(0.5);
+        assert!(nearly_equal(field.x, comp_field.x));
+        assert!(nearly_equal(field.y, comp_field.y));
+
+        // Normal component is continuous across the surface
+        let magnet = Circle::new(
+            0.5,
+            (0.2, -0.3),
+            Angle::Degrees(20.0),
+            1.0,
+            Angle::Degrees(30.0),
+        );
+        let inside = get_field_circle(&magnet, &Point2::new(0.7 - 1e-9, -0.3)).unwrap();
+        let outside = get_field_circle(&magnet, &Point2::new(0.7 + 1e-9, -0.3)).unwrap();
+        assert!((inside.x - outside.x).abs() < 1e-8);
+    }
 }
@@ -0,0 +1,275 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::ellipse_field::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::utils::conversions::Angle;
+
+use crate::magnets::magnet2d::Gradient2;
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential};
+use crate::points::{Point2, Points2};
+use crate::{MagnetError, PI};
+use std::fmt;
+
+/// A 2D elliptical magnet, i.e. an infinitely long elliptical cylinder
+///
+/// Arguments using `new()` method:
+///     - a (f64): semi-axis along the local x-axis
+///     - b (f64): semi-axis along the local y-axis
+///     - center (Point2): magnet center
+///     - alpha (f64): clockwise rotation of the ellipse w.r.t. x-axis, as for
+///       a Rectangle
+///     - jr (f64): remnant magnetisation in T
+///     - phi (f64): angle of magnetisation w.r.t. the local x-axis
+///
+/// The default method generates an ellipse of semi-axes 1.0 and 0.5, centred at
+/// (0.0, 0.0), magnetised in y with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::Ellipse;
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = Ellipse::default();
+/// println!("Magnet 1:{}", magnet_1);
+/// let magnet_2 = Ellipse::new(1.0, 0.25, (0.0, -1.0), Angle::Degrees(30.0), 1.0, Angle::Degrees(0.0));
+/// println!("Magnet 2:{}", magnet_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Ellipse {
+    pub a: f64,
+    pub b: f64,
+    pub center: Point2,
+    pub alpha: Angle,
+    pub jr: f64,
+    pub phi: Angle,
+    pub jx: f64,
+    pub jy: f64,
+}
+
+impl Default for Ellipse {
+    /// Default method for Ellipse.
+    ///
+    /// Generates an elliptical magnet of semi-axes 1.0 and 0.5, centred at (0,0),
+    /// with a magnetisation of 1 tesla in y
+    fn default() -> Self {
+        Ellipse {
+            a: 1.0,
+            b: 0.5,
+            center: Point2::new(0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            jr: 1.0,
+            phi: Angle::Degrees(90.0),
+            jx: 0.0,
+            jy: 1.0,
+        }
+    }
+}
+
+impl Ellipse {
+    /// Constructor for 2D Ellipse magnet
+    pub fn new<C>(a: f64, b: f64, center: C, alpha: Angle, jr: f64, phi: Angle) -> Ellipse
+    where
+        C: GetCenter<Point2>,
+    {
+        let phi_rad = phi.to_radians();
+        Ellipse {
+            a,
+            b,
+            center: center.center(),
+            alpha,
+            jr,
+            phi,
+            jx: jr * phi_rad.cos(),
+            jy: jr * phi_rad.sin(),
+        }
+    }
+
+    /// Constructor for an Ellipse rotated by `alpha`, with magnetisation
+    /// vector `magnetisation` in the global frame
+    pub fn from_global_magnetisation<C>(
+        a: f64,
+        b: f64,
+        center: C,
+        alpha: Angle,
+        magnetisation: &Point2,
+    ) -> Ellipse
+    where
+        C: GetCenter<Point2>,
+    {
+        // As for a Rectangle, the ellipse is rotated by -alpha
+        let local = magnetisation.rotate(&alpha.to_radians());
+        Ellipse::new(
+            a,
+            b,
+            center,
+            alpha,
+            local.magnitude(),
+            Angle::Radians(local.y.atan2(local.x)),
+        )
+    }
+
+    /// Returns the magnetisation vector $`\mathbf{J}`$ in the global frame,
+    /// rotated back from the local frame by -alpha
+    pub fn global_magnetisation(&self) -> Point2 {
+        Point2::new(self.jx, self.jy).rotate(&(-self.alpha.to_radians()))
+    }
+
+    /// Returns the demagnetising factors $`(N_x, N_y)`$ along the local axes,
+    /// which sum to 1
+    pub fn demagnetising_factors(&self) -> (f64, f64) {
+        (self.b / (self.a + self.b), self.a / (self.a + self.b))
+    }
+
+    /// Returns the area of the ellipse
+    pub fn area(&self) -> f64 {
+        PI * self.a * self.b
+    }
+
+    /// Translates and rotates a point into the local frame of the ellipse
+    pub(crate) fn local_point(&self, point: &Point2) -> Point2 {
+        (*point - self.center).rotate(&self.alpha.to_radians())
+    }
+
+    /// Returns the point on the boundary of the ellipse at the parametric
+    /// angle `t`, in the global frame
+    pub fn boundary_point(&self, t: f64) -> Point2 {
+        self.center
+            + Point2::new(self.a * t.cos(), self.b * t.sin()).rotate(&(-self.alpha.to_radians()))
+    }
+}
+
+/// Implements Display for Ellipse magnets.
+///
+/// Example:
+///
+impl fmt::Display for Ellipse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[a: {},\tb: {},\tc: {},\talpha:{}\tJ ({:.3}, {:.3})]",
+            self.a,
+            self.b,
+            self.center,
+            self.alpha.to_degrees(),
+            self.jr,
+            self.phi.to_degrees()
+        )
+    }
+}
+
+impl Contains<&Point2> for Ellipse {
+    /// Returns true if a point lies inside the ellipse
+    fn contains(&self, point: &Point2) -> bool {
+        let local_point = self.local_point(point);
+        (local_point.x / self.a).powi(2) + (local_point.y / self.b).powi(2) < 1.0
+    }
+}
+
+impl GetField<&Point2, Result<Point2, MagnetError>> for Ellipse {
+    /// Returns the magnetic field of an elliptical magnet at a Point2 struct {x,y}
+    fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
+        get_field_ellipse(self, point)
+    }
+}
+
+impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Ellipse {
+    /// Returns the magnetic field of an elliptical magnet at a 2-element tuple (x,y)
+    fn field(&self, point: &(f64, f64)) -> Result<(f64, f64), MagnetError> {
+        let field_vec = get_field_ellipse(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok((field_vec.x, field_vec.y))
+    }
+}
+
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Ellipse {
+    /// Returns the field gradient of an elliptical magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_ellipse(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Ellipse {
+    /// Returns the field gradient of an elliptical magnet at a 2-element tuple
+    /// (x,y), as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_ellipse(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Ellipse {
+    /// Returns the vector potential of an elliptical magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_ellipse(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Ellipse {
+    /// Returns the vector potential of an elliptical magnet at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_ellipse(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::{Magnet2D, Rectangle};
+    use crate::points::Points;
+
+    #[test]
+    fn test_ellipse_contains() {
+        let magnet = Ellipse::new(
+            1.0,
+            0.25,
+            (0.5, 0.5),
+            Angle::Degrees(90.0),
+            1.0,
+            Angle::Degrees(0.0),
+        );
+        // The major axis lies along y
+        assert!(magnet.contains(&Point2::new(0.5, 1.4)));
+        assert!(magnet.contains(&Point2::new(0.7, 0.5)));
+        assert!(!magnet.contains(&Point2::new(1.4, 0.5)));
+        let boundary = magnet.boundary_point(0.3) - magnet.center;
+        assert!(!magnet.contains(&(magnet.center + boundary.scale(1.01))));
+        assert!(magnet.contains(&(magnet.center + boundary.scale(0.99))));
+        assert_eq!(magnet.demagnetising_factors(), (0.2, 0.8));
+    }
+
+    #[test]
+    fn test_rotation_matches_rectangle() {
+        // alpha rotates the ellipse clockwise, as for a Rectangle
+        let alpha = Angle::Degrees(30.0);
+        let ellipse = Ellipse::new(1.0, 0.1, (0.0, 0.0), alpha, 1.0, Angle::Degrees(0.0));
+        let rectangle = Rectangle::new(2.0, 0.2, (0.0, 0.0), alpha, 1.0, Angle::Degrees(0.0));
+        let along = Point2::new(0.9, 0.0).rotate(&(-PI / 6.0));
+        let across = Point2::new(0.9, 0.0).rotate(&(PI / 6.0));
+        assert!(ellipse.contains(&along) && rectangle.contains(&along));
+        assert!(!ellipse.contains(&across) && !rectangle.contains(&across));
+
+        let magnetisation = Magnet2D::Ellipse(ellipse).magnetisation_vector();
+        let comp_magnetisation = Magnet2D::Rectangle(rectangle).magnetisation_vector();
+        assert!((magnetisation - comp_magnetisation).magnitude() < 1e-12);
+    }
+}
@@ -0,0 +1,264 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Fields of elliptical magnets
+//!
+//! A uniformly magnetised elliptical cylinder with semi-axes $`a`$ and $`b`$
+//! has a uniform internal field, with demagnetising factors
+//! $`N_x = b / (a + b)`$ and $`N_y = a / (a + b)`$ along its axes,
+//!
+//! ```math
+//! \mathbf{B} = \left( \frac{a J_x}{a + b}, \frac{b J_y}{a + b} \right)
+//! ```
+//!
+//! Outside, the field is written using complex coordinates $`z = x + iy`$ in
+//! the local frame of the ellipse, and $`J = J_x + i J_y`$, as
+//!
+//! ```math
+//! B_x - i B_y = \frac{ab J}{w (z + w)}, \qquad w = \sqrt{z^2 - c^2}
+//! ```
+//!
+//! where $`c^2 = a^2 - b^2`$ locates the foci, and the branch cut of $`w`$ joins
+//! the foci so that $`w \to z`$ far from the magnet. The vector potential outside
+//! is $`A_z = -\mathrm{Im} \left[ abJ / (z + w) \right]`$, which is continuous
+//! with the linear potential inside.
+
+use crate::magnets::magnet2d::{Ellipse, Gradient2};
+use crate::points::{Point2, Points, Points2};
+use crate::MagnetError;
+
+/// Returns the product of two complex numbers stored as (re, im)
+fn complex_mul(z_1: &Point2, z_2: &Point2) -> Point2 {
+    Point2::new(z_1.x * z_2.x - z_1.y * z_2.y, z_1.x * z_2.y + z_1.y * z_2.x)
+}
+
+/// Returns the quotient of two complex numbers stored as (re, im)
+fn complex_div(z_1: &Point2, z_2: &Point2) -> Point2 {
+    let denom = z_2.magnitude_squared();
+    Point2::new(
+        (z_1.x * z_2.x + z_1.y * z_2.y) / denom,
+        (z_1.y * z_2.x - z_1.x * z_2.y) / denom,
+    )
+}
+
+/// Returns the principal square root of a complex number stored as (re, im)
+fn complex_sqrt(z: &Point2) -> Point2 {
+    let modulus = z.magnitude();
+    Point2::new(
+        ((modulus + z.x) / 2.0).sqrt(),
+        z.y.signum() * ((modulus - z.x) / 2.0).sqrt(),
+    )
+}
+
+/// Returns $`w = \sqrt{z^2 - c^2}`$ at a local point outside the ellipse,
+/// with the branch cut between the foci
+fn focal_root(magnet: &Ellipse, z: &Point2) -> Point2 {
+    let focus = (magnet.a.powi(2) - magnet.b.powi(2)).abs().sqrt();
+    let root = |z: &Point2| {
+        complex_mul(
+            &complex_sqrt(&(*z - Point2::new(focus, 0.0))),
+            &complex_sqrt(&(*z + Point2::new(focus, 0.0))),
+        )
+    };
+    if magnet.a >= magnet.b {
+        root(z)
+    } else {
+        // The foci lie on the y-axis, so rotate them onto the x-axis, z' = -iz,
+        // and w = i sqrt(z'^2 - c^2)
+        let root = root(&Point2::new(z.y, -z.x));
+        Point2::new(-root.y, root.x)
+    }
+}
+
+/// Returns true if a local point lies inside the ellipse
+fn inside(magnet: &Ellipse, local_point: &Point2) -> bool {
+    (local_point.x / magnet.a).powi(2) + (local_point.y / magnet.b).powi(2) < 1.0
+}
+
+/// Returns the magnetic field vector due to an elliptical magnet at a point `point`
+pub fn get_field_ellipse(magnet: &Ellipse, point: &Point2) -> Result<Point2, MagnetError> {
+    let local_point = magnet.local_point(point);
+    let sum_axes = magnet.a + magnet.b;
+
+    let field = if inside(magnet, &local_point) {
+        Point2::new(
+            magnet.jx * magnet.a / sum_axes,
+            magnet.jy * magnet.b / sum_axes,
+        )
+    } else {
+        let w = focal_root(magnet, &local_point);
+        let numerator = Point2::new(magnet.jx, magnet.jy).scale(magnet.a * magnet.b);
+        let conj_field = complex_div(&numerator, &complex_mul(&w, &(local_point + w)));
+        Point2::new(conj_field.x, -conj_field.y)
+    };
+
+    // Rotate back from the local frame by -alpha
+    Ok(field.rotate(&(-magnet.alpha.to_radians())))
+}
+
+/// Returns the field gradient tensor due to an elliptical magnet at a point `point`
+pub fn get_gradient_ellipse(magnet: &Ellipse, point: &Point2) -> Result<Gradient2, MagnetError> {
+    let local_point = magnet.local_point(point);
+
+    // The internal field is uniform
+    if inside(magnet, &local_point) {
+        return Ok(Gradient2::zero());
+    }
+
+    // d/dz (B_x - i B_y) = -abJ / w^3
+    let w = focal_root(magnet, &local_point);
+    let numerator = Point2::new(magnet.jx, magnet.jy).scale(-magnet.a * magnet.b);
+    let derivative = complex_div(&numerator, &complex_mul(&w, &complex_mul(&w, &w)));
+    let gradient = Gradient2::new(derivative.x, -derivative.y, -derivative.y, -derivative.x);
+
+    Ok(gradient.rotate(&(-magnet.alpha.to_radians())))
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to an elliptical
+/// magnet at a point `point`
+pub fn get_potential_ellipse(magnet: &Ellipse, point: &Point2) -> Result<f64, MagnetError> {
+    let local_point = magnet.local_point(point);
+    let sum_axes = magnet.a + magnet.b;
+
+    if inside(magnet, &local_point) {
+        // B = (dA/dy, -dA/dx) for the uniform internal field
+        let field_x = magnet.jx * magnet.a / sum_axes;
+        let field_y = magnet.jy * magnet.b / sum_axes;
+        Ok(field_x * local_point.y - field_y * local_point.x)
+    } else {
+        let w = focal_root(magnet, &local_point);
+        let numerator = Point2::new(magnet.jx, magnet.jy).scale(magnet.a * magnet.b);
+        Ok(-complex_div(&numerator, &(local_point + w)).y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::get_field_circle;
+    use crate::magnets::magnet2d::test_utils::{assert_derivatives, polygon_from_ring};
+    use crate::magnets::{Circle, Polygon};
+    use crate::magnets::{GetField, GetGradient, GetPotential};
+    use crate::points::Points;
+    use crate::utils::conversions::Angle;
+    use crate::M2_PI;
+
+    fn ellipses() -> Vec<Ellipse> {
+        vec![
+            Ellipse::new(
+                1.0,
+                0.4,
+                (0.2, -0.1),
+                Angle::Degrees(25.0),
+                1.2,
+                Angle::Degrees(70.0),
+            ),
+            Ellipse::new(
+                0.3,
+                0.8,
+                (-0.1, 0.3),
+                Angle::Degrees(-40.0),
+                1.0,
+                Angle::Degrees(20.0),
+            ),
+        ]
+    }
+
+    /// Inscribes a polygon with `count` vertices, in clockwise order
+    fn polygon(magnet: &Ellipse, count: usize) -> Polygon {
+        let vertices: Vec<Point2> = (0..count)
+            .map(|i| magnet.boundary_point(-M2_PI * i as f64 / count as f64))
+            .collect();
+        let magnetisation = magnet.global_magnetisation();
+        polygon_from_ring(
+            &vertices,
+            magnet.center,
+            magnet.jr,
+            magnetisation.y.atan2(magnetisation.x),
+        )
+    }
+
+    #[test]
+    fn test_ellipse_matches_polygon() {
+        // Polygons with many vertices converge on the exact solution, both for
+        // the uniform internal field and for the external field
+        for magnet in &ellipses() {
+            let polygon = polygon(magnet, 2000);
+            for &(t, scale) in &[(0.3, 0.5), (2.0, 0.0), (1.0, 1.5), (4.0, 2.5)] {
+                let point = magnet.center + (magnet.boundary_point(t) - magnet.center).scale(scale);
+                let field = get_field_ellipse(magnet, &point).unwrap();
+                let comp_field = polygon.field(&point).unwrap();
+                assert!((field - comp_field).magnitude() < 1e-4 * magnet.jr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_circle_limit() {
+        let ellipse = Ellipse::new(
+            0.5,
+            0.5,
+            (0.1, 0.2),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(20.0),
+        );
+        let magnetisation = ellipse.global_magnetisation();
+        let circle = Circle::new(
+            0.5,
+            (0.1, 0.2),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Radians(magnetisation.y.atan2(magnetisation.x)),
+        );
+        for point in &[
+            Point2::new(0.2, 0.1),
+            Point2::new(1.0, -0.4),
+            Point2::new(-0.8, 0.9),
+        ] {
+            let field = get_field_ellipse(&ellipse, point).unwrap();
+            let comp_field = get_field_circle(&circle, point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_normal_field_continuous() {
+        // The normal component of B is continuous across the surface
+        for magnet in &ellipses() {
+            for &t in &[0.1, 1.3, 2.9, 4.4] {
+                let point = magnet.boundary_point(t);
+                let normal = Point2::new(magnet.b * t.cos(), magnet.a * t.sin())
+                    .rotate(&(-magnet.alpha.to_radians()))
+                    .unit();
+                let field_out = magnet.field(&(point + normal.scale(1e-9))).unwrap();
+                let field_in = magnet.field(&(point - normal.scale(1e-9))).unwrap();
+                assert!((field_out.dot(&normal) - field_in.dot(&normal)).abs() < 1e-6);
+
+                let potential_out = magnet.potential(&(point + normal.scale(1e-9))).unwrap();
+                let potential_in = magnet.potential(&(point - normal.scale(1e-9))).unwrap();
+                assert!((potential_out - potential_in).abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ellipse_derivatives() {
+        for magnet in &ellipses() {
+            for point in &[
+                Point2::new(1.5, 0.7),
+                Point2::new(-0.2, -1.3),
+                magnet.center,
+            ] {
+                assert_derivatives(
+                    |p| magnet.field(p),
+                    |p| magnet.gradient(p),
+                    |p| magnet.potential(p),
+                    point,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Magnetostatic energy of 2D magnets
+//!
+//! The interaction energy per unit length of a uniformly magnetised magnet
+//! $`i`$ in the field of magnet $`j`$ is
+//! $`E_{ij} = -\frac{1}{\mu_0} \int \mathbf{J}_i \cdot \mathbf{B}_j \, dA`$.
+//! Writing $`\mathbf{B}_j`$ in terms of its vector potential and integrating
+//! by parts turns this into a boundary integral over the surface currents
+//! $`K_i = (\mathbf{J}_i \times \mathbf{\hat{n}})_z`$,
+//!
+//! ```math
+//! E_{ij} = -\frac{1}{\mu_0} \oint A_{z,j} K_i \, dl
+//! ```
+//!
+//! The self-energy of a magnet is its demagnetisation energy,
+//! $`E_{ii} = -\frac{\mu_0}{2} \int \mathbf{M} \cdot \mathbf{H}_d \, dA`$,
+//! which follows from the same boundary integral as
+//!
+//! ```math
+//! E_{ii} = \frac{1}{2 \mu_0} \left( J^2 A - \oint A_{z,i} K_i \, dl \right)
+//! ```
+//!
+//! where $`A`$ is the area of the magnet. Radially and azimuthally magnetised
+//! rings neither exert nor feel a field outside of themselves, and have self
+//! energies of $`J^2 A / 2 \mu_0`$ and zero respectively. The radial
+//! magnetisation of an arc segment is curl free, so the same boundary integral
+//! gives its self energy, with $`J^2 A`$ replaced by $`\int J^2 \, dA`$.
+//!
+//! A current carrying wire instead
+//! interacts through $`E_{ij} = -\int j_i A_{z,j} \, dA`$, while its self
+//! energy depends on the return path of the current, outside of the 2D model,
+//! and is taken as zero.
+//!
+//! The integrals are evaluated with the same quadrature points as the forces.
+//! With lengths in m, magnetisations in T and currents in A, energies are
+//! returned in J/m.
+
+use crate::magnets::magnet2d::RingMagnetisation;
+use crate::magnets::{current_elements_2d, loop_potential_2d, surface_elements_2d, Magnet2D};
+use crate::points::Points2;
+use crate::{MagnetError, MU0, PI};
+use serde_derive::{Deserialize, Serialize};
+
+/// Stored magnetostatic energy of a list of 2D magnets
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Energy2D {
+    /// Self (demagnetisation) energy of each magnet
+    pub self_energy: Vec<f64>,
+    /// Symmetric matrix of pairwise interaction energies, with zeros on the diagonal
+    pub interaction: Vec<Vec<f64>>,
+    /// Sum of the self energies and of each pairwise interaction energy
+    pub total: f64,
+}
+
+/// Returns the boundary integral $`\oint A_z K \, dl`$ over the surface of
+/// `target`, or $`\mu_0 \int A_z j \, dA`$ over the cross-section of a wire,
+/// for the potential due to `source`
+fn potential_integral(target: &Magnet2D, source: &Magnet2D) -> Result<f64, MagnetError> {
+    let source = std::slice::from_ref(source);
+    let mut integral = 0.0;
+    for element in surface_elements_2d(target) {
+        let potential = loop_potential_2d(source, &element.position)?;
+        integral += potential * element.current() * element.length;
+    }
+    for element in current_elements_2d(target) {
+        let potential = loop_potential_2d(source, &element.position)?;
+        integral += MU0 * potential * element.current;
+    }
+    Ok(integral)
+}
+
+/// Returns the area of a 2D magnet
+pub(crate) fn magnet_area(magnet: &Magnet2D) -> f64 {
+    match magnet {
+        Magnet2D::Rectangle(mag) => 4.0 * mag.a * mag.b,
+        Magnet2D::Circle(mag) => PI * mag.radius.powi(2),
+        Magnet2D::Polygon(mag) => mag.area(),
+        Magnet2D::Wire(wire) => wire.area(),
+        Magnet2D::Ring(ring) => ring.area(),
+        Magnet2D::ArcSegment(segment) => segment.area(),
+        Magnet2D::Ellipse(ellipse) => ellipse.area(),
+    }
+}
+
+/// Returns the interaction energy per unit length between two magnets
+pub fn interaction_energy_2d(magnet_1: &Magnet2D, magnet_2: &Magnet2D) -> Result<f64, MagnetError> {
+    Ok(-potential_integral(magnet_2, magnet_1)? / MU0)
+}
+
+/// Returns the self (demagnetisation) energy per unit length of a magnet
+pub fn self_energy_2d(magnet: &Magnet2D) -> Result<f64, MagnetError> {
+    match magnet {
+        Magnet2D::Wire(_) => return Ok(0.0),
+        Magnet2D::Ring(ring) => match ring.magnetisation {
+            // H = -J / mu0 throughout a radially magnetised ring, and vanishes
+            // in an azimuthally magnetised ring
+            RingMagnetisation::Radial => return Ok(ring.jr.powi(2) * ring.area() / (2.0 * MU0)),
+            RingMagnetisation::Azimuthal => return Ok(0.0),
+            RingMagnetisation::Diametric => {}
+        },
+        _ => {}
+    }
+    let magnetisation_sq = match magnet {
+        Magnet2D::ArcSegment(segment) => segment.jr.powi(2),
+        _ => magnet.magnetisation_vector().magnitude_squared(),
+    };
+    let energy = magnetisation_sq * magnet_area(magnet) - potential_integral(magnet, magnet)?;
+    Ok(energy / (2.0 * MU0))
+}
+
+/// Returns the self energies, pairwise interaction energies, and total energy
+/// per unit length of a list of magnets
+pub fn get_energy_2d(magnet_list: &[Magnet2D]) -> Result<Energy2D, MagnetError> {
+    let num_magnets = magnet_list.len();
+
+    let mut self_energy = Vec::with_capacity(num_magnets);
+    let mut interaction = vec![vec![0.0; num_magnets]; num_magnets];
+    let mut total = 0.0;
+
+    for (i, magnet_i) in magnet_list.iter().enumerate() {
+        let energy = self_energy_2d(magnet_i)?;
+        self_energy.push(energy);
+        total += energy;
+
+        for (j, magnet_j) in magnet_list.iter().enumerate().skip(i + 1) {
+            let energy = interaction_energy_2d(magnet_i, magnet_j)?;
+            interaction[i][j] = energy;
+            interaction[j][i] = energy;
+            total += energy;
+        }
+    }
+
+    Ok(Energy2D {
+        self_energy,
+        interaction,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::{PolyDimension, Vertices};
+    use crate::magnets::{get_force_2d, Circle, Ellipse, Polygon, Rectangle, Wire, WireSection};
+    use crate::utils::conversions::Angle;
+
+    fn rectangle(center: (f64, f64), phi: f64) -> Magnet2D {
+        Magnet2D::Rectangle(Rectangle::new(
+            1.0,
+            0.5,
+            center,
+            Angle::Degrees(15.0),
+            1.0,
+            Angle::Degrees(phi),
+        ))
+    }
+
+    #[test]
+    fn test_dipole_interaction() {
+        // Coaxial circles interact as 2D dipoles, E = -mu0 m1 m2 / (2 pi d^2)
+        let circle = |center| {
+            Magnet2D::Circle(Circle::new(
+                0.5,
+                center,
+                Angle::Degrees(0.0),
+                1.0,
+                Angle::Degrees(0.0),
+            ))
+        };
+        let energy = interaction_energy_2d(&circle((0.0, 0.0)), &circle((2.0, 0.0))).unwrap();
+        let m = PI * 0.25 / MU0;
+        let comp_energy = -MU0 * m * m / (PI * 2.0 * 4.0);
+        assert!(((energy - comp_energy) / comp_energy).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_self_energy() {
+        // Circles and squares both have a demagnetising factor of 1/2
+        let circle = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 0.0),
+            Angle::Degrees(10.0),
+            1.2,
+            Angle::Degrees(30.0),
+        ));
+        let comp_energy = 1.44 * PI * 0.25 / (4.0 * MU0);
+        let energy = self_energy_2d(&circle).unwrap();
+        assert!(((energy - comp_energy) / comp_energy).abs() < 1e-8);
+
+        let square = Magnet2D::Polygon(
+            Polygon::new(
+                (0.3, 0.1),
+                Angle::Degrees(20.0),
+                1.0,
+                Angle::Degrees(70.0),
+                Vertices::Regular(4, PolyDimension::Side(1.0)),
+            )
+            .unwrap(),
+        );
+        let comp_energy = 1.0 / (4.0 * MU0);
+        let energy = self_energy_2d(&square).unwrap();
+        assert!(((energy - comp_energy) / comp_energy).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_ellipse_self_energy() {
+        // E = (Nx Jx^2 + Ny Jy^2) A / (2 mu0), with the exact demagnetising factors
+        let ellipse = Ellipse::new(
+            1.0,
+            0.3,
+            (0.2, -0.4),
+            Angle::Degrees(35.0),
+            1.1,
+            Angle::Degrees(60.0),
+        );
+        let (n_x, n_y) = ellipse.demagnetising_factors();
+        let comp_energy =
+            (n_x * ellipse.jx.powi(2) + n_y * ellipse.jy.powi(2)) * ellipse.area() / (2.0 * MU0);
+        let energy = self_energy_2d(&Magnet2D::Ellipse(ellipse)).unwrap();
+        assert!(((energy - comp_energy) / comp_energy).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_energy_gradient_matches_force() {
+        let delta = 1e-5;
+        let energy = |x: f64| {
+            interaction_energy_2d(&rectangle((0.0, 0.0), 90.0), &rectangle((x, 0.8), 60.0)).unwrap()
+        };
+        let force_x = -(energy(1.2 + delta) - energy(1.2 - delta)) / (2.0 * delta);
+
+        let magnet_list = vec![rectangle((0.0, 0.0), 90.0), rectangle((1.2, 0.8), 60.0)];
+        let force = get_force_2d(&magnet_list, 1).unwrap();
+        assert!(((force.x - force_x) / force.x).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_wire_energy() {
+        // Parallel filaments, E = mu0 I1 I2 ln(d) / (2 pi)
+        let wire =
+            |center, section| Magnet2D::Wire(Wire::new(center, Angle::Degrees(0.0), 1e4, section));
+        let energy = interaction_energy_2d(
+            &wire((0.0, 0.0), WireSection::Filament),
+            &wire((3.0, 0.0), WireSection::Filament),
+        )
+        .unwrap();
+        let comp_energy = MU0 * 1e8 * 3.0_f64.ln() / (2.0 * PI);
+        assert!(((energy - comp_energy) / comp_energy).abs() < 1e-12);
+        assert_eq!(
+            self_energy_2d(&wire((0.0, 0.0), WireSection::Filament)).unwrap(),
+            0.0
+        );
+
+        // Energies between wires and magnets are reciprocal
+        let magnet = rectangle((0.0, 0.0), 60.0);
+        let wire = wire((0.4, 1.2), WireSection::Rectangle(0.3, 0.2));
+        let energy = interaction_energy_2d(&magnet, &wire).unwrap();
+        let reverse = interaction_energy_2d(&wire, &magnet).unwrap();
+        assert!((reverse - energy).abs() < 1e-5 * energy.abs());
+    }
+
+    #[test]
+    fn test_get_energy_2d() {
+        let magnet_list = vec![
+            rectangle((0.0, 0.0), 90.0),
+            rectangle((1.2, 0.8), 60.0),
+            rectangle((-0.5, 1.5), 0.0),
+        ];
+        let energy = get_energy_2d(&magnet_list).unwrap();
+
+        let mut total: f64 = energy.self_energy.iter().sum();
+        for (i, row) in energy.interaction.iter().enumerate() {
+            assert_eq!(row[i], 0.0);
+            total += row.iter().skip(i + 1).sum::<f64>();
+        }
+        assert!((energy.total - total).abs() < 1e-12 * total.abs());
+
+        // Interaction energies are reciprocal
+        let reverse = interaction_energy_2d(&magnet_list[1], &magnet_list[0]).unwrap();
+        let scale = energy.interaction[0][1].abs();
+        assert!((reverse - energy.interaction[0][1]).abs() < 1e-5 * scale);
+    }
+}
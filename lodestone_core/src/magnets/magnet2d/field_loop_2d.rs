@@ -1,6 +1,6 @@
-use crate::magnets::{GetField, Magnet2D};
-use crate::points::{Point2, Points2};
-use crate::MagnetError;
+use crate::magnets::{Contains, FieldKind, GetField, Magnet2D};
+use crate::points::{Point2, Points, Points2};
+use crate::{MagnetError, MU0};
 
 /// Loops over every 2D Magnet and gets the field at a point `point` .
 ///
@@ -15,6 +15,10 @@ pub fn loop_field_2d(magnet_list: &[Magnet2D], point: &Point2) -> Result<Point2,
             Magnet2D::Rectangle(magnet) => local_field += magnet.field(point)?,
             Magnet2D::Circle(magnet) => local_field += magnet.field(point)?,
             Magnet2D::Polygon(magnet) => local_field += magnet.field(point)?,
+            Magnet2D::Wire(magnet) => local_field += magnet.field(point)?,
+            Magnet2D::Ring(magnet) => local_field += magnet.field(point)?,
+            Magnet2D::ArcSegment(magnet) => local_field += magnet.field(point)?,
+            Magnet2D::Ellipse(magnet) => local_field += magnet.field(point)?,
         }
     }
     Ok(local_field)
@@ -35,11 +39,59 @@ pub fn get_field_2d(
             Magnet2D::Rectangle(magnet) => local_field += magnet.field(&point)?,
             Magnet2D::Circle(magnet) => local_field += magnet.field(&point)?,
             Magnet2D::Polygon(magnet) => local_field += magnet.field(&point)?,
+            Magnet2D::Wire(magnet) => local_field += magnet.field(&point)?,
+            Magnet2D::Ring(magnet) => local_field += magnet.field(&point)?,
+            Magnet2D::ArcSegment(magnet) => local_field += magnet.field(&point)?,
+            Magnet2D::Ellipse(magnet) => local_field += magnet.field(&point)?,
         }
     }
     Ok(local_field.as_tuple())
 }
 
+/// Returns the magnetisation $`\mathbf{J} = \mu_0 \mathbf{M}`$ in tesla at a
+/// point `point`, summed over every magnet containing it. This is zero outside
+/// all of the magnets.
+pub fn loop_magnetisation_2d(magnet_list: &[Magnet2D], point: &Point2) -> Point2 {
+    let mut local_magnetisation = Point2::zero();
+
+    for mag in magnet_list {
+        if mag.contains(point) {
+            local_magnetisation += mag.magnetisation_at(point);
+        }
+    }
+    local_magnetisation
+}
+
+/// Loops over every 2D Magnet and gets the field selected by `field_kind` at a
+/// point `point`.
+///
+/// B is returned in tesla, while H and M are returned in A/m, with
+/// $`\mathbf{H} = \mathbf{B}/\mu_0 - \mathbf{M}`$.
+pub fn loop_field_kind_2d(
+    magnet_list: &[Magnet2D],
+    point: &Point2,
+    field_kind: FieldKind,
+) -> Result<Point2, MagnetError> {
+    Ok(match field_kind {
+        FieldKind::B => loop_field_2d(magnet_list, point)?,
+        FieldKind::H => (loop_field_2d(magnet_list, point)?
+            - loop_magnetisation_2d(magnet_list, point))
+        .scale(1.0 / MU0),
+        FieldKind::M => loop_magnetisation_2d(magnet_list, point).scale(1.0 / MU0),
+    })
+}
+
+/// Returns the field selected by `field_kind` due to an array of magnets
+/// `magnet_list`, at a point `point`
+pub fn get_field_kind_2d(
+    magnet_list: &[Magnet2D],
+    point: (&f64, &f64),
+    field_kind: FieldKind,
+) -> Result<(f64, f64), MagnetError> {
+    let point = Point2::new(*point.0, *point.1);
+    Ok(loop_field_kind_2d(magnet_list, &point, field_kind)?.as_tuple())
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -82,4 +134,57 @@ mod tests {
         println!("Total field is {} at point {}", local_field, point);
         assert!(nearly_equal(local_field.x, 1.357145077959237));
     }
+
+    #[test]
+    fn test_field_kind_2d() {
+        let magnet_list = vec![Magnet2D::Rectangle(Rectangle::new(
+            1.0,
+            1.0,
+            (0.0, 0.0),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(90.0),
+        ))];
+
+        // Outside, H = B / mu0 and M = 0
+        let point = Point2::new(1.0, 1.0);
+        let field = loop_field_2d(&magnet_list, &point).unwrap();
+        let h_field = loop_field_kind_2d(&magnet_list, &point, FieldKind::H).unwrap();
+        let m_field = loop_field_kind_2d(&magnet_list, &point, FieldKind::M).unwrap();
+        assert!(nearly_equal(h_field.x * MU0, field.x));
+        assert!(nearly_equal(h_field.y * MU0, field.y));
+        assert_eq!(m_field, Point2::zero());
+
+        // At the center H opposes M, with B = mu0 (H + M)
+        let point = Point2::new(0.0, 0.0);
+        let field = loop_field_2d(&magnet_list, &point).unwrap();
+        let h_field = loop_field_kind_2d(&magnet_list, &point, FieldKind::H).unwrap();
+        let m_field = loop_field_kind_2d(&magnet_list, &point, FieldKind::M).unwrap();
+        let magnetisation = magnet_list[0].magnetisation_vector();
+        assert!(nearly_equal(m_field.x * MU0, magnetisation.x));
+        assert!(nearly_equal(m_field.y * MU0, magnetisation.y));
+        assert!(h_field.dot(&m_field) < 0.0);
+        assert!(nearly_equal((h_field.x + m_field.x) * MU0, field.x));
+        assert!(nearly_equal((h_field.y + m_field.y) * MU0, field.y));
+    }
+
+    #[test]
+    fn test_magnetisation_vector_matches_field() {
+        // Far from a rotated magnet the field is dipolar, and along the
+        // magnetisation it is parallel to it
+        let magnet = Magnet2D::Rectangle(Rectangle::new(
+            1.0,
+            1.0,
+            (0.0, 0.0),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(0.0),
+        ));
+        let direction = magnet.magnetisation_vector().unit();
+        let point = direction.scale(100.0);
+        let field = loop_field_2d(std::slice::from_ref(&magnet), &point)
+            .unwrap()
+            .unit();
+        assert!((field.dot(&direction) - 1.0).abs() < 1e-6);
+    }
 }
@@ -0,0 +1,732 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Forces and torques between 2D magnets
+//!
+//! A uniformly magnetised magnet is equivalent to a surface charge density
+//! $`\sigma = \mathbf{J} \cdot \mathbf{\hat{n}}`$ on its boundary. The force
+//! per unit length on a magnet due to an external field $`\mathbf{B}`$ is then
+//!
+//! ```math
+//! \mathbf{F} = \frac{1}{\mu_0} \oint \sigma \mathbf{B} \, dl
+//! ```
+//!
+//! and the torque per unit length about a pivot $`\mathbf{p}`$ is
+//!
+//! ```math
+//! \tau_z = \frac{1}{\mu_0} \oint \sigma \left[ (\mathbf{r} - \mathbf{p}) \times \mathbf{B} \right]_z dl
+//! ```
+//!
+//! Magnets without a uniform magnetisation also carry a volume charge
+//! $`\rho = -\nabla \cdot \mathbf{J}`$, which feels a force
+//! $`\frac{1}{\mu_0} \int \rho \mathbf{B} \, dA`$.
+//!
+//! Current carrying wires instead feel the Lorentz force
+//! $`\mathbf{F} = \int j \mathbf{\hat{z}} \times \mathbf{B} \, dA`$.
+//!
+//! The boundary integrals are evaluated by Gauss-Legendre quadrature on each
+//! edge or arc, or the trapezoidal rule around a circle, and the integrals over
+//! the cross-section of a wire or arc segment by Gauss-Legendre quadrature on a
+//! grid of cells.
+//! With lengths in m, magnetisations in T and currents in A, forces are
+//! returned in N/m and torques in N.
+
+use crate::magnets::magnet2d::{
+    ring_points, ArcSegment, Circle, Ellipse, Polygon, Rectangle, Ring, RingMagnetisation, Wire,
+    WireSection,
+};
+use crate::magnets::{loop_field_2d, Magnet2D};
+use crate::points::{Point2, Points, Points2};
+use crate::{MagnetError, M2_PI, MU0};
+
+/// Number of segments each straight edge is divided into for quadrature
+const EDGE_SEGMENTS: usize = 16;
+
+/// Number of quadrature points around the circumference of a circle
+const CIRCLE_NODES: usize = 256;
+
+/// Number of cells along each side, or radius, of the cross-section of a wire
+const WIRE_SEGMENTS: usize = 4;
+
+/// Number of segments the span of an arc segment is divided into for quadrature
+const ARC_SEGMENTS: usize = 16;
+
+/// Number of cells along the radius of an arc segment
+const ARC_RADIAL_SEGMENTS: usize = 4;
+
+/// Four point Gauss-Legendre nodes and weights on [-1, 1]
+pub(crate) const GAUSS_NODES: [(f64, f64); 4] = [
+    (-0.861_136_311_594_052_6, 0.347_854_845_137_453_9),
+    (-0.339_981_043_584_856_3, 0.652_145_154_862_546_1),
+    (0.339_981_043_584_856_3, 0.652_145_154_862_546_1),
+    (0.861_136_311_594_052_6, 0.347_854_845_137_453_9),
+];
+
+/// A quadrature point on the surface of a 2D magnet
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SurfaceElement {
+    /// position of the quadrature point
+    pub position: Point2,
+    /// outward unit normal
+    pub normal: Point2,
+    /// quadrature weight, i.e. the length of surface it represents
+    pub length: f64,
+    /// magnetisation just inside the surface, in T
+    pub magnetisation: Point2,
+}
+
+impl SurfaceElement {
+    /// Returns the surface charge density $`\sigma = \mathbf{J} \cdot \mathbf{\hat{n}}`$
+    pub fn sigma(&self) -> f64 {
+        self.magnetisation.dot(&self.normal)
+    }
+
+    /// Returns the surface current density $`K_z = (\mathbf{J} \times \mathbf{\hat{n}})_z`$
+    pub fn current(&self) -> f64 {
+        self.magnetisation.x * self.normal.y - self.magnetisation.y * self.normal.x
+    }
+}
+
+/// A quadrature point within the cross-section of a current carrying wire
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CurrentElement {
+    /// position of the quadrature point
+    pub position: Point2,
+    /// current it represents, in A
+    pub current: f64,
+}
+
+/// A quadrature point within the volume charge of a magnet
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChargeElement {
+    /// position of the quadrature point
+    pub position: Point2,
+    /// volume charge it represents, $`\rho \, dA`$ in T m
+    pub charge: f64,
+}
+
+/// Returns the quadrature points along a straight edge from `start` to `end`,
+/// with outward normal `normal`
+fn edge_elements(
+    start: &Point2,
+    end: &Point2,
+    normal: &Point2,
+    magnetisation: &Point2,
+) -> Vec<SurfaceElement> {
+    let delta = (*end - *start).scale(1.0 / EDGE_SEGMENTS as f64);
+    let segment_length = delta.magnitude();
+
+    let mut elements = Vec::with_capacity(EDGE_SEGMENTS * GAUSS_NODES.len());
+    for segment in 0..EDGE_SEGMENTS {
+        let midpoint = *start + delta.scale(segment as f64 + 0.5);
+        for &(node, weight) in &GAUSS_NODES {
+            elements.push(SurfaceElement {
+                position: midpoint + delta.scale(node / 2.0),
+                normal: *normal,
+                length: weight * segment_length / 2.0,
+                magnetisation: *magnetisation,
+            });
+        }
+    }
+    elements
+}
+
+/// Returns the quadrature points around a closed polygon, given its vertices
+/// in either winding order
+fn polygon_elements(vertices: &[Point2], magnetisation: &Point2) -> Vec<SurfaceElement> {
+    let num_vertices = vertices.len();
+
+    // Signed area, > 0 for anti-clockwise vertices
+    let area: f64 = (0..num_vertices)
+        .map(|i| {
+            let j = (i + 1) % num_vertices;
+            vertices[i].x * vertices[j].y - vertices[j].x * vertices[i].y
+        })
+        .sum();
+    let orientation = if area > 0.0 { 1.0 } else { -1.0 };
+
+    let mut elements = Vec::new();
+    for i in 0..num_vertices {
+        let j = (i + 1) % num_vertices;
+        let delta = vertices[j] - vertices[i];
+        let length = delta.magnitude();
+        if length == 0.0 {
+            continue;
+        }
+        let normal = Point2::new(delta.y, -delta.x).scale(orientation / length);
+        elements.extend(edge_elements(
+            &vertices[i],
+            &vertices[j],
+            &normal,
+            magnetisation,
+        ));
+    }
+    elements
+}
+
+fn rectangle_elements(magnet: &Rectangle) -> Vec<SurfaceElement> {
+    // The field routine rotates points by alpha into the local frame, so the
+    // magnet itself is rotated by -alpha
+    let reverse_alpha = M2_PI - magnet.alpha.to_radians();
+    let vertices: Vec<Point2> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)]
+        .iter()
+        .map(|(sx, sy)| {
+            magnet.center + Point2::new(sx * magnet.a, sy * magnet.b).rotate(&reverse_alpha)
+        })
+        .collect();
+    polygon_elements(&vertices, &magnet.global_magnetisation())
+}
+
+/// Returns the quadrature points around a circle, with normals pointing
+/// outwards for a positive `orientation`, and inwards for a negative one
+fn circular_elements(
+    center: &Point2,
+    radius: f64,
+    orientation: f64,
+    magnetisation: &Point2,
+) -> Vec<SurfaceElement> {
+    let d_theta = M2_PI / CIRCLE_NODES as f64;
+    (0..CIRCLE_NODES)
+        .map(|i| {
+            let theta = i as f64 * d_theta;
+            let radial = Point2::new(theta.cos(), theta.sin());
+            SurfaceElement {
+                position: *center + radial.scale(radius),
+                normal: radial.scale(orientation),
+                length: radius * d_theta,
+                magnetisation: *magnetisation,
+            }
+        })
+        .collect()
+}
+
+fn circle_elements(magnet: &Circle) -> Vec<SurfaceElement> {
+    let magnetisation = Magnet2D::Circle(*magnet).magnetisation_vector();
+    circular_elements(&magnet.center, magnet.radius, 1.0, &magnetisation)
+}
+
+fn ellipse_elements(magnet: &Ellipse) -> Vec<SurfaceElement> {
+    // The ellipse is rotated by -alpha, as for a Rectangle
+    let alpha = -magnet.alpha.to_radians();
+    let magnetisation = magnet.global_magnetisation();
+    let d_t = M2_PI / CIRCLE_NODES as f64;
+    (0..CIRCLE_NODES)
+        .map(|i| {
+            let t = i as f64 * d_t;
+            let tangent = Point2::new(-magnet.a * t.sin(), magnet.b * t.cos());
+            SurfaceElement {
+                position: magnet.boundary_point(t),
+                normal: Point2::new(tangent.y, -tangent.x).unit().rotate(&alpha),
+                length: tangent.magnitude() * d_t,
+                magnetisation,
+            }
+        })
+        .collect()
+}
+
+fn ring_elements(magnet: &Ring) -> Vec<SurfaceElement> {
+    match magnet.magnetisation {
+        RingMagnetisation::Diametric => {
+            let magnetisation = magnet.magnetisation_at(&magnet.center);
+            let mut elements =
+                circular_elements(&magnet.center, magnet.outer_radius, 1.0, &magnetisation);
+            elements.extend(circular_elements(
+                &magnet.center,
+                magnet.inner_radius,
+                -1.0,
+                &magnetisation,
+            ));
+            elements
+        }
+        // The surface and volume charges of a radially magnetised ring cancel,
+        // and an azimuthally magnetised ring has none, so neither feels a force
+        RingMagnetisation::Radial | RingMagnetisation::Azimuthal => Vec::new(),
+    }
+}
+
+/// Returns the quadrature points along an arc of radius `radius` spanning
+/// angles `start` to `end`, with radial normals pointing outwards for a
+/// positive `orientation`, and inwards for a negative one
+fn arc_elements(
+    magnet: &ArcSegment,
+    radius: f64,
+    start: f64,
+    end: f64,
+    orientation: f64,
+) -> Vec<SurfaceElement> {
+    let d_theta = (end - start) / ARC_SEGMENTS as f64;
+
+    let mut elements = Vec::with_capacity(ARC_SEGMENTS * GAUSS_NODES.len());
+    for segment in 0..ARC_SEGMENTS {
+        for &(node, weight) in &GAUSS_NODES {
+            let theta = start + (segment as f64 + 0.5 + node / 2.0) * d_theta;
+            let radial = Point2::new(theta.cos(), theta.sin());
+            elements.push(SurfaceElement {
+                position: magnet.center + radial.scale(radius),
+                normal: radial.scale(orientation),
+                length: weight * radius * d_theta / 2.0,
+                magnetisation: radial.scale(magnet.jr),
+            });
+        }
+    }
+    elements
+}
+
+fn arc_segment_elements(magnet: &ArcSegment) -> Vec<SurfaceElement> {
+    let (start, end) = magnet.flank_angles();
+    let mut elements = arc_elements(magnet, magnet.outer_radius, start, end, 1.0);
+    elements.extend(arc_elements(magnet, magnet.inner_radius, start, end, -1.0));
+
+    // The flanks carry no charge, but do carry the surface currents
+    for (angle, sign) in &[(start, -1.0), (end, 1.0)] {
+        let radial = Point2::new(angle.cos(), angle.sin());
+        elements.extend(edge_elements(
+            &(magnet.center + radial.scale(magnet.inner_radius)),
+            &(magnet.center + radial.scale(magnet.outer_radius)),
+            &Point2::new(-radial.y, radial.x).scale(*sign),
+            &radial.scale(magnet.jr),
+        ));
+    }
+    elements
+}
+
+fn polygon_magnet_elements(magnet: &Polygon) -> Vec<SurfaceElement> {
+    let magnetisation = Point2::new(magnet.jx, magnet.jy);
+    let mut elements = polygon_elements(&ring_points(&magnet.vertices), &magnetisation);
+
+    // The surfaces of the holes face into them
+    for hole in &magnet.holes {
+        elements.extend(
+            polygon_elements(&ring_points(hole), &magnetisation)
+                .into_iter()
+                .map(|element| SurfaceElement {
+                    normal: -element.normal,
+                    ..element
+                }),
+        );
+    }
+    elements
+}
+
+/// Returns the quadrature points on the surface of a 2D magnet
+pub fn surface_elements_2d(magnet: &Magnet2D) -> Vec<SurfaceElement> {
+    match magnet {
+        Magnet2D::Rectangle(magnet) => rectangle_elements(magnet),
+        Magnet2D::Circle(magnet) => circle_elements(magnet),
+        Magnet2D::Polygon(magnet) => polygon_magnet_elements(magnet),
+        // Wires are not magnetised, so carry no surface charge
+        Magnet2D::Wire(_) => Vec::new(),
+        Magnet2D::Ring(magnet) => ring_elements(magnet),
+        Magnet2D::ArcSegment(magnet) => arc_segment_elements(magnet),
+        Magnet2D::Ellipse(magnet) => ellipse_elements(magnet),
+    }
+}
+
+fn arc_segment_charges(magnet: &ArcSegment) -> Vec<ChargeElement> {
+    // The volume charge density -J/r cancels the area element r dr dtheta
+    let (start, end) = magnet.flank_angles();
+    let d_rho = (magnet.outer_radius - magnet.inner_radius) / ARC_RADIAL_SEGMENTS as f64;
+    let d_theta = (end - start) / ARC_SEGMENTS as f64;
+
+    let mut elements = Vec::new();
+    for i in 0..ARC_RADIAL_SEGMENTS {
+        for &(node_rho, weight_rho) in &GAUSS_NODES {
+            let rho = magnet.inner_radius + (i as f64 + 0.5 + node_rho / 2.0) * d_rho;
+            for j in 0..ARC_SEGMENTS {
+                for &(node_theta, weight_theta) in &GAUSS_NODES {
+                    let theta = start + (j as f64 + 0.5 + node_theta / 2.0) * d_theta;
+                    elements.push(ChargeElement {
+                        position: magnet.center + Point2::new(theta.cos(), theta.sin()).scale(rho),
+                        charge: -magnet.jr * weight_rho * weight_theta * d_rho * d_theta / 4.0,
+                    });
+                }
+            }
+        }
+    }
+    elements
+}
+
+/// Returns the quadrature points within the volume charge of a magnet, or an
+/// empty list for a uniformly magnetised magnet
+pub fn charge_elements_2d(magnet: &Magnet2D) -> Vec<ChargeElement> {
+    match magnet {
+        Magnet2D::ArcSegment(magnet) => arc_segment_charges(magnet),
+        _ => Vec::new(),
+    }
+}
+
+fn wire_elements(wire: &Wire) -> Vec<CurrentElement> {
+    let density = wire.current_density();
+    match wire.section {
+        WireSection::Filament => vec![CurrentElement {
+            position: wire.center,
+            current: wire.current,
+        }],
+        WireSection::Rectangle(width, height) => {
+            // The field routine rotates points by alpha into the local frame
+            let reverse_alpha = M2_PI - wire.alpha.to_radians();
+            let delta = Point2::new(width, height).scale(1.0 / WIRE_SEGMENTS as f64);
+            let cell_area = delta.x * delta.y / 4.0;
+
+            let mut elements = Vec::new();
+            for i in 0..WIRE_SEGMENTS {
+                for j in 0..WIRE_SEGMENTS {
+                    let midpoint = Point2::new(
+                        (i as f64 + 0.5) * delta.x - width / 2.0,
+                        (j as f64 + 0.5) * delta.y - height / 2.0,
+                    );
+                    for &(node_x, weight_x) in &GAUSS_NODES {
+                        for &(node_y, weight_y) in &GAUSS_NODES {
+                            let local = midpoint
+                                + Point2::new(node_x * delta.x / 2.0, node_y * delta.y / 2.0);
+                            elements.push(CurrentElement {
+                                position: wire.center + local.rotate(&reverse_alpha),
+                                current: density * weight_x * weight_y * cell_area,
+                            });
+                        }
+                    }
+                }
+            }
+            elements
+        }
+        WireSection::Circle(radius) => {
+            let d_rho = radius / WIRE_SEGMENTS as f64;
+            let d_theta = M2_PI / CIRCLE_NODES as f64;
+
+            let mut elements = Vec::new();
+            for i in 0..WIRE_SEGMENTS {
+                for &(node, weight) in &GAUSS_NODES {
+                    let rho = (i as f64 + 0.5 + node / 2.0) * d_rho;
+                    let current = density * rho * weight * d_rho / 2.0 * d_theta;
+                    elements.extend((0..CIRCLE_NODES).map(|k| {
+                        let theta = k as f64 * d_theta;
+                        CurrentElement {
+                            position: wire.center
+                                + Point2::new(theta.cos(), theta.sin()).scale(rho),
+                            current,
+                        }
+                    }));
+                }
+            }
+            elements
+        }
+    }
+}
+
+/// Returns the quadrature points within the cross-section of a current
+/// carrying wire, or an empty list for a magnet
+pub fn current_elements_2d(magnet: &Magnet2D) -> Vec<CurrentElement> {
+    match magnet {
+        Magnet2D::Wire(wire) => wire_elements(wire),
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the field at a point due to every magnet in the list except the
+/// one at `index`
+fn external_field_2d(
+    magnet_list: &[Magnet2D],
+    index: usize,
+    point: &Point2,
+) -> Result<Point2, MagnetError> {
+    Ok(loop_field_2d(&magnet_list[..index], point)?
+        + loop_field_2d(&magnet_list[index + 1..], point)?)
+}
+
+/// Returns the force per unit length and the torque per unit length about
+/// `pivot`, on the magnet at `index` due to all other magnets in the list
+pub fn get_force_torque_2d(
+    magnet_list: &[Magnet2D],
+    index: usize,
+    pivot: &Point2,
+) -> Result<(Point2, f64), MagnetError> {
+    let target = magnet_list
+        .get(index)
+        .ok_or(MagnetError::MagnetIndexError(index))?;
+
+    let mut force = Point2::zero();
+    let mut torque = 0.0;
+    for element in surface_elements_2d(target) {
+        let field = external_field_2d(magnet_list, index, &element.position)?;
+        let charge = element.sigma() * element.length;
+        force += field.scale(charge);
+
+        let arm = element.position - *pivot;
+        torque += charge * (arm.x * field.y - arm.y * field.x);
+    }
+    for element in charge_elements_2d(target) {
+        let field = external_field_2d(magnet_list, index, &element.position)?;
+        force += field.scale(element.charge);
+
+        let arm = element.position - *pivot;
+        torque += element.charge * (arm.x * field.y - arm.y * field.x);
+    }
+    force = force.scale(1.0 / MU0);
+    torque /= MU0;
+
+    for element in current_elements_2d(target) {
+        let field = external_field_2d(magnet_list, index, &element.position)?;
+        let lorentz = Point2::new(-field.y, field.x).scale(element.current);
+        force += lorentz;
+
+        let arm = element.position - *pivot;
+        torque += arm.x * lorentz.y - arm.y * lorentz.x;
+    }
+
+    Ok((force, torque))
+}
+
+/// Returns the force per unit length on the magnet at `index` due to all
+/// other magnets in the list
+pub fn get_force_2d(magnet_list: &[Magnet2D], index: usize) -> Result<Point2, MagnetError> {
+    let (force, _) = get_force_torque_2d(magnet_list, index, &Point2::zero())?;
+    Ok(force)
+}
+
+/// Returns the torque per unit length about `pivot` on the magnet at `index`
+/// due to all other magnets in the list
+pub fn get_torque_2d(
+    magnet_list: &[Magnet2D],
+    index: usize,
+    pivot: &Point2,
+) -> Result<f64, MagnetError> {
+    let (_, torque) = get_force_torque_2d(magnet_list, index, pivot)?;
+    Ok(torque)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::{PolyDimension, Vertices};
+    use crate::utils::conversions::Angle;
+    use crate::PI;
+
+    fn circle(center: (f64, f64), phi: f64) -> Magnet2D {
+        Magnet2D::Circle(Circle::new(
+            0.5,
+            center,
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(phi),
+        ))
+    }
+
+    #[test]
+    fn test_coaxial_circles() {
+        // Uniformly magnetised circles interact exactly as 2D dipoles,
+        // F = -pi R1^2 R2^2 J1 J2 / (mu0 d^3)
+        let magnet_list = vec![circle((0.0, 0.0), 0.0), circle((2.0, 0.0), 0.0)];
+        let force = get_force_2d(&magnet_list, 1).unwrap();
+        let comp_force = -PI * 0.5_f64.powi(4) / (MU0 * 8.0);
+        assert!(((force.x - comp_force) / comp_force).abs() < 1e-10);
+        assert!((force.y / comp_force).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_rotated_circle() {
+        // Rotating a circle by alpha rotates its magnetisation by alpha
+        let source = circle((-1.5, 0.5), 30.0);
+        let rotated = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.5, -0.2),
+            Angle::Degrees(40.0),
+            1.0,
+            Angle::Degrees(20.0),
+        ));
+        let comp = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.5, -0.2),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(60.0),
+        ));
+        let pivot = Point2::new(0.1, 0.3);
+        let (force, torque) = get_force_torque_2d(&[source.clone(), rotated], 1, &pivot).unwrap();
+        let (comp_force, comp_torque) = get_force_torque_2d(&[source, comp], 1, &pivot).unwrap();
+        assert!((force - comp_force).magnitude() < 1e-10 * comp_force.magnitude());
+        assert!((torque - comp_torque).abs() < 1e-10 * comp_torque.abs());
+    }
+
+    #[test]
+    fn test_arc_segment_newtons_third_law() {
+        // Includes the volume charge of the radially magnetised segment
+        let segment = Magnet2D::ArcSegment(ArcSegment::new(
+            0.5,
+            1.0,
+            (0.0, 0.0),
+            Angle::Degrees(30.0),
+            Angle::Degrees(45.0),
+            1.0,
+        ));
+        let magnet_list = vec![segment, circle((1.2, 1.4), 20.0)];
+        let force_0 = get_force_2d(&magnet_list, 0).unwrap();
+        let force_1 = get_force_2d(&magnet_list, 1).unwrap();
+        assert!((force_0 + force_1).magnitude() < 1e-5 * force_0.magnitude());
+
+        let pivot = Point2::new(0.3, -0.2);
+        let torque_0 = get_torque_2d(&magnet_list, 0, &pivot).unwrap();
+        let torque_1 = get_torque_2d(&magnet_list, 1, &pivot).unwrap();
+        assert!((torque_0 + torque_1).abs() < 1e-5 * torque_0.abs());
+    }
+
+    #[test]
+    fn test_coaxial_ring_and_circle() {
+        // A diametric ring has the dipole moment of a rod of area pi (Ro^2 - Ri^2)
+        let ring = Magnet2D::Ring(Ring::new(
+            0.3,
+            0.6,
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(0.0),
+            RingMagnetisation::Diametric,
+        ));
+        let magnet_list = vec![ring, circle((2.0, 0.0), 0.0)];
+        let force = get_force_2d(&magnet_list, 0).unwrap();
+        let comp_force = PI * (0.6_f64.powi(2) - 0.3_f64.powi(2)) * 0.25 / (MU0 * 8.0);
+        assert!(((force.x - comp_force) / comp_force).abs() < 1e-10);
+        assert!((force.y / comp_force).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_perpendicular_circles_torque() {
+        // tau = -m2 mu0 m1 / (2 pi d^2), with m = pi R^2 J / mu0
+        let magnet_list = vec![circle((0.0, 0.0), 0.0), circle((2.0, 0.0), 90.0)];
+        let torque = get_torque_2d(&magnet_list, 1, &Point2::new(2.0, 0.0)).unwrap();
+        let m = PI * 0.25 / MU0;
+        let comp_torque = -m * MU0 * m / (M2_PI * 4.0);
+        assert!(((torque - comp_torque) / comp_torque).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_newtons_third_law() {
+        let magnet_list = vec![
+            Magnet2D::Rectangle(Rectangle::new(
+                1.0,
+                2.0,
+                (0.0, 0.0),
+                Angle::Degrees(20.0),
+                1.0,
+                Angle::Degrees(90.0),
+            )),
+            Magnet2D::Rectangle(Rectangle::new(
+                0.5,
+                0.5,
+                (1.5, 0.3),
+                Angle::Degrees(0.0),
+                1.2,
+                Angle::Degrees(30.0),
+            )),
+        ];
+        let (force_0, torque_0) =
+            get_force_torque_2d(&magnet_list, 0, &Point2::new(0.5, 0.0)).unwrap();
+        let (force_1, torque_1) =
+            get_force_torque_2d(&magnet_list, 1, &Point2::new(0.5, 0.0)).unwrap();
+
+        let scale = force_0.magnitude();
+        assert!((force_0.x + force_1.x).abs() < 1e-6 * scale);
+        assert!((force_0.y + force_1.y).abs() < 1e-6 * scale);
+        assert!((torque_0 + torque_1).abs() < 1e-6 * scale);
+    }
+
+    #[test]
+    fn test_polygon_matches_rectangle() {
+        let source = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 2.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(45.0),
+        ));
+        let rectangle = Magnet2D::Rectangle(Rectangle::new(
+            2.0,
+            2.0,
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+        ));
+        let polygon = Magnet2D::Polygon(
+            Polygon::new(
+                (0.0, 0.0),
+                Angle::Degrees(0.0),
+                1.0,
+                Angle::Degrees(90.0),
+                Vertices::Regular(4, PolyDimension::Side(2.0)),
+            )
+            .unwrap(),
+        );
+
+        let force_rect = get_force_2d(&[source, rectangle], 1).unwrap();
+        let source = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 2.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(45.0),
+        ));
+        let force_poly = get_force_2d(&[source, polygon], 1).unwrap();
+
+        assert!(((force_rect.x - force_poly.x) / force_rect.x).abs() < 1e-10);
+        assert!(((force_rect.y - force_poly.y) / force_rect.y).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_parallel_wires() {
+        // Parallel currents attract, F = mu0 I1 I2 / (2 pi d)
+        let magnet_list = vec![
+            Magnet2D::Wire(Wire::new(
+                (0.0, 0.0),
+                Angle::Degrees(0.0),
+                2.0,
+                WireSection::Filament,
+            )),
+            Magnet2D::Wire(Wire::new(
+                (0.5, 0.0),
+                Angle::Degrees(0.0),
+                3.0,
+                WireSection::Circle(0.1),
+            )),
+        ];
+        let force = get_force_2d(&magnet_list, 1).unwrap();
+        let comp_force = -MU0 * 6.0 / (M2_PI * 0.5);
+        assert!(((force.x - comp_force) / comp_force).abs() < 1e-6);
+        assert!((force.y / comp_force).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_wire_magnet_newtons_third_law() {
+        let magnet_list = vec![
+            Magnet2D::Wire(Wire::new(
+                (0.2, 1.5),
+                Angle::Degrees(20.0),
+                1e5,
+                WireSection::Rectangle(0.4, 0.2),
+            )),
+            Magnet2D::Rectangle(Rectangle::new(
+                1.0,
+                0.5,
+                (0.0, 0.0),
+                Angle::Degrees(10.0),
+                1.0,
+                Angle::Degrees(60.0),
+            )),
+        ];
+        let pivot = Point2::new(0.3, 0.4);
+        let (force_0, torque_0) = get_force_torque_2d(&magnet_list, 0, &pivot).unwrap();
+        let (force_1, torque_1) = get_force_torque_2d(&magnet_list, 1, &pivot).unwrap();
+
+        let scale = force_0.magnitude();
+        assert!((force_0.x + force_1.x).abs() < 1e-6 * scale);
+        assert!((force_0.y + force_1.y).abs() < 1e-6 * scale);
+        assert!((torque_0 + torque_1).abs() < 1e-6 * scale);
+    }
+
+    #[test]
+    fn test_index_error() {
+        let magnet_list = vec![circle((0.0, 0.0), 0.0)];
+        assert!(get_force_2d(&magnet_list, 1).is_err());
+    }
+}
@@ -0,0 +1,210 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Field gradients of 2D magnets
+//!
+//! The gradient tensor $`G_{ij} = \partial B_i / \partial x_j`$ is found by
+//! differentiating the field expressions of each magnet analytically.
+//! Outside the surfaces of the magnets the field is both curl and divergence
+//! free, so the tensor is symmetric and traceless, $`G_{xy} = G_{yx}`$ and
+//! $`G_{xx} = -G_{yy}`$.
+
+use crate::magnets::{GetGradient, Magnet2D};
+use crate::points::Point2;
+use crate::MagnetError;
+use serde_derive::{Deserialize, Serialize};
+
+use std::fmt;
+use std::ops::{Add, AddAssign};
+
+/// Magnetic field gradient tensor in 2D, $`G_{ij} = \partial B_i / \partial x_j`$
+#[derive(Copy, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct Gradient2 {
+    /// $`\partial B_x / \partial x`$
+    pub xx: f64,
+    /// $`\partial B_x / \partial y`$
+    pub xy: f64,
+    /// $`\partial B_y / \partial x`$
+    pub yx: f64,
+    /// $`\partial B_y / \partial y`$
+    pub yy: f64,
+}
+
+impl Gradient2 {
+    /// Constructor for a Gradient2
+    pub fn new(xx: f64, xy: f64, yx: f64, yy: f64) -> Gradient2 {
+        Gradient2 { xx, xy, yx, yy }
+    }
+
+    /// Returns a tensor of zeros
+    pub fn zero() -> Gradient2 {
+        Gradient2::default()
+    }
+
+    /// Returns the components as a tuple (xx, xy, yx, yy)
+    pub fn as_tuple(&self) -> (f64, f64, f64, f64) {
+        (self.xx, self.xy, self.yx, self.yy)
+    }
+
+    /// Returns the gradient of a field after rotating both the field and the
+    /// coordinates anti-clockwise by `alpha`, $`R G R^T`$
+    pub fn rotate(&self, alpha: &f64) -> Gradient2 {
+        let (sin, cos) = alpha.sin_cos();
+
+        // G R^T
+        let m_xx = self.xx * cos - self.xy * sin;
+        let m_xy = self.xx * sin + self.xy * cos;
+        let m_yx = self.yx * cos - self.yy * sin;
+        let m_yy = self.yx * sin + self.yy * cos;
+
+        Gradient2 {
+            xx: cos * m_xx - sin * m_yx,
+            xy: cos * m_xy - sin * m_yy,
+            yx: sin * m_xx + cos * m_yx,
+            yy: sin * m_xy + cos * m_yy,
+        }
+    }
+
+    /// Returns the gradient of a field after rotating only the field
+    /// anti-clockwise by `alpha`, $`R G`$
+    pub fn rotate_field(&self, alpha: &f64) -> Gradient2 {
+        let (sin, cos) = alpha.sin_cos();
+        Gradient2 {
+            xx: cos * self.xx - sin * self.yx,
+            xy: cos * self.xy - sin * self.yy,
+            yx: sin * self.xx + cos * self.yx,
+            yy: sin * self.xy + cos * self.yy,
+        }
+    }
+
+    /// Replaces any singular (non-finite) components with 0.0
+    pub(crate) fn bind_singular(self) -> Gradient2 {
+        let bind = |value: f64| if value.is_finite() { value } else { 0.0 };
+        Gradient2 {
+            xx: bind(self.xx),
+            xy: bind(self.xy),
+            yx: bind(self.yx),
+            yy: bind(self.yy),
+        }
+    }
+}
+
+impl fmt::Display for Gradient2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "(xx: {}, xy: {}, yx: {}, yy: {})",
+            self.xx, self.xy, self.yx, self.yy
+        )
+    }
+}
+
+impl Add for Gradient2 {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self::Output {
+        Gradient2 {
+            xx: self.xx + other.xx,
+            xy: self.xy + other.xy,
+            yx: self.yx + other.yx,
+            yy: self.yy + other.yy,
+        }
+    }
+}
+
+impl AddAssign for Gradient2 {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Loops over every 2D Magnet and gets the field gradient at a point `point`
+pub fn loop_gradient_2d(
+    magnet_list: &[Magnet2D],
+    point: &Point2,
+) -> Result<Gradient2, MagnetError> {
+    let mut local_gradient = Gradient2::zero();
+
+    for mag in magnet_list {
+        match mag {
+            Magnet2D::Rectangle(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::Circle(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::Polygon(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::Wire(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::Ring(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::ArcSegment(magnet) => local_gradient += magnet.gradient(point)?,
+            Magnet2D::Ellipse(magnet) => local_gradient += magnet.gradient(point)?,
+        }
+    }
+    Ok(local_gradient)
+}
+
+/// Returns the field gradient due to an array of magnets `magnet_list`, at a
+/// point `point`, as a tuple (xx, xy, yx, yy)
+pub fn get_gradient_2d(
+    magnet_list: &[Magnet2D],
+    point: (&f64, &f64),
+) -> Result<(f64, f64, f64, f64), MagnetError> {
+    let point = Point2::new(*point.0, *point.1);
+    Ok(loop_gradient_2d(magnet_list, &point)?.as_tuple())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::{loop_field_2d, Circle, Rectangle};
+    use crate::points::Points;
+    use crate::utils::conversions::Angle;
+    use crate::PI_4;
+
+    #[test]
+    fn test_rotate() {
+        // Rotating the field and coordinates of a uniform gradient Bx = x, By = -y
+        // by 45° gives Bx = y, By = x
+        let gradient = Gradient2::new(1.0, 0.0, 0.0, -1.0).rotate(&PI_4);
+        assert!(gradient.xx.abs() < 1e-15);
+        assert!((gradient.xy - 1.0).abs() < 1e-15);
+        assert!((gradient.yx - 1.0).abs() < 1e-15);
+        assert!(gradient.yy.abs() < 1e-15);
+    }
+
+    #[test]
+    fn test_loop_gradient_2d() {
+        let magnet_list = vec![
+            Magnet2D::Rectangle(Rectangle::new(
+                1.0,
+                2.0,
+                (0.0, 0.0),
+                Angle::Degrees(30.0),
+                1.0,
+                Angle::Degrees(60.0),
+            )),
+            Magnet2D::Circle(Circle::new(
+                0.5,
+                (2.0, 0.5),
+                Angle::Degrees(0.0),
+                1.0,
+                Angle::Degrees(-20.0),
+            )),
+        ];
+        let point = Point2::new(1.2, 1.5);
+        let gradient = loop_gradient_2d(&magnet_list, &point).unwrap();
+
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        let field_dx = (loop_field_2d(&magnet_list, &(point + d_x)).unwrap()
+            - loop_field_2d(&magnet_list, &(point - d_x)).unwrap())
+        .scale(0.5 / delta);
+        let field_dy = (loop_field_2d(&magnet_list, &(point + d_y)).unwrap()
+            - loop_field_2d(&magnet_list, &(point - d_y)).unwrap())
+        .scale(0.5 / delta);
+
+        assert!((gradient.xx - field_dx.x).abs() < 1e-8);
+        assert!((gradient.yx - field_dx.y).abs() < 1e-8);
+        assert!((gradient.xy - field_dy.x).abs() < 1e-8);
+        assert!((gradient.yy - field_dy.y).abs() < 1e-8);
+    }
+}
@@ -5,8 +5,9 @@ Copyright 2021 Peter Dunne */
 
 // #![allow(unused_imports)]
 
-use super::line_field::sheet_field;
-use crate::magnets::GetField;
+use super::line_field::{sheet_field, sheet_gradient, sheet_potential};
+use crate::magnets::magnet2d::Gradient2;
+use crate::magnets::{GetField, GetGradient, GetPotential};
 use crate::{
     points::{Point2, PointVec2, Points, Points2},
     utils::conversions::Angle,
@@ -94,9 +95,8 @@ pub fn generate_line_array(vertices: &PointVec2, jx: &f64, jy: &f64) -> (LineVec
 
     // divide by 3
     centroid = centroid.scale(1.0 / area / 3.0);
-    //TODO: check winding order of polygon, area > 0 for anti-clockwise ordering of points
-    // if area > 0.0 {}
-    // then change sign of beta and kr (*-1)
+    // The area is negative for the clockwise vertices returned by
+    // validate_vertices, for which the edge normals point outwards
 
     (line_array, area * 0.5, centroid)
 }
@@ -160,6 +160,52 @@ fn get_field_line(magnet: &Line, point: &Point2) -> Result<Point2, MagnetError>
     Ok(field)
 }
 
+fn get_gradient_line(magnet: &Line, point: &Point2) -> Result<Gradient2, MagnetError> {
+    if magnet.kr.abs() <= FP_CUTOFF {
+        return Ok(Gradient2::zero());
+    }
+
+    // Translate and rotate into local coordinates
+    let mut local_point = *point - magnet.center;
+    let rotation_flag = (magnet.beta.to_radians() % PI).abs() > FP_CUTOFF;
+    if rotation_flag {
+        let reverse_angle = M2_PI - magnet.beta.to_radians();
+        local_point = local_point.rotate(&reverse_angle);
+    }
+
+    let mut gradient = sheet_gradient(
+        &local_point.x,
+        &local_point.y,
+        &(magnet.length / 2.0),
+        &magnet.kr,
+    )?;
+
+    if rotation_flag {
+        gradient = gradient.rotate(&magnet.beta.to_radians());
+    }
+    Ok(gradient.bind_singular())
+}
+
+fn get_potential_line(magnet: &Line, point: &Point2) -> Result<f64, MagnetError> {
+    if magnet.kr.abs() <= FP_CUTOFF {
+        return Ok(0.0);
+    }
+
+    // The potential is a scalar, so only the point needs rotating
+    let mut local_point = *point - magnet.center;
+    if (magnet.beta.to_radians() % PI).abs() > FP_CUTOFF {
+        let reverse_angle = M2_PI - magnet.beta.to_radians();
+        local_point = local_point.rotate(&reverse_angle);
+    }
+
+    sheet_potential(
+        &local_point.x,
+        &local_point.y,
+        &(magnet.length / 2.0),
+        &magnet.kr,
+    )
+}
+
 impl GetField<&Point2, Result<Point2, MagnetError>> for Line {
     /// Returns the magnetic field of a line magnet at a Point2 struct {x,y}
     fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
@@ -181,6 +227,20 @@ impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Line {
     }
 }
 
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Line {
+    /// Returns the field gradient of a line magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_line(self, point)
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Line {
+    /// Returns the vector potential of a line magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_line(self, point)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
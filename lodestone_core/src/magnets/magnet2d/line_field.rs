@@ -3,6 +3,7 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 Copyright 2021 Peter Dunne */
 
+use crate::magnets::magnet2d::Gradient2;
 use crate::points::Point2;
 use crate::{MagnetError, M4_PI};
 
@@ -23,12 +24,54 @@ pub fn sheet_field(x: &f64, y: &f64, h: &f64, kr: &f64) -> Result<Point2, Magnet
     Ok(Point2::new(bx, by))
 }
 
+/// Returns the field gradient tensor due to an infinite current sheet, the
+/// derivatives of `sheet_field`
+pub fn sheet_gradient(x: &f64, y: &f64, h: &f64, kr: &f64) -> Result<Gradient2, MagnetError> {
+    let prefac = kr / M4_PI;
+
+    let y_plus_h = y + h;
+    let y_minus_h = y - h;
+    let dist_minus_sq = x.powi(2) + y_minus_h.powi(2);
+    let dist_plus_sq = x.powi(2) + y_plus_h.powi(2);
+    let product = dist_minus_sq * dist_plus_sq;
+
+    let bxx = 2.0 * prefac * x * (1.0 / dist_minus_sq - 1.0 / dist_plus_sq);
+    let bxy = 2.0 * prefac * (y_minus_h / dist_minus_sq - y_plus_h / dist_plus_sq);
+
+    let byx = 4.0 * prefac * h * (y.powi(2) - h.powi(2) - x.powi(2)) / product;
+    let byy = -8.0 * prefac * h * x * y / product;
+
+    Ok(Gradient2::new(bxx, bxy, byx, byy))
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to an infinite current
+/// sheet, such that `sheet_field` is $`(\partial_y A_z, -\partial_x A_z)`$.
+///
+/// Integrating the potential of a line current along the sheet gives
+/// $`A_z = -\frac{K}{4\pi} \left[ F(x, y + h) - F(x, y - h) \right]`$, with
+/// $`F(x, s) = s \ln(x^2 + s^2) - 2s + 2x \arctan(s / x)`$.
+pub fn sheet_potential(x: &f64, y: &f64, h: &f64, kr: &f64) -> Result<f64, MagnetError> {
+    let prefac = kr / M4_PI;
+    Ok(-prefac * (sheet_primitive(x, &(y + h)) - sheet_primitive(x, &(y - h))))
+}
+
+/// Returns the antiderivative of $`\ln(x^2 + s^2)`$ with respect to `s`
+pub(crate) fn sheet_primitive(x: &f64, s: &f64) -> f64 {
+    let dist_sq = x.powi(2) + s.powi(2);
+
+    // Both the logarithmic and arctangent terms vanish at the end of the sheet
+    if dist_sq <= 0.0 {
+        return 0.0;
+    }
+    s * dist_sq.ln() - 2.0 * s + 2.0 * x * (s / x).atan()
+}
+
 // pub fn signed_area_2d(Vec<()>)
 
 #[cfg(test)]
 mod tests {
-    use crate::magnets::sheet_field;
-    use crate::points::Point2;
+    use crate::magnets::{sheet_field, sheet_gradient, sheet_potential};
+    use crate::points::{Point2, Points};
     // use crate::utils::comparison::nearly_equal;
     use crate::PI;
 
@@ -52,4 +95,48 @@ mod tests {
         let comp_field = Point2::new(0.0, 2.0_f64.atan2(1.0) / PI);
         assert_eq!(field, comp_field);
     }
+
+    #[test]
+    fn test_sheet_gradient() {
+        // Central differences of sheet_field
+        let (x, y, h, kr) = (0.3, -0.7, 0.5, 1.2);
+        let delta = 1e-6;
+        let gradient = sheet_gradient(&x, &y, &h, &kr).unwrap();
+        let d_x = (sheet_field(&(x + delta), &y, &h, &kr).unwrap()
+            - sheet_field(&(x - delta), &y, &h, &kr).unwrap())
+        .scale(0.5 / delta);
+        let d_y = (sheet_field(&x, &(y + delta), &h, &kr).unwrap()
+            - sheet_field(&x, &(y - delta), &h, &kr).unwrap())
+        .scale(0.5 / delta);
+
+        assert!((gradient.xx - d_x.x).abs() < 1e-8);
+        assert!((gradient.yx - d_x.y).abs() < 1e-8);
+        assert!((gradient.xy - d_y.x).abs() < 1e-8);
+        assert!((gradient.yy - d_y.y).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_sheet_potential() {
+        // The field is the curl of the potential
+        let (h, kr) = (0.5, 1.2);
+        let delta = 1e-6;
+        for &(x, y) in &[(0.3, -0.7), (-1.1, 0.2), (0.0, 1.5), (0.2, 0.0)] {
+            let field = sheet_field(&x, &y, &h, &kr).unwrap();
+            let d_x = (sheet_potential(&(x + delta), &y, &h, &kr).unwrap()
+                - sheet_potential(&(x - delta), &y, &h, &kr).unwrap())
+                / (2.0 * delta);
+            let d_y = (sheet_potential(&x, &(y + delta), &h, &kr).unwrap()
+                - sheet_potential(&x, &(y - delta), &h, &kr).unwrap())
+                / (2.0 * delta);
+
+            assert!((field.x - d_y).abs() < 1e-8);
+            assert!((field.y + d_x).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_sheet_potential_at_edge() {
+        let potential = sheet_potential(&0.0, &0.5, &0.5, &1.0).unwrap();
+        assert!(potential.is_finite());
+    }
 }
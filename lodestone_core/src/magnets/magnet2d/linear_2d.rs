@@ -0,0 +1,822 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Linear magnetic materials in 2D
+//!
+//! Real permanent magnets have a recoil permeability $`\mu_r = 1 + \chi`$,
+//! and soft magnetic pole pieces have no remanence but $`\mu_r \gg 1`$. In both
+//! cases the magnetisation responds linearly to the local field,
+//!
+//! ```math
+//! \mathbf{J} = \mathbf{J}_r + \chi \mu_0 \mathbf{H}
+//! ```
+//!
+//! Each body is divided into uniformly magnetised cells, rectangles for
+//! `Rectangle` magnets and triangles or annular sectors otherwise, and $`\mu_0 \mathbf{H}`$
+//! is evaluated at the centroid of each cell. The cell magnetisations are
+//! relaxed by block Gauss-Seidel iteration, solving for the self-field of each
+//! cell implicitly so that the iteration remains stable for large $`\chi`$.
+
+use crate::magnets::magnet2d::{
+    point_in_polygon, ring_points, ArcSegment, Circle, Ellipse, Polygon, Rectangle, Ring, Vertices,
+};
+use crate::magnets::{loop_field_2d, GetField, Magnet2D};
+use crate::points::{Point2, PointVec2, Points, Points2};
+use crate::utils::conversions::Angle;
+use crate::{MagnetError, M2_PI};
+
+/// A magnet made of a linear magnetic material, with remanent magnetisation
+/// set by `magnet`, and susceptibility `chi`.
+///
+/// Soft magnetic bodies are described by a magnet with `jr = 0`. Wires are
+/// not magnetisable, and should be passed to the solver as sources instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearMagnet2D {
+    /// Geometry and remanent magnetisation of the body
+    pub magnet: Magnet2D,
+    /// Magnetic susceptibility, $`\chi = \mu_r - 1`$
+    pub chi: f64,
+    /// Number of cells along each side of the body used by the solver
+    pub divisions: usize,
+}
+
+impl LinearMagnet2D {
+    /// Generates a new LinearMagnet2D from its susceptibility
+    pub fn new(magnet: Magnet2D, chi: f64, divisions: usize) -> LinearMagnet2D {
+        LinearMagnet2D {
+            magnet,
+            chi,
+            divisions,
+        }
+    }
+
+    /// Generates a new LinearMagnet2D from its relative (recoil) permeability
+    pub fn with_permeability(magnet: Magnet2D, mu_r: f64, divisions: usize) -> LinearMagnet2D {
+        LinearMagnet2D::new(magnet, mu_r - 1.0, divisions)
+    }
+
+    /// Returns the relative permeability
+    pub fn mu_r(&self) -> f64 {
+        1.0 + self.chi
+    }
+}
+
+/// Settings for the iterative solver
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SolverSettings2D {
+    /// Largest change in magnetisation between iterations, relative to the
+    /// largest magnetisation, at which the solution is converged
+    pub tolerance: f64,
+    /// Maximum number of iterations
+    pub max_iterations: usize,
+    /// Relaxation factor, < 1 for under-relaxation
+    pub relaxation: f64,
+}
+
+impl Default for SolverSettings2D {
+    /// Default method for SolverSettings2D
+    fn default() -> Self {
+        SolverSettings2D {
+            tolerance: 1e-8,
+            max_iterations: 10000,
+            relaxation: 1.0,
+        }
+    }
+}
+
+/// Shape of a uniformly magnetised cell
+#[derive(Clone, Debug)]
+enum CellShape {
+    Rectangle {
+        width: f64,
+        height: f64,
+        center: Point2,
+        alpha: f64,
+    },
+    Polygon {
+        vertices: PointVec2,
+    },
+}
+
+/// A uniformly magnetised cell of a linear body
+#[derive(Clone, Debug)]
+struct Cell {
+    shape: CellShape,
+    centroid: Point2,
+    remanence: Point2,
+    chi: f64,
+}
+
+/// 2x2 matrix, stored by rows
+type Matrix2 = [[f64; 2]; 2];
+
+impl Cell {
+    /// Returns the cell as a magnet with magnetisation `magnetisation` in the global frame
+    fn magnet(&self, magnetisation: &Point2) -> Result<Magnet2D, MagnetError> {
+        let jr = magnetisation.magnitude();
+        Ok(match &self.shape {
+            CellShape::Rectangle {
+                width,
+                height,
+                center,
+                alpha,
+            } => Magnet2D::Rectangle(Rectangle::from_global_magnetisation(
+                *width,
+                *height,
+                *center,
+                Angle::Radians(*alpha),
+                magnetisation,
+            )),
+            CellShape::Polygon { vertices } => Magnet2D::Polygon(Polygon::new(
+                self.centroid,
+                Angle::Radians(0.0),
+                jr,
+                Angle::Radians(magnetisation.y.atan2(magnetisation.x)),
+                Vertices::Custom(vertices.clone()),
+            )?),
+        })
+    }
+
+    /// Returns the field at a point due to the cell magnetised with 1 T in x
+    /// and in y, as the columns of a matrix
+    fn field_matrix(&self, point: &Point2) -> Result<Matrix2, MagnetError> {
+        let field_x = self.magnet(&Point2::new(1.0, 0.0))?.field(point)?;
+        let field_y = self.magnet(&Point2::new(0.0, 1.0))?.field(point)?;
+        Ok([[field_x.x, field_y.x], [field_x.y, field_y.y]])
+    }
+}
+
+fn mat_vec(matrix: &Matrix2, vector: &Point2) -> Point2 {
+    Point2::new(
+        matrix[0][0] * vector.x + matrix[0][1] * vector.y,
+        matrix[1][0] * vector.x + matrix[1][1] * vector.y,
+    )
+}
+
+/// Returns a polygon cell with clockwise vertices, as expected by `Polygon`
+fn polygon_cell(points: &[Point2], remanence: &Point2, chi: f64) -> Cell {
+    let num_points = points.len();
+    let area: f64 = (0..num_points)
+        .map(|i| {
+            let j = (i + 1) % num_points;
+            points[i].x * points[j].y - points[j].x * points[i].y
+        })
+        .sum();
+    let centroid = points
+        .iter()
+        .fold(Point2::zero(), |sum, point| sum + *point)
+        .scale(1.0 / num_points as f64);
+
+    let ordered: Vec<&Point2> = if area > 0.0 {
+        points.iter().rev().collect()
+    } else {
+        points.iter().collect()
+    };
+
+    Cell {
+        shape: CellShape::Polygon {
+            vertices: PointVec2::new(
+                ordered.iter().map(|point| point.x).collect(),
+                ordered.iter().map(|point| point.y).collect(),
+            ),
+        },
+        centroid,
+        remanence: *remanence,
+        chi,
+    }
+}
+
+fn rectangle_cells(magnet: &Rectangle, body: &LinearMagnet2D) -> Vec<Cell> {
+    let divisions = body.divisions.max(1);
+    let width = 2.0 * magnet.a / divisions as f64;
+    let height = 2.0 * magnet.b / divisions as f64;
+    let alpha = magnet.alpha.to_radians();
+    let remanence = magnet.global_magnetisation();
+
+    let mut cells = Vec::with_capacity(divisions * divisions);
+    for i in 0..divisions {
+        for j in 0..divisions {
+            let offset = Point2::new(
+                -magnet.a + (i as f64 + 0.5) * width,
+                -magnet.b + (j as f64 + 0.5) * height,
+            );
+            let center = magnet.center + offset.rotate(&(-alpha));
+            cells.push(Cell {
+                shape: CellShape::Rectangle {
+                    width,
+                    height,
+                    center,
+                    alpha,
+                },
+                centroid: center,
+                remanence,
+                chi: body.chi,
+            });
+        }
+    }
+    cells
+}
+
+/// Divides an annulus between radii `inner_radius` and `outer_radius`, and
+/// angles `start_angle` and `start_angle + span`, into rings of sectors, each
+/// with the remanence of `magnet` at its midpoint
+fn annulus_cells(
+    magnet: &Magnet2D,
+    center: &Point2,
+    inner_radius: f64,
+    outer_radius: f64,
+    (start_angle, span): (f64, f64),
+    body: &LinearMagnet2D,
+) -> Vec<Cell> {
+    let divisions = body.divisions.max(1);
+    let num_sectors = ((4 * divisions) as f64 * span / M2_PI).ceil().max(1.0) as usize;
+    let d_rho = (outer_radius - inner_radius) / divisions as f64;
+    let d_theta = span / num_sectors as f64;
+
+    let corner = |rho: f64, theta: f64| *center + Point2::new(theta.cos(), theta.sin()).scale(rho);
+
+    let mut cells = Vec::with_capacity(divisions * num_sectors);
+    for ring in 0..divisions {
+        let inner = inner_radius + ring as f64 * d_rho;
+        let outer = inner + d_rho;
+        for sector in 0..num_sectors {
+            let start = start_angle + sector as f64 * d_theta;
+            let end = start + d_theta;
+            let points = if inner <= 0.0 {
+                vec![*center, corner(outer, start), corner(outer, end)]
+            } else {
+                vec![
+                    corner(inner, start),
+                    corner(outer, start),
+                    corner(outer, end),
+                    corner(inner, end),
+                ]
+            };
+            let remanence =
+                magnet.magnetisation_at(&corner(inner + d_rho / 2.0, start + d_theta / 2.0));
+            cells.push(polygon_cell(&points, &remanence, body.chi));
+        }
+    }
+    cells
+}
+
+fn circle_cells(magnet: &Circle, body: &LinearMagnet2D) -> Vec<Cell> {
+    annulus_cells(
+        &body.magnet,
+        &magnet.center,
+        0.0,
+        magnet.radius,
+        (0.0, M2_PI),
+        body,
+    )
+}
+
+fn ellipse_cells(magnet: &Ellipse, body: &LinearMagnet2D) -> Vec<Cell> {
+    // Stretch the cells of a unit circle onto the ellipse, rotated by -alpha as
+    // for a Rectangle, which preserves the winding order of each cell
+    let alpha = -magnet.alpha.to_radians();
+    let stretch = |point: &Point2| {
+        magnet.center + Point2::new(magnet.a * point.x, magnet.b * point.y).rotate(&alpha)
+    };
+    annulus_cells(&body.magnet, &Point2::zero(), 0.0, 1.0, (0.0, M2_PI), body)
+        .into_iter()
+        .map(|cell| match &cell.shape {
+            CellShape::Polygon { vertices } => {
+                let points: Vec<Point2> = vertices
+                    .x
+                    .iter()
+                    .zip(vertices.y.iter())
+                    .map(|(x, y)| stretch(&Point2::new(*x, *y)))
+                    .collect();
+                polygon_cell(&points, &cell.remanence, cell.chi)
+            }
+            CellShape::Rectangle { .. } => cell,
+        })
+        .collect()
+}
+
+fn ring_cells(magnet: &Ring, body: &LinearMagnet2D) -> Vec<Cell> {
+    annulus_cells(
+        &body.magnet,
+        &magnet.center,
+        magnet.inner_radius,
+        magnet.outer_radius,
+        (0.0, M2_PI),
+        body,
+    )
+}
+
+fn arc_segment_cells(magnet: &ArcSegment, body: &LinearMagnet2D) -> Vec<Cell> {
+    let (start_angle, _) = magnet.flank_angles();
+    annulus_cells(
+        &body.magnet,
+        &magnet.center,
+        magnet.inner_radius,
+        magnet.outer_radius,
+        (start_angle, magnet.span.to_radians()),
+        body,
+    )
+}
+
+/// Returns true if `point` lies inside or on the triangle (a, b, c)
+fn in_triangle(point: &Point2, a: &Point2, b: &Point2, c: &Point2) -> bool {
+    let cross =
+        |p: &Point2, q: &Point2, r: &Point2| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+    let d_1 = cross(a, b, point);
+    let d_2 = cross(b, c, point);
+    let d_3 = cross(c, a, point);
+    let negative = d_1 < 0.0 || d_2 < 0.0 || d_3 < 0.0;
+    let positive = d_1 > 0.0 || d_2 > 0.0 || d_3 > 0.0;
+    !(negative && positive)
+}
+
+/// Splits a simple polygon into triangles by ear clipping
+fn triangulate(vertices: &[Point2]) -> Result<Vec<[Point2; 3]>, MagnetError> {
+    let area: f64 = (0..vertices.len())
+        .map(|i| {
+            let j = (i + 1) % vertices.len();
+            vertices[i].x * vertices[j].y - vertices[j].x * vertices[i].y
+        })
+        .sum();
+
+    // Work with anti-clockwise vertices, so that ears have a positive cross product
+    let mut remaining: Vec<Point2> = if area > 0.0 {
+        vertices.to_vec()
+    } else {
+        vertices.iter().rev().cloned().collect()
+    };
+
+    let mut triangles = Vec::with_capacity(vertices.len().saturating_sub(2));
+    while remaining.len() > 3 {
+        let num_remaining = remaining.len();
+        let ear = (0..num_remaining).find(|&i| {
+            let a = remaining[(i + num_remaining - 1) % num_remaining];
+            let b = remaining[i];
+            let c = remaining[(i + 1) % num_remaining];
+            let convex = (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x) > 0.0;
+            convex
+                && remaining
+                    .iter()
+                    .filter(|p| **p != a && **p != b && **p != c)
+                    .all(|p| !in_triangle(p, &a, &b, &c))
+        });
+
+        // Degenerate polygons have no ears
+        let i = ear.ok_or(MagnetError::PolygonTriangulationError(num_remaining))?;
+        let a = remaining[(i + num_remaining - 1) % num_remaining];
+        let c = remaining[(i + 1) % num_remaining];
+        triangles.push([a, remaining[i], c]);
+        remaining.remove(i);
+    }
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    Ok(triangles)
+}
+
+/// Returns true if the segments (a, b) and (c, d) cross at a point interior to both
+fn segments_cross(a: &Point2, b: &Point2, c: &Point2, d: &Point2) -> bool {
+    let cross =
+        |p: &Point2, q: &Point2, r: &Point2| (q.x - p.x) * (r.y - p.y) - (q.y - p.y) * (r.x - p.x);
+    cross(a, b, c) * cross(a, b, d) < 0.0 && cross(c, d, a) * cross(c, d, b) < 0.0
+}
+
+/// Joins the holes of a polygon to its outer ring by bridges, returning a
+/// single ring that can be triangulated by ear clipping.
+///
+/// The outer ring and holes must have opposite winding orders, as stored by
+/// `Polygon`. Each hole is bridged from its right-most vertex to the nearest
+/// vertex of the ring that is visible from it, with the holes taken from right
+/// to left so that the bridges do not cross.
+fn bridge_holes(outer: &[Point2], holes: &[Vec<Point2>]) -> Result<Vec<Point2>, MagnetError> {
+    let mut order: Vec<(usize, usize)> = holes
+        .iter()
+        .enumerate()
+        .map(|(i, hole)| {
+            let right_most = (0..hole.len())
+                .max_by(|&j, &k| hole[j].x.partial_cmp(&hole[k].x).unwrap())
+                .unwrap_or(0);
+            (i, right_most)
+        })
+        .collect();
+    order.sort_by(|(i, m), (j, n)| holes[*j][*n].x.partial_cmp(&holes[*i][*m].x).unwrap());
+
+    let edges = |ring: &[Point2]| -> Vec<(Point2, Point2)> {
+        (0..ring.len())
+            .map(|i| (ring[i], ring[(i + 1) % ring.len()]))
+            .collect()
+    };
+
+    let mut ring = outer.to_vec();
+    for (i, m) in order {
+        let hole = &holes[i];
+        let start = hole[m];
+        let mut blocking = edges(&ring);
+        for hole in holes {
+            blocking.extend(edges(hole));
+        }
+
+        let visible = |end: &Point2| {
+            blocking
+                .iter()
+                .all(|(a, b)| !segments_cross(&start, end, a, b))
+                && !point_in_polygon(
+                    &PointVec2::new(
+                        hole.iter().map(|point| point.x).collect(),
+                        hole.iter().map(|point| point.y).collect(),
+                    ),
+                    &(start + *end).scale(0.5),
+                )
+        };
+        let bridge = (0..ring.len())
+            .filter(|&p| visible(&ring[p]))
+            .min_by(|&p, &q| {
+                (ring[p] - start)
+                    .magnitude()
+                    .partial_cmp(&(ring[q] - start).magnitude())
+                    .unwrap()
+            })
+            .ok_or(MagnetError::PolygonBridgeError(i))?;
+
+        // Walk around the hole from the bridge, and back to the ring
+        let mut merged = ring[..=bridge].to_vec();
+        merged.extend(hole[m..].iter().chain(hole[..=m].iter()));
+        merged.push(ring[bridge]);
+        merged.extend(ring[bridge + 1..].iter());
+        ring = merged;
+    }
+    Ok(ring)
+}
+
+fn polygon_cells(magnet: &Polygon, body: &LinearMagnet2D) -> Result<Vec<Cell>, MagnetError> {
+    let divisions = body.divisions.max(1);
+    let remanence = Point2::new(magnet.jx, magnet.jy);
+    let holes: Vec<Vec<Point2>> = magnet.holes.iter().map(ring_points).collect();
+    let vertices = bridge_holes(&ring_points(&magnet.vertices), &holes)?;
+
+    let mut cells = Vec::new();
+    for [a, b, c] in triangulate(&vertices)? {
+        // Divide each triangle into divisions^2 similar triangles
+        let step_b = (b - a).scale(1.0 / divisions as f64);
+        let step_c = (c - a).scale(1.0 / divisions as f64);
+        let node = |i: usize, j: usize| a + step_b.scale(i as f64) + step_c.scale(j as f64);
+        for i in 0..divisions {
+            for j in 0..(divisions - i) {
+                let upright = [node(i, j), node(i + 1, j), node(i, j + 1)];
+                cells.push(polygon_cell(&upright, &remanence, body.chi));
+                if i + j + 1 < divisions {
+                    let inverted = [node(i + 1, j), node(i + 1, j + 1), node(i, j + 1)];
+                    cells.push(polygon_cell(&inverted, &remanence, body.chi));
+                }
+            }
+        }
+    }
+    Ok(cells)
+}
+
+/// Divides a linear body into uniformly magnetised cells
+fn generate_cells(body: &LinearMagnet2D) -> Result<Vec<Cell>, MagnetError> {
+    Ok(match &body.magnet {
+        Magnet2D::Rectangle(magnet) => rectangle_cells(magnet, body),
+        Magnet2D::Circle(magnet) => circle_cells(magnet, body),
+        Magnet2D::Polygon(magnet) => polygon_cells(magnet, body)?,
+        // Wires are not magnetisable, and should be passed as sources
+        Magnet2D::Wire(_) => Vec::new(),
+        Magnet2D::Ring(magnet) => ring_cells(magnet, body),
+        Magnet2D::ArcSegment(magnet) => arc_segment_cells(magnet, body),
+        Magnet2D::Ellipse(magnet) => ellipse_cells(magnet, body),
+    })
+}
+
+/// Solves for the self-consistent magnetisation of a list of linear bodies,
+/// in the presence of the field of ideal magnets `sources`.
+///
+/// Returns the uniformly magnetised cells of every body, which can be passed
+/// to the other field routines along with `sources`.
+pub fn solve_magnetisation_2d(
+    bodies: &[LinearMagnet2D],
+    sources: &[Magnet2D],
+    settings: &SolverSettings2D,
+) -> Result<Vec<Magnet2D>, MagnetError> {
+    let mut cells = Vec::new();
+    for body in bodies {
+        cells.extend(generate_cells(body)?);
+    }
+    let num_cells = cells.len();
+
+    // Field at the centroid of cell i due to cell j, per unit magnetisation
+    let mut interaction = vec![[[0.0; 2]; 2]; num_cells * num_cells];
+    for (i, cell_i) in cells.iter().enumerate() {
+        for (j, cell_j) in cells.iter().enumerate() {
+            interaction[i * num_cells + j] = cell_j.field_matrix(&cell_i.centroid)?;
+        }
+    }
+
+    let mut external = Vec::with_capacity(num_cells);
+    for cell in &cells {
+        external.push(loop_field_2d(sources, &cell.centroid)?);
+    }
+
+    let mut magnetisation: Vec<Point2> = cells.iter().map(|cell| cell.remanence).collect();
+    let mut residual = f64::INFINITY;
+
+    for _ in 0..settings.max_iterations {
+        let mut max_change: f64 = 0.0;
+        let mut max_magnetisation: f64 = 0.0;
+
+        for (i, cell) in cells.iter().enumerate() {
+            // Field due to everything except the cell itself
+            let mut field = external[i];
+            for (j, other) in magnetisation.iter().enumerate() {
+                if j != i {
+                    field += mat_vec(&interaction[i * num_cells + j], other);
+                }
+            }
+
+            // Solve (I - chi (N_ii - I)) J = J_r + chi B_ext for the cell
+            let self_field = interaction[i * num_cells + i];
+            let m_xx = 1.0 + cell.chi * (1.0 - self_field[0][0]);
+            let m_xy = -cell.chi * self_field[0][1];
+            let m_yx = -cell.chi * self_field[1][0];
+            let m_yy = 1.0 + cell.chi * (1.0 - self_field[1][1]);
+            let rhs = cell.remanence + field.scale(cell.chi);
+            let det = m_xx * m_yy - m_xy * m_yx;
+            let target = Point2::new(
+                (m_yy * rhs.x - m_xy * rhs.y) / det,
+                (m_xx * rhs.y - m_yx * rhs.x) / det,
+            );
+
+            let change = (target - magnetisation[i]).scale(settings.relaxation);
+            magnetisation[i] += change;
+            max_change = max_change.max(change.magnitude());
+            max_magnetisation = max_magnetisation.max(magnetisation[i].magnitude());
+        }
+
+        residual = if max_magnetisation > 0.0 {
+            max_change / max_magnetisation
+        } else {
+            0.0
+        };
+        if residual < settings.tolerance {
+            return cells
+                .iter()
+                .zip(magnetisation.iter())
+                .map(|(cell, magnetisation)| cell.magnet(magnetisation))
+                .collect();
+        }
+    }
+
+    Err(MagnetError::ConvergenceError(
+        settings.max_iterations,
+        residual,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::{magnet_area, PolyDimension};
+    use crate::PI;
+
+    /// Returns the area weighted mean magnetisation of a list of cells
+    fn mean_magnetisation(cells: &[Magnet2D]) -> Point2 {
+        let area: f64 = cells.iter().map(magnet_area).sum();
+        cells
+            .iter()
+            .fold(Point2::zero(), |sum, cell| {
+                sum + cell.magnetisation_vector().scale(magnet_area(cell))
+            })
+            .scale(1.0 / area)
+    }
+
+    #[test]
+    fn test_ideal_magnet_unchanged() {
+        let magnet = Rectangle::new(
+            1.0,
+            0.5,
+            (0.2, 0.1),
+            Angle::Degrees(25.0),
+            1.0,
+            Angle::Degrees(60.0),
+        );
+        let body = LinearMagnet2D::new(Magnet2D::Rectangle(magnet), 0.0, 4);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+        assert_eq!(cells.len(), 16);
+
+        let point = Point2::new(1.5, -0.7);
+        let field = magnet.field(&point).unwrap();
+        let cell_field = loop_field_2d(&cells, &point).unwrap();
+        assert!((field.x - cell_field.x).abs() < 1e-12);
+        assert!((field.y - cell_field.y).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_recoil_permeability_circle() {
+        // A circle has a demagnetising factor of 1/2, so J = Jr / (1 + chi / 2)
+        let chi = 0.2;
+        let magnet = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            0.4,
+            Angle::Degrees(90.0),
+        ));
+        let body = LinearMagnet2D::new(magnet, chi, 6);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+
+        let magnetisation = mean_magnetisation(&cells);
+        let comp_magnetisation = 0.4 / (1.0 + chi / 2.0);
+        assert!(magnetisation.x.abs() < 1e-6);
+        assert!(((magnetisation.y - comp_magnetisation) / comp_magnetisation).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_recoil_permeability_ellipse() {
+        // Each component is reduced by its own demagnetising factor
+        let chi = 0.5;
+        let ellipse = Ellipse::new(
+            1.0,
+            0.25,
+            (0.0, 0.0),
+            Angle::Degrees(30.0),
+            1.0,
+            Angle::Degrees(45.0),
+        );
+        let (n_x, n_y) = ellipse.demagnetising_factors();
+        let body = LinearMagnet2D::new(Magnet2D::Ellipse(ellipse), chi, 8);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+
+        // Rotate into the local frame of the ellipse, which is rotated by -alpha
+        let magnetisation = mean_magnetisation(&cells).rotate(&(PI / 6.0));
+        let comp_x = ellipse.jx / (1.0 + chi * n_x);
+        let comp_y = ellipse.jy / (1.0 + chi * n_y);
+        assert!(((magnetisation.x - comp_x) / comp_x).abs() < 2e-2);
+        assert!(((magnetisation.y - comp_y) / comp_y).abs() < 2e-2);
+    }
+
+    #[test]
+    fn test_soft_iron_circle() {
+        // A soft circle in a nearly uniform field B0 is magnetised to
+        // J = 2 chi B0 / (chi + 2)
+        let source = Magnet2D::Circle(Circle::new(
+            1.0,
+            (-20.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(0.0),
+        ));
+        let field = source.field(&Point2::zero()).unwrap();
+
+        let iron = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            0.0,
+            Angle::Degrees(0.0),
+        ));
+        let body = LinearMagnet2D::with_permeability(iron, 1000.0, 4);
+        let cells =
+            solve_magnetisation_2d(&[body], &[source], &SolverSettings2D::default()).unwrap();
+
+        let magnetisation = mean_magnetisation(&cells);
+        let comp_magnetisation = 2.0 * 999.0 * field.x / 1001.0;
+        assert!(((magnetisation.x - comp_magnetisation) / comp_magnetisation).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_polygon_cells_cover_polygon() {
+        // An L-shaped polygon is not convex
+        let vertices = PointVec2::new(
+            vec![0.0, 0.0, 2.0, 2.0, 1.0, 1.0],
+            vec![0.0, 2.0, 2.0, 1.0, 1.0, 0.0],
+        );
+        let magnet = Polygon::new(
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Vertices::Custom(vertices),
+        )
+        .unwrap();
+        let body = LinearMagnet2D::new(Magnet2D::Polygon(magnet.clone()), 0.0, 3);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+
+        // 4 triangles of 9 cells each
+        assert_eq!(cells.len(), 36);
+        let point = Point2::new(3.0, -1.0);
+        let field = magnet.field(&point).unwrap();
+        let cell_field = loop_field_2d(&cells, &point).unwrap();
+        assert!((field.x - cell_field.x).abs() < 1e-10);
+        assert!((field.y - cell_field.y).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_polygon_cells_with_hole() {
+        let magnet = Polygon::with_holes(
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+            Vertices::Regular(4, PolyDimension::Side(2.0)),
+            vec![PointVec2::new(
+                vec![-0.2, 0.5, 0.5, -0.2],
+                vec![-0.5, -0.5, 0.3, 0.3],
+            )],
+        )
+        .unwrap();
+        let body = LinearMagnet2D::new(Magnet2D::Polygon(magnet.clone()), 0.0, 2);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+
+        // The cells cover the polygon, but not its hole
+        let area: f64 = cells.iter().map(magnet_area).sum();
+        assert!((area - magnet.area()).abs() < 1e-12);
+        for point in &[Point2::new(3.0, -1.0), Point2::new(0.1, -0.1)] {
+            let field = magnet.field(point).unwrap();
+            let cell_field = loop_field_2d(&cells, point).unwrap();
+            assert!((field - cell_field).magnitude() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_polygon_cells_with_blocking_hole() {
+        // The right-most hole lies between the other hole and the right of the
+        // outer ring, so the second bridge ends on the first hole
+        let magnet = Polygon::with_holes(
+            (0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(-45.0),
+            Vertices::Regular(4, PolyDimension::Side(4.0)),
+            vec![
+                PointVec2::new(vec![0.5, 1.0, 1.0, 0.5], vec![-1.5, -1.5, 1.5, 1.5]),
+                PointVec2::new(vec![-0.5, 0.0, 0.0, -0.5], vec![-0.2, -0.2, 0.2, 0.2]),
+            ],
+        )
+        .unwrap();
+        let body = LinearMagnet2D::new(Magnet2D::Polygon(magnet.clone()), 0.0, 2);
+        let cells = solve_magnetisation_2d(&[body], &[], &SolverSettings2D::default()).unwrap();
+
+        let area: f64 = cells.iter().map(magnet_area).sum();
+        assert!((area - magnet.area()).abs() < 1e-12);
+        for point in &[Point2::new(3.0, -1.0), Point2::new(0.25, 0.0)] {
+            let field = magnet.field(point).unwrap();
+            let cell_field = loop_field_2d(&cells, point).unwrap();
+            assert!((field - cell_field).magnitude() < 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_bridge_holes_not_visible() {
+        // A hole enclosing the outer ring cannot see any of its vertices
+        let square = |half: f64| {
+            vec![
+                Point2::new(-half, -half),
+                Point2::new(half, -half),
+                Point2::new(half, half),
+                Point2::new(-half, half),
+            ]
+        };
+        assert!(matches!(
+            bridge_holes(&square(1.0), &[square(0.2), square(2.0)]),
+            Err(MagnetError::PolygonBridgeError(1))
+        ));
+    }
+
+    #[test]
+    fn test_triangulate_degenerate() {
+        // Collinear vertices have no ears to clip
+        let vertices: Vec<Point2> = (0..4).map(|i| Point2::new(i as f64, 0.0)).collect();
+        assert!(matches!(
+            triangulate(&vertices),
+            Err(MagnetError::PolygonTriangulationError(4))
+        ));
+    }
+
+    #[test]
+    fn test_convergence_error() {
+        let iron = Magnet2D::Polygon(
+            Polygon::new(
+                (0.0, 0.0),
+                Angle::Degrees(0.0),
+                0.0,
+                Angle::Degrees(0.0),
+                Vertices::Regular(6, PolyDimension::Radius(1.0)),
+            )
+            .unwrap(),
+        );
+        let source = Magnet2D::Circle(Circle::new(
+            0.5,
+            (0.0, 3.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+        ));
+        let body = LinearMagnet2D::with_permeability(iron, 500.0, 3);
+        let settings = SolverSettings2D {
+            max_iterations: 2,
+            ..SolverSettings2D::default()
+        };
+        let result = solve_magnetisation_2d(&[body], &[source], &settings);
+        assert!(matches!(result, Err(MagnetError::ConvergenceError(2, _))));
+    }
+}
@@ -3,12 +3,12 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 Copyright 2021 Peter Dunne */
 
-use crate::magnets::magnet2d::{generate_line_array, LineVec};
-use crate::magnets::{GetCenter, GetField};
+use crate::magnets::magnet2d::{generate_line_array, Gradient2, LineVec};
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential};
 use crate::utils::conversions::Angle;
 
 use crate::points::{Point2, PointVec2, Points2};
-use crate::{MagnetError, PI};
+use crate::{MagnetError, ERR_CUTOFF, PI};
 
 use serde_derive::{Deserialize, Serialize};
 use std::fmt;
@@ -23,6 +23,9 @@ pub struct Polygon {
     pub jy: f64,
     pub vertices: PointVec2,
     pub num_vertices: usize,
+    /// Interior cut-outs, with anti-clockwise vertices
+    #[serde(default)]
+    pub holes: Vec<PointVec2>,
     pub line_array: LineVec,
 }
 
@@ -44,6 +47,7 @@ impl Default for Polygon {
             jy: 1.0,
             vertices,
             num_vertices: 4,
+            holes: Vec::new(),
             line_array,
         }
     }
@@ -55,18 +59,51 @@ impl Polygon {
     /// Vertices is an enum for generating either a regular polygon, or a custom polygon.
     ///
     /// For the former, `Vertices::
-    pub fn new<C>(center: C, alpha: Angle, jr: f64, phi: Angle, vertices: Vertices) -> Polygon
+    ///
+    /// The vertices are validated and reordered clockwise, see [`validate_vertices`],
+    /// so that a MagnetError is returned for degenerate or self-intersecting polygons.
+    pub fn new<C>(
+        center: C,
+        alpha: Angle,
+        jr: f64,
+        phi: Angle,
+        vertices: Vertices,
+    ) -> Result<Polygon, MagnetError>
+    where
+        C: GetCenter<Point2>,
+    {
+        Polygon::with_holes(center, alpha, jr, phi, vertices, Vec::new())
+    }
+
+    /// Creates a new Polygon with interior cut-outs, such as mounting holes or slots
+    ///
+    /// Each hole is a ring of vertices in either winding order, which must lie
+    /// inside the outer ring without touching it or any other hole. The hole
+    /// vertices are stored anti-clockwise, so that their edges are current
+    /// sheets with the opposite normal to those of the outer ring.
+    pub fn with_holes<C>(
+        center: C,
+        alpha: Angle,
+        jr: f64,
+        phi: Angle,
+        vertices: Vertices,
+        holes: Vec<PointVec2>,
+    ) -> Result<Polygon, MagnetError>
     where
         C: GetCenter<Point2>,
     {
         let phi_rad = phi.to_radians();
-        let returned_vert = generate_vertices_wrapper(vertices, &center.center(), &alpha).unwrap();
+        let returned_vert = generate_vertices_wrapper(vertices, &center.center(), &alpha)?;
+        let holes = validate_holes(&returned_vert, &holes)?;
         let num_vertices = returned_vert.x.len();
         let jx = jr * phi_rad.cos();
         let jy = jr * phi_rad.sin();
-        let (line_array, _, _) = generate_line_array(&returned_vert, &jx, &jy);
+        let (mut line_array, _, _) = generate_line_array(&returned_vert, &jx, &jy);
+        for hole in &holes {
+            line_array.extend(generate_line_array(hole, &jx, &jy).0);
+        }
 
-        Polygon {
+        Ok(Polygon {
             center: center.center(),
             alpha,
             jr,
@@ -75,8 +112,15 @@ impl Polygon {
             jy,
             vertices: returned_vert,
             num_vertices,
+            holes,
             line_array,
-        }
+        })
+    }
+
+    /// Returns the area of the polygon, less that of its holes
+    pub fn area(&self) -> f64 {
+        let ring_area = |vertices: &PointVec2| signed_area_2(&ring_points(vertices)).abs() / 2.0;
+        ring_area(&self.vertices) - self.holes.iter().map(ring_area).sum::<f64>()
     }
 }
 
@@ -166,17 +210,226 @@ fn regular_vertices(
     }
 }
 
-/// Returns the vertices of a polygon
+/// Returns the validated vertices of a polygon, in clockwise order
 pub fn generate_vertices_wrapper(
     vertex_wrapper: Vertices,
     center: &Point2,
     alpha: &Angle,
 ) -> Result<PointVec2, MagnetError> {
-    match vertex_wrapper {
-        Vertices::Regular(val, param) => Ok(regular_vertices(&val, center, &param, alpha)?),
+    let vertices = match vertex_wrapper {
+        Vertices::Regular(val, param) => regular_vertices(&val, center, &param, alpha)?,
+
+        Vertices::Custom(val) => val,
+    };
+    validate_vertices(&vertices)
+}
+
+/// Returns the vertices of a ring as a vector of points
+pub(crate) fn ring_points(vertices: &PointVec2) -> Vec<Point2> {
+    vertices
+        .x
+        .iter()
+        .zip(vertices.y.iter())
+        .map(|(x, y)| Point2::new(*x, *y))
+        .collect()
+}
+
+/// Returns twice the signed area of a polygon, which is positive for
+/// anti-clockwise vertices
+fn signed_area_2(vertices: &[Point2]) -> f64 {
+    let num_vertices = vertices.len();
+    (0..num_vertices)
+        .map(|i| {
+            let (start, end) = (vertices[i], vertices[(i + 1) % num_vertices]);
+            start.x * end.y - end.x * start.y
+        })
+        .sum()
+}
+
+/// Returns the cross product of `a - origin` and `b - origin`, which is
+/// positive if `b` lies to the left of the line from `origin` through `a`
+fn orientation(origin: &Point2, a: &Point2, b: &Point2) -> f64 {
+    (a.x - origin.x) * (b.y - origin.y) - (a.y - origin.y) * (b.x - origin.x)
+}
+
+/// Returns true if `point`, known to be collinear with the segment, lies within
+/// its bounding box
+fn on_segment(start: &Point2, end: &Point2, point: &Point2, tolerance: f64) -> bool {
+    point.x >= start.x.min(end.x) - tolerance
+        && point.x <= start.x.max(end.x) + tolerance
+        && point.y >= start.y.min(end.y) - tolerance
+        && point.y <= start.y.max(end.y) + tolerance
+}
+
+/// Returns true if two segments cross or touch, with `tolerance` the smallest
+/// significant length
+fn segments_intersect(
+    p_1: &Point2,
+    p_2: &Point2,
+    q_1: &Point2,
+    q_2: &Point2,
+    tolerance: f64,
+) -> bool {
+    let area_tolerance = tolerance * (*p_2 - *p_1).magnitude().max((*q_2 - *q_1).magnitude());
+    let sign = |value: f64| {
+        if value > area_tolerance {
+            1
+        } else if value < -area_tolerance {
+            -1
+        } else {
+            0
+        }
+    };
+    let d_1 = sign(orientation(q_1, q_2, p_1));
+    let d_2 = sign(orientation(q_1, q_2, p_2));
+    let d_3 = sign(orientation(p_1, p_2, q_1));
+    let d_4 = sign(orientation(p_1, p_2, q_2));
+
+    (d_1 * d_2 < 0 && d_3 * d_4 < 0)
+        || (d_1 == 0 && on_segment(q_1, q_2, p_1, tolerance))
+        || (d_2 == 0 && on_segment(q_1, q_2, p_2, tolerance))
+        || (d_3 == 0 && on_segment(p_1, p_2, q_1, tolerance))
+        || (d_4 == 0 && on_segment(p_1, p_2, q_2, tolerance))
+}
+
+/// Checks that vertices describe a simple polygon, and returns them in clockwise
+/// order.
+///
+/// The edges of a polygon are current sheets whose sign depends on the winding
+/// order, and the fields are only correct for clockwise vertices. Anti-clockwise
+/// vertices are therefore reversed, while the following return an error:
+///
+/// - fewer than 3 vertices
+/// - zero-length edges, i.e. repeated consecutive vertices
+/// - duplicate vertices
+/// - vertices collinear with their neighbours
+/// - self-intersections between non-adjacent edges
+///
+/// Indices in the errors refer to the vertices as passed in, with edge `i`
+/// joining vertex `i` to vertex `i + 1`. Lengths are compared relative to the
+/// size of the polygon.
+pub fn validate_vertices(vertices: &PointVec2) -> Result<PointVec2, MagnetError> {
+    let points = ring_points(vertices);
+    let num_vertices = points.len();
+    if num_vertices < 3 {
+        return Err(MagnetError::PolygonSideError());
+    }
+
+    let (mut min, mut max) = (points[0], points[0]);
+    for point in &points {
+        min = Point2::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point2::new(max.x.max(point.x), max.y.max(point.y));
+    }
+    let tolerance = ERR_CUTOFF * (max - min).magnitude();
+    let next = |i: usize| (i + 1) % num_vertices;
+
+    for i in 0..num_vertices {
+        if (points[next(i)] - points[i]).magnitude() <= tolerance {
+            return Err(MagnetError::PolygonZeroLengthEdgeError(i));
+        }
+    }
+
+    for i in 0..num_vertices {
+        for j in (i + 2)..num_vertices {
+            if (points[j] - points[i]).magnitude() <= tolerance {
+                return Err(MagnetError::PolygonDuplicateVertexError(i, j));
+            }
+        }
+    }
+
+    for i in 0..num_vertices {
+        let previous = points[(i + num_vertices - 1) % num_vertices];
+        let (to_previous, to_next) = (previous - points[i], points[next(i)] - points[i]);
+        let cross = to_previous.x * to_next.y - to_previous.y * to_next.x;
+        if cross.abs() <= ERR_CUTOFF * to_previous.magnitude() * to_next.magnitude() {
+            return Err(MagnetError::PolygonCollinearVertexError(i));
+        }
+    }
+
+    for i in 0..num_vertices {
+        // Skip the edges adjacent to edge i, which share a vertex with it
+        for j in (i + 2)..num_vertices {
+            if i == 0 && j == num_vertices - 1 {
+                continue;
+            }
+            if segments_intersect(
+                &points[i],
+                &points[next(i)],
+                &points[j],
+                &points[next(j)],
+                tolerance,
+            ) {
+                return Err(MagnetError::PolygonSelfIntersectionError(i, j));
+            }
+        }
+    }
 
-        Vertices::Custom(val) => Ok(val),
+    let mut points = points;
+    if signed_area_2(&points) > 0.0 {
+        points.reverse();
     }
+    let (x, y): (Vec<f64>, Vec<f64>) = points.iter().map(|point| point.as_tuple()).unzip();
+    Ok(PointVec2::new(x, y))
+}
+
+/// Checks the holes of a polygon with clockwise `vertices`, and returns them
+/// with anti-clockwise vertices.
+///
+/// Each hole is first checked by [`validate_vertices`]. A `PolygonHoleError`
+/// is returned if a hole is not strictly inside the outer ring, or if it
+/// crosses, touches, or contains another hole.
+pub fn validate_holes(
+    vertices: &PointVec2,
+    holes: &[PointVec2],
+) -> Result<Vec<PointVec2>, MagnetError> {
+    let outer = ring_points(vertices);
+    let rings = holes
+        .iter()
+        .map(|hole| validate_vertices(hole).map(|hole| ring_points(&hole)))
+        .collect::<Result<Vec<Vec<Point2>>, MagnetError>>()?;
+
+    let (mut min, mut max) = (outer[0], outer[0]);
+    for point in &outer {
+        min = Point2::new(min.x.min(point.x), min.y.min(point.y));
+        max = Point2::new(max.x.max(point.x), max.y.max(point.y));
+    }
+    let tolerance = ERR_CUTOFF * (max - min).magnitude();
+
+    let edges = |ring: &[Point2]| -> Vec<(Point2, Point2)> {
+        (0..ring.len())
+            .map(|i| (ring[i], ring[(i + 1) % ring.len()]))
+            .collect()
+    };
+    let rings_touch = |ring_1: &[Point2], ring_2: &[Point2]| {
+        edges(ring_1).iter().any(|(p_1, p_2)| {
+            edges(ring_2)
+                .iter()
+                .any(|(q_1, q_2)| segments_intersect(p_1, p_2, q_1, q_2, tolerance))
+        })
+    };
+    let ring_vec = |ring: &[Point2]| {
+        let (x, y): (Vec<f64>, Vec<f64>) = ring.iter().map(|point| point.as_tuple()).unzip();
+        PointVec2::new(x, y)
+    };
+
+    for (i, ring) in rings.iter().enumerate() {
+        if !point_in_polygon(vertices, &ring[0]) || rings_touch(ring, &outer) {
+            return Err(MagnetError::PolygonHoleError(i));
+        }
+        for (j, other) in rings.iter().enumerate().skip(i + 1) {
+            if rings_touch(ring, other)
+                || point_in_polygon(&ring_vec(other), &ring[0])
+                || point_in_polygon(&ring_vec(ring), &other[0])
+            {
+                return Err(MagnetError::PolygonHoleError(j));
+            }
+        }
+    }
+
+    Ok(rings
+        .iter()
+        .map(|ring| ring_vec(&ring.iter().rev().cloned().collect::<Vec<Point2>>()))
+        .collect())
 }
 
 // Return the magnetic field at a point due to a polygon
@@ -202,6 +455,70 @@ fn get_field_polygon(magnet: &Polygon, point: &Point2) -> Result<Point2, MagnetE
     Ok(field)
 }
 
+/// Returns the field gradient at a point due to a polygon, the sum of the
+/// gradients of its edges
+pub fn get_gradient_polygon(magnet: &Polygon, point: &Point2) -> Result<Gradient2, MagnetError> {
+    let mut gradient = Gradient2::zero();
+    for line in &magnet.line_array {
+        gradient += line.gradient(point)?;
+    }
+    Ok(gradient)
+}
+
+/// Returns the vector potential at a point due to a polygon, the sum of the
+/// potentials of its edges
+pub fn get_potential_polygon(magnet: &Polygon, point: &Point2) -> Result<f64, MagnetError> {
+    let mut potential = 0.0;
+    for line in &magnet.line_array {
+        potential += line.potential(point)?;
+    }
+    Ok(potential)
+}
+
+/// Returns true if a point lies inside a closed polygon, using the crossing
+/// number (even-odd) rule. Points lying on an edge are treated as outside.
+pub fn point_in_polygon(vertices: &PointVec2, point: &Point2) -> bool {
+    let num_vertices = vertices.x.len();
+    if num_vertices < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut previous = Point2::new(vertices.x[num_vertices - 1], vertices.y[num_vertices - 1]);
+
+    for (x, y) in vertices.x.iter().zip(vertices.y.iter()) {
+        let current = Point2::new(*x, *y);
+
+        // Reject points lying on the edge
+        let cross = (previous.x - current.x) * (point.y - current.y)
+            - (previous.y - current.y) * (point.x - current.x);
+        let between = (point.x - current.x) * (point.x - previous.x) <= 0.0
+            && (point.y - current.y) * (point.y - previous.y) <= 0.0;
+        if cross.abs() < ERR_CUTOFF && between {
+            return false;
+        }
+
+        // Count crossings of a ray cast from the point in +x
+        if (current.y > point.y) != (previous.y > point.y) {
+            let x_cross = current.x
+                + (point.y - current.y) * (previous.x - current.x) / (previous.y - current.y);
+            if point.x < x_cross {
+                inside = !inside;
+            }
+        }
+        previous = current;
+    }
+    inside
+}
+
+impl Contains<&Point2> for Polygon {
+    /// Returns true if a point lies inside the polygon, and outside its holes
+    fn contains(&self, point: &Point2) -> bool {
+        point_in_polygon(&self.vertices, point)
+            && !self.holes.iter().any(|hole| point_in_polygon(hole, point))
+    }
+}
+
 impl GetField<&Point2, Result<Point2, MagnetError>> for Polygon {
     /// Returns the magnetic field of a polygon magnet at a Point2 struct {x,y}
     fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
@@ -223,13 +540,57 @@ impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Polygon {
     }
 }
 
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Polygon {
+    /// Returns the field gradient of a polygon magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_polygon(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Polygon {
+    /// Returns the field gradient of a polygon magnet at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_polygon(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Polygon {
+    /// Returns the vector potential of a polygon magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_polygon(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Polygon {
+    /// Returns the vector potential of a polygon magnet at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_polygon(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
+    use crate::magnets::{surface_elements_2d, Magnet2D};
     use crate::{
+        points::Points,
         utils::comparison::{nearly_equal, nearly_equal_array},
-        PI_2,
+        I_2PI, PI_2,
     };
 
     #[test]
@@ -345,7 +706,8 @@ mod tests {
             1.0,
             Angle::Radians(PI_2),
             Vertices::Regular(4, PolyDimension::Side(2.0)),
-        );
+        )
+        .unwrap();
 
         println!("{}", magnet);
         let comp_vert = PointVec2 {
@@ -370,7 +732,8 @@ mod tests {
             1.0,
             Angle::Radians(PI_2),
             vertex_wrapper,
-        );
+        )
+        .unwrap();
 
         println!("{}", magnet);
         let comp_vert = PointVec2 {
@@ -381,4 +744,271 @@ mod tests {
         assert_eq!(magnet.vertices, comp_vert);
         assert!(nearly_equal(magnet.jr, 1.0));
     }
+
+    #[test]
+    fn test_anticlockwise_vertices_reversed() {
+        let clockwise = Polygon::new(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+            Vertices::Custom(PointVec2::new(vec![0.0, 1.0, 2.0], vec![0.0, 1.0, 0.0])),
+        )
+        .unwrap();
+        let anticlockwise = Polygon::new(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+            Vertices::Custom(PointVec2::new(vec![0.0, 2.0, 1.0], vec![0.0, 0.0, 1.0])),
+        )
+        .unwrap();
+
+        assert_eq!(
+            anticlockwise.vertices,
+            PointVec2::new(vec![1.0, 2.0, 0.0], vec![1.0, 0.0, 0.0])
+        );
+        for point in &[Point2::new(1.0, 0.3), Point2::new(-0.5, 2.0)] {
+            let field = clockwise.field(point).unwrap();
+            let comp_field = anticlockwise.field(point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-12);
+        }
+    }
+
+    /// Returns a square of side 2 with an off-centre rectangular hole
+    fn slotted_square(phi: f64) -> Result<Polygon, MagnetError> {
+        Polygon::with_holes(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Degrees(phi),
+            Vertices::Regular(4, PolyDimension::Side(2.0)),
+            vec![PointVec2::new(
+                vec![-0.5, 0.2, 0.2, -0.5],
+                vec![-0.6, -0.6, 0.4, 0.4],
+            )],
+        )
+    }
+
+    #[test]
+    fn test_polygon_with_hole() {
+        let magnet = slotted_square(40.0).unwrap();
+        let hole = Polygon::new(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Degrees(40.0),
+            Vertices::Custom(magnet.holes[0].clone()),
+        )
+        .unwrap();
+        let outer = Polygon::new(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Degrees(40.0),
+            Vertices::Custom(magnet.vertices.clone()),
+        )
+        .unwrap();
+
+        // The hole is stored anti-clockwise, and its field is subtracted
+        assert!(signed_area_2(&ring_points(&magnet.holes[0])) > 0.0);
+        assert!((magnet.area() - 3.3).abs() < 1e-12);
+        for point in &[
+            Point2::new(1.5, 0.3),
+            Point2::new(0.0, 0.0),
+            Point2::new(0.6, -0.7),
+        ] {
+            let field = magnet.field(point).unwrap();
+            let comp_field = outer.field(point).unwrap() - hole.field(point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-12);
+            let potential = magnet.potential(point).unwrap();
+            let comp_potential = outer.potential(point).unwrap() - hole.potential(point).unwrap();
+            assert!((potential - comp_potential).abs() < 1e-12);
+        }
+
+        assert!(magnet.contains(&Point2::new(0.6, -0.7)));
+        assert!(!magnet.contains(&Point2::new(0.0, 0.0)));
+        assert!(!magnet.contains(&Point2::new(1.5, 0.0)));
+    }
+
+    #[test]
+    fn test_hole_surface_charges() {
+        // Outside the magnet, B is the field of the surface charges, including
+        // those on the edges of the hole
+        let magnet = Magnet2D::Polygon(slotted_square(-70.0).unwrap());
+        let elements = surface_elements_2d(&magnet);
+        for point in &[Point2::new(1.5, 0.3), Point2::new(-0.1, 0.1)] {
+            let mut comp_field = Point2::zero();
+            for element in &elements {
+                let delta = *point - element.position;
+                comp_field += delta
+                    .scale(element.sigma() * element.length * I_2PI / delta.magnitude_squared());
+            }
+            let field = magnet.field(point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-6 * field.magnitude());
+        }
+    }
+
+    #[test]
+    fn test_invalid_holes() {
+        let with_hole = |x: Vec<f64>, y: Vec<f64>| {
+            Polygon::with_holes(
+                (0.0, 0.0),
+                Angle::Radians(0.0),
+                1.0,
+                Angle::Radians(0.0),
+                Vertices::Regular(4, PolyDimension::Side(2.0)),
+                vec![
+                    PointVec2::new(vec![-0.5, 0.0, 0.0, -0.5], vec![-0.5, -0.5, 0.5, 0.5]),
+                    PointVec2::new(x, y),
+                ],
+            )
+        };
+
+        // Outside, crossing the outer ring, overlapping and containing the first hole
+        for (x, y) in [
+            (vec![2.0, 3.0, 3.0], vec![0.0, 0.0, 1.0]),
+            (vec![0.5, 1.5, 0.5], vec![0.0, 0.0, 0.5]),
+            (vec![-0.2, 0.5, 0.5], vec![0.0, 0.0, 0.5]),
+            (vec![-0.9, 0.9, 0.0], vec![-0.9, -0.9, 0.9]),
+        ] {
+            assert!(matches!(
+                with_hole(x, y),
+                Err(MagnetError::PolygonHoleError(1))
+            ));
+        }
+        assert!(matches!(
+            with_hole(vec![0.5, 0.8, 0.65], vec![0.0, 0.0, 0.0]),
+            Err(MagnetError::PolygonCollinearVertexError(0))
+        ));
+        assert!(with_hole(vec![0.2, 0.8, 0.5], vec![0.0, 0.0, 0.5]).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_vertices() {
+        let validate = |x: Vec<f64>, y: Vec<f64>| validate_vertices(&PointVec2::new(x, y));
+
+        assert!(matches!(
+            validate(vec![0.0, 1.0], vec![0.0, 1.0]),
+            Err(MagnetError::PolygonSideError())
+        ));
+        assert!(matches!(
+            validate(vec![0.0, 0.0, 1.0, 1.0], vec![0.0, 1.0, 1.0, 1.0]),
+            Err(MagnetError::PolygonZeroLengthEdgeError(2))
+        ));
+        // A square visiting one corner twice
+        assert!(matches!(
+            validate(
+                vec![0.0, 0.0, 1.0, 0.0, 2.0, 2.0],
+                vec![0.0, 1.0, 1.0, 0.0, 0.0, -1.0]
+            ),
+            Err(MagnetError::PolygonDuplicateVertexError(0, 3))
+        ));
+        assert!(matches!(
+            validate(vec![0.0, 0.0, 0.0, 1.0], vec![0.0, 0.5, 1.0, 0.0]),
+            Err(MagnetError::PolygonCollinearVertexError(1))
+        ));
+        // A bow tie, whose halves would have fields of opposite signs
+        assert!(matches!(
+            validate(vec![0.0, 1.0, 1.0, 0.0], vec![0.0, 1.0, 0.0, 1.0]),
+            Err(MagnetError::PolygonSelfIntersectionError(0, 2))
+        ));
+        assert!(Polygon::new(
+            (0.0, 0.0),
+            Angle::Radians(0.0),
+            1.0,
+            Angle::Radians(0.0),
+            Vertices::Regular(4, PolyDimension::Radius(0.0)),
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_polygon_gradient() {
+        let magnet = Polygon::new(
+            (0.2, 0.1),
+            Angle::Degrees(10.0),
+            1.0,
+            Angle::Degrees(60.0),
+            Vertices::Regular(5, PolyDimension::Side(1.0)),
+        )
+        .unwrap();
+        let point = Point2::new(1.3, -0.4);
+        let gradient = magnet.gradient(&point).unwrap();
+
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        let field_dx = (magnet.field(&(point + d_x)).unwrap()
+            - magnet.field(&(point - d_x)).unwrap())
+        .scale(0.5 / delta);
+        let field_dy = (magnet.field(&(point + d_y)).unwrap()
+            - magnet.field(&(point - d_y)).unwrap())
+        .scale(0.5 / delta);
+
+        assert!((gradient.xx - field_dx.x).abs() < 1e-8);
+        assert!((gradient.yx - field_dx.y).abs() < 1e-8);
+        assert!((gradient.xy - field_dy.x).abs() < 1e-8);
+        assert!((gradient.yy - field_dy.y).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_polygon_potential() {
+        let magnet = Polygon::new(
+            (0.2, 0.1),
+            Angle::Degrees(10.0),
+            1.0,
+            Angle::Degrees(60.0),
+            Vertices::Regular(5, PolyDimension::Side(1.0)),
+        )
+        .unwrap();
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        for point in &[Point2::new(1.3, -0.4), Point2::new(0.3, 0.2)] {
+            let field = magnet.field(point).unwrap();
+            let potential_dx = (magnet.potential(&(*point + d_x)).unwrap()
+                - magnet.potential(&(*point - d_x)).unwrap())
+                / (2.0 * delta);
+            let potential_dy = (magnet.potential(&(*point + d_y)).unwrap()
+                - magnet.potential(&(*point - d_y)).unwrap())
+                / (2.0 * delta);
+
+            assert!((field.x - potential_dy).abs() < 1e-8);
+            assert!((field.y + potential_dx).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        // Concave "L" shape
+        let vertices = PointVec2::new(
+            vec![0.0, 2.0, 2.0, 1.0, 1.0, 0.0],
+            vec![0.0, 0.0, 1.0, 1.0, 2.0, 2.0],
+        );
+        assert!(point_in_polygon(&vertices, &Point2::new(0.5, 0.5)));
+        assert!(point_in_polygon(&vertices, &Point2::new(1.5, 0.5)));
+        assert!(point_in_polygon(&vertices, &Point2::new(0.5, 1.5)));
+        assert!(!point_in_polygon(&vertices, &Point2::new(1.5, 1.5)));
+        assert!(!point_in_polygon(&vertices, &Point2::new(-0.5, 0.5)));
+
+        // On an edge and on a vertex
+        assert!(!point_in_polygon(&vertices, &Point2::new(1.0, 0.0)));
+        assert!(!point_in_polygon(&vertices, &Point2::new(1.0, 1.0)));
+    }
+
+    #[test]
+    fn test_polygon_contains() {
+        let magnet = Polygon::new(
+            (1.0, 1.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Vertices::Regular(6, PolyDimension::Radius(1.0)),
+        )
+        .unwrap();
+        assert!(magnet.contains(&Point2::new(1.0, 1.0)));
+        assert!(magnet.contains(&Point2::new(1.8, 1.0)));
+        assert!(!magnet.contains(&Point2::new(2.2, 1.0)));
+    }
 }
@@ -0,0 +1,95 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Vector potential of 2D magnets
+//!
+//! In 2D the magnetic vector potential only has an out-of-plane component,
+//! $`\mathbf{B} = \nabla \times A_z \mathbf{\hat{z}} = (\partial_y A_z, -\partial_x A_z)`$.
+//! Field lines are therefore the contours of $`A_z`$, and the flux per unit
+//! length passing between two points is the difference of $`A_z`$ at those points.
+
+use crate::magnets::{GetPotential, Magnet2D};
+use crate::points::Point2;
+use crate::MagnetError;
+
+/// Loops over every 2D Magnet and gets the vector potential at a point `point`
+pub fn loop_potential_2d(magnet_list: &[Magnet2D], point: &Point2) -> Result<f64, MagnetError> {
+    let mut local_potential = 0.0;
+
+    for mag in magnet_list {
+        match mag {
+            Magnet2D::Rectangle(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::Circle(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::Polygon(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::Wire(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::Ring(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::ArcSegment(magnet) => local_potential += magnet.potential(point)?,
+            Magnet2D::Ellipse(magnet) => local_potential += magnet.potential(point)?,
+        }
+    }
+    Ok(local_potential)
+}
+
+/// Returns the vector potential due to an array of magnets `magnet_list`, at a
+/// point `point`
+pub fn get_potential_2d(magnet_list: &[Magnet2D], point: (&f64, &f64)) -> Result<f64, MagnetError> {
+    let point = Point2::new(*point.0, *point.1);
+    loop_potential_2d(magnet_list, &point)
+}
+
+/// Returns the flux per unit length crossing the straight line from `start` to
+/// `stop`, $`A_z(\mathbf{r}_1) - A_z(\mathbf{r}_2)`$, positive when the field
+/// crosses the line from right to left when looking from `start` to `stop`.
+pub fn get_flux_2d(
+    magnet_list: &[Magnet2D],
+    start: &Point2,
+    stop: &Point2,
+) -> Result<f64, MagnetError> {
+    Ok(loop_potential_2d(magnet_list, start)? - loop_potential_2d(magnet_list, stop)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::{loop_field_2d, Circle, Rectangle};
+    use crate::points::{Points, Points2};
+    use crate::utils::conversions::Angle;
+
+    #[test]
+    fn test_flux_2d() {
+        let magnet_list = vec![
+            Magnet2D::Rectangle(Rectangle::new(
+                1.0,
+                2.0,
+                (0.0, 0.0),
+                Angle::Degrees(30.0),
+                1.0,
+                Angle::Degrees(60.0),
+            )),
+            Magnet2D::Circle(Circle::new(
+                0.5,
+                (2.0, 0.5),
+                Angle::Degrees(0.0),
+                1.0,
+                Angle::Degrees(-20.0),
+            )),
+        ];
+        let start = Point2::new(1.0, -2.0);
+        let stop = Point2::new(1.5, 2.0);
+        let flux = get_flux_2d(&magnet_list, &start, &stop).unwrap();
+
+        // Midpoint rule for the integral of B·n along the line
+        let num_points = 2000;
+        let delta = (stop - start).scale(1.0 / num_points as f64);
+        let normal = Point2::new(-delta.y, delta.x);
+        let mut comp_flux = 0.0;
+        for i in 0..num_points {
+            let point = start + delta.scale(i as f64 + 0.5);
+            comp_flux += loop_field_2d(&magnet_list, &point).unwrap().dot(&normal);
+        }
+
+        assert!((flux - comp_flux).abs() < 1e-6);
+    }
+}
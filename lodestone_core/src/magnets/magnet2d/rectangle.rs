@@ -10,10 +10,11 @@ use serde_derive::{Deserialize, Serialize};
 use std::fmt;
 // use std::ops::{Add, Mul};
 
-use crate::magnets::{GetCenter, GetField, MagnetTrait};
-use crate::points::{Point2, PolarPoint};
+use crate::magnets::magnet2d::Gradient2;
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential, MagnetTrait};
+use crate::points::{Point2, Points2, PolarPoint};
 use crate::utils::conversions::Angle;
-use crate::MagnetError;
+use crate::{MagnetError, FP_CUTOFF};
 
 /// A 2D rectangular magnet
 ///
@@ -116,6 +117,36 @@ impl Rectangle {
             b: height / 2.0,
         }
     }
+
+    /// Constructor for a Rectangle rotated by `alpha`, with magnetisation
+    /// vector `magnetisation` in the global frame
+    pub fn from_global_magnetisation<C>(
+        width: f64,
+        height: f64,
+        center: C,
+        alpha: Angle,
+        magnetisation: &Point2,
+    ) -> Rectangle
+    where
+        C: GetCenter<Point2>,
+    {
+        // The rectangle is rotated by -alpha, so rotate J into its local frame by alpha
+        let local = magnetisation.rotate(&alpha.to_radians());
+        Rectangle::new(
+            width,
+            height,
+            center,
+            alpha,
+            local.magnitude(),
+            Angle::Radians(local.y.atan2(local.x)),
+        )
+    }
+
+    /// Returns the magnetisation vector $`\mathbf{J}`$ in the global frame,
+    /// rotated back from the local frame by -alpha
+    pub fn global_magnetisation(&self) -> Point2 {
+        Point2::new(self.jx, self.jy).rotate(&(-self.alpha.to_radians()))
+    }
 }
 
 /// Implements Display for Rectangle magnets.
@@ -186,6 +217,17 @@ impl MagnetTrait<[f64; 2], Point2, [f64; 2], PolarPoint> for Rectangle {
     }
 }
 
+impl Contains<&Point2> for Rectangle {
+    /// Returns true if a point lies inside the rectangle
+    fn contains(&self, point: &Point2) -> bool {
+        let mut local_point = *point - self.center;
+        if self.alpha.to_radians().abs() > FP_CUTOFF {
+            local_point = local_point.rotate(&self.alpha.to_radians());
+        }
+        local_point.x.abs() < self.a && local_point.y.abs() < self.b
+    }
+}
+
 impl GetField<&Point2, Result<Point2, MagnetError>> for Rectangle {
     /// Returns the magnetic field of a rectangular magnet at a Point2 struct {x,y}
     fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
@@ -207,6 +249,48 @@ impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Rectangle {
     }
 }
 
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Rectangle {
+    /// Returns the field gradient of a rectangular magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_rectangle(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Rectangle {
+    /// Returns the field gradient of a rectangular magnet at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_rectangle(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Rectangle {
+    /// Returns the vector potential of a rectangular magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_rectangle(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Rectangle {
+    /// Returns the vector potential of a rectangular magnet at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_rectangle(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::utils::comparison::nearly_equal;
@@ -8,7 +8,7 @@ Copyright 2021 Peter Dunne */
 //! This submodule exposes
 
 #![allow(clippy::many_single_char_names)]
-use crate::magnets::magnet2d::Rectangle;
+use crate::magnets::magnet2d::{sheet_potential, Gradient2, Rectangle};
 use crate::points::{Point2, Points2};
 use crate::{MagnetError, FP_CUTOFF, I_2PI, I_4PI, M2_PI};
 
@@ -162,11 +162,126 @@ fn field_in_y_for_y_mag(x: f64, y: f64, a: f64, b: f64, j: f64) -> Result<f64, M
     Ok(j * I_2PI * (top_1.atan2(bottom_1) - top_2.atan2(bottom_2)))
 }
 
+/// Returns the field gradient tensor due to a rectangle of width `2a`, height `2b`
+/// centered at the origin, with an arbitrary magnetisation
+///
+/// Singular points, on the corners of the magnet, are bound to 0.0
+pub fn get_gradient_rectangle(
+    magnet: &Rectangle,
+    point: &Point2,
+) -> Result<Gradient2, MagnetError> {
+    let mut gradient = Gradient2::zero();
+
+    // Translate and rotate into local coordinates
+    let mut local_point = *point - magnet.center;
+
+    if magnet.alpha.to_radians().abs() > FP_CUTOFF {
+        local_point = local_point.rotate(&magnet.alpha.to_radians());
+    }
+
+    if (magnet.jx / magnet.jr).abs() > FP_CUTOFF {
+        gradient += gradient_for_x_mag(local_point.x, local_point.y, magnet.a, magnet.b, magnet.jx);
+    }
+
+    if (magnet.jy / magnet.jr).abs() > FP_CUTOFF {
+        gradient += gradient_for_y_mag(local_point.x, local_point.y, magnet.a, magnet.b, magnet.jy);
+    }
+
+    if magnet.alpha.to_radians().abs() > FP_CUTOFF {
+        let reverse_alpha = M2_PI - magnet.alpha.to_radians();
+        gradient = gradient.rotate(&reverse_alpha);
+    }
+
+    Ok(gradient.bind_singular())
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to a rectangle of width
+/// `2a`, height `2b`, with an arbitrary magnetisation.
+///
+/// The magnet is replaced by the surface currents $`K = \mathbf{J} \times \mathbf{\hat{n}}`$
+/// on its four faces, each of which is a current sheet.
+pub fn get_potential_rectangle(magnet: &Rectangle, point: &Point2) -> Result<f64, MagnetError> {
+    // Translate and rotate into local coordinates
+    let mut local_point = *point - magnet.center;
+
+    if magnet.alpha.to_radians().abs() > FP_CUTOFF {
+        local_point = local_point.rotate(&magnet.alpha.to_radians());
+    }
+    let (x, y) = (local_point.x, local_point.y);
+    let (a, b) = (magnet.a, magnet.b);
+
+    // Faces at x = ±a carry K = ∓Jy, faces at y = ±b carry K = ±Jx
+    let potential = sheet_potential(&(x - a), &y, &b, &(-magnet.jy))?
+        + sheet_potential(&(x + a), &y, &b, &magnet.jy)?
+        + sheet_potential(&(y - b), &x, &a, &magnet.jx)?
+        + sheet_potential(&(y + b), &x, &a, &(-magnet.jx))?;
+
+    Ok(potential)
+}
+
+/// Returns the gradient tensor due to a rectangle magnetised in x, from the
+/// derivatives of `field_in_x_for_x_mag` and `field_in_y_for_x_mag`
+#[allow(clippy::many_single_char_names)]
+fn gradient_for_x_mag(x: f64, y: f64, a: f64, b: f64, j: f64) -> Gradient2 {
+    let mut gradient = Gradient2::zero();
+
+    // Bx is the sum of atan2(2 a s, x^2 - a^2 + s^2), for s = b + y and s = b - y
+    for &(s, ds_dy) in &[(b + y, 1.0), (b - y, -1.0)] {
+        let s_sq = s.powi(2);
+        let denominator = ((x - a).powi(2) + s_sq) * ((x + a).powi(2) + s_sq);
+        gradient.xx += -4.0 * a * x * s / denominator;
+        gradient.xy += ds_dy * 2.0 * a * (x.powi(2) - a.powi(2) - s_sq) / denominator;
+    }
+    gradient.xx *= j * I_2PI;
+    gradient.xy *= j * I_2PI;
+
+    let top_1 = (x - a).powi(2) + (y - b).powi(2);
+    let bottom_1 = (x + a).powi(2) + (y - b).powi(2);
+    let top_2 = (x - a).powi(2) + (y + b).powi(2);
+    let bottom_2 = (x + a).powi(2) + (y + b).powi(2);
+
+    gradient.yx =
+        -j * I_2PI * ((x - a) / top_1 - (x + a) / bottom_1 - (x - a) / top_2 + (x + a) / bottom_2);
+    gradient.yy =
+        -j * I_2PI * ((y - b) / top_1 - (y - b) / bottom_1 - (y + b) / top_2 + (y + b) / bottom_2);
+
+    gradient
+}
+
+/// Returns the gradient tensor due to a rectangle magnetised in y, from the
+/// derivatives of `field_in_x_for_y_mag` and `field_in_y_for_y_mag`
+#[allow(clippy::many_single_char_names)]
+fn gradient_for_y_mag(x: f64, y: f64, a: f64, b: f64, j: f64) -> Gradient2 {
+    let mut gradient = Gradient2::zero();
+
+    let top_1 = (x + a).powi(2) + (y - b).powi(2);
+    let bottom_1 = (x + a).powi(2) + (y + b).powi(2);
+    let top_2 = (x - a).powi(2) + (y - b).powi(2);
+    let bottom_2 = (x - a).powi(2) + (y + b).powi(2);
+
+    gradient.xx =
+        j * I_2PI * ((x + a) / top_1 - (x + a) / bottom_1 - (x - a) / top_2 + (x - a) / bottom_2);
+    gradient.xy =
+        j * I_2PI * ((y - b) / top_1 - (y + b) / bottom_1 - (y - b) / top_2 + (y + b) / bottom_2);
+
+    // By is the difference of atan2(2 b p, p^2 + y^2 - b^2), for p = x + a and p = x - a
+    for &(p, sign) in &[(x + a, 1.0), (x - a, -1.0)] {
+        let p_sq = p.powi(2);
+        let denominator = (p_sq + (y - b).powi(2)) * (p_sq + (y + b).powi(2));
+        gradient.yx += sign * 2.0 * b * (y.powi(2) - b.powi(2) - p_sq) / denominator;
+        gradient.yy += sign * -4.0 * b * p * y / denominator;
+    }
+    gradient.yx *= j * I_2PI;
+    gradient.yy *= j * I_2PI;
+
+    gradient
+}
+
 #[cfg(test)]
 mod tests {
     use crate::magnets::magnet2d::rectangle_field::*;
     use crate::magnets::magnet2d::Rectangle;
-    use crate::points::Point2;
+    use crate::points::{Point2, Points};
     use crate::utils::comparison::nearly_equal;
     use crate::utils::conversions::Angle;
     use crate::PI;
@@ -287,4 +402,81 @@ mod tests {
         assert!(nearly_equal(field.x, comp_field.x));
         assert!(nearly_equal(field.y, comp_field.y));
     }
+
+    /// Compares the gradient to central differences of the field
+    fn check_gradient(magnet: &Rectangle, point: &Point2) {
+        let gradient = get_gradient_rectangle(magnet, point).unwrap();
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        let field_dx = (get_field_rectangle(magnet, &(*point + d_x)).unwrap()
+            - get_field_rectangle(magnet, &(*point - d_x)).unwrap())
+        .scale(0.5 / delta);
+        let field_dy = (get_field_rectangle(magnet, &(*point + d_y)).unwrap()
+            - get_field_rectangle(magnet, &(*point - d_y)).unwrap())
+        .scale(0.5 / delta);
+
+        assert!((gradient.xx - field_dx.x).abs() < 1e-8);
+        assert!((gradient.yx - field_dx.y).abs() < 1e-8);
+        assert!((gradient.xy - field_dy.x).abs() < 1e-8);
+        assert!((gradient.yy - field_dy.y).abs() < 1e-8);
+    }
+
+    #[test]
+    fn gradient_matches_field() {
+        for &(alpha, phi) in &[(0.0, 0.0), (0.0, 90.0), (25.0, 40.0), (-60.0, 200.0)] {
+            let magnet = Rectangle::new(
+                1.0,
+                0.6,
+                Point2::new(0.1, -0.2),
+                Angle::Degrees(alpha),
+                1.0,
+                Angle::Degrees(phi),
+            );
+            check_gradient(&magnet, &Point2::new(0.9, 0.4));
+            // Inside the magnet
+            check_gradient(&magnet, &Point2::new(0.2, -0.1));
+        }
+    }
+
+    #[test]
+    fn gradient_at_corner_is_bound() {
+        let magnet = Rectangle::new(
+            1.0,
+            1.0,
+            Point2::new(0.0, 0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(45.0),
+        );
+        let gradient = get_gradient_rectangle(&magnet, &Point2::new(0.5, 0.5)).unwrap();
+        assert_eq!(gradient, Gradient2::zero());
+    }
+
+    #[test]
+    fn potential_curl_matches_field() {
+        let magnet = Rectangle::new(
+            1.0,
+            0.6,
+            Point2::new(0.1, -0.2),
+            Angle::Degrees(25.0),
+            1.0,
+            Angle::Degrees(40.0),
+        );
+        let delta = 1e-6;
+        let d_x = Point2::new(delta, 0.0);
+        let d_y = Point2::new(0.0, delta);
+        for point in &[Point2::new(0.9, 0.4), Point2::new(0.2, -0.1)] {
+            let field = get_field_rectangle(&magnet, point).unwrap();
+            let potential_dx = (get_potential_rectangle(&magnet, &(*point + d_x)).unwrap()
+                - get_potential_rectangle(&magnet, &(*point - d_x)).unwrap())
+                / (2.0 * delta);
+            let potential_dy = (get_potential_rectangle(&magnet, &(*point + d_y)).unwrap()
+                - get_potential_rectangle(&magnet, &(*point - d_y)).unwrap())
+                / (2.0 * delta);
+
+            assert!((field.x - potential_dy).abs() < 1e-8);
+            assert!((field.y + potential_dx).abs() < 1e-8);
+        }
+    }
 }
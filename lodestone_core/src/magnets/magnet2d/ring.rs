@@ -0,0 +1,237 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::ring_field::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::utils::conversions::Angle;
+
+use crate::magnets::magnet2d::{Circle, Gradient2};
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential};
+use crate::points::{Point2, Points, Points2};
+use crate::{MagnetError, PI};
+use std::fmt;
+
+/// Enum for the magnetisation pattern of a ring magnet
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RingMagnetisation {
+    /// Uniform magnetisation at an angle phi to the x-axis
+    Diametric,
+    /// Radial magnetisation, outwards for a positive jr
+    Radial,
+    /// Azimuthal magnetisation, anti-clockwise for a positive jr
+    Azimuthal,
+}
+
+/// A 2D ring (annulus) magnet
+///
+/// Arguments using `new()` method:
+///     - inner_radius (f64): radius of the bore
+///     - outer_radius (f64): outer radius of the ring
+///     - center (Point2): magnet center
+///     - alpha (f64): rotation of the magnetisation, as for a Circle
+///     - jr (f64): remnant magnetisation in T
+///     - phi (f64): angle of a diametric magnetisation w.r.t. x-axis
+///     - magnetisation (RingMagnetisation): diametric, radial or azimuthal
+///
+/// The default method generates a ring of radii 0.5 and 1.0, centred at
+/// (0.0, 0.0), magnetised diametrically in y with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::{Ring, RingMagnetisation};
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = Ring::default();
+/// println!("Magnet 1:{}", magnet_1);
+/// let magnet_2 = Ring::new(0.5, 1.0, (0.0, -1.0), Angle::Degrees(0.0), 1.0, Angle::Degrees(0.0), RingMagnetisation::Radial);
+/// println!("Magnet 2:{}", magnet_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Ring {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub center: Point2,
+    pub alpha: Angle,
+    pub jr: f64,
+    pub phi: Angle,
+    pub magnetisation: RingMagnetisation,
+}
+
+impl Default for Ring {
+    /// Default method for Ring.
+    ///
+    /// Generates a ring magnet of radii 0.5 and 1.0, centred at (0,0),
+    /// with a diametric magnetisation of 1 tesla in y
+    fn default() -> Self {
+        Ring {
+            inner_radius: 0.5,
+            outer_radius: 1.0,
+            center: Point2::new(0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            jr: 1.0,
+            phi: Angle::Degrees(90.0),
+            magnetisation: RingMagnetisation::Diametric,
+        }
+    }
+}
+
+impl Ring {
+    /// Constructor for 2D Ring magnet
+    pub fn new<C>(
+        inner_radius: f64,
+        outer_radius: f64,
+        center: C,
+        alpha: Angle,
+        jr: f64,
+        phi: Angle,
+        magnetisation: RingMagnetisation,
+    ) -> Ring
+    where
+        C: GetCenter<Point2>,
+    {
+        Ring {
+            inner_radius,
+            outer_radius,
+            center: center.center(),
+            alpha,
+            jr,
+            phi,
+            magnetisation,
+        }
+    }
+
+    /// Returns the solid rod filling the outer radius, with the same diametric magnetisation
+    pub fn outer_circle(&self) -> Circle {
+        Circle::new(
+            self.outer_radius,
+            self.center,
+            self.alpha,
+            self.jr,
+            self.phi,
+        )
+    }
+
+    /// Returns the solid rod filling the bore, with the same diametric magnetisation
+    pub fn inner_circle(&self) -> Circle {
+        Circle::new(
+            self.inner_radius,
+            self.center,
+            self.alpha,
+            self.jr,
+            self.phi,
+        )
+    }
+
+    /// Returns the area of the ring
+    pub fn area(&self) -> f64 {
+        PI * (self.outer_radius.powi(2) - self.inner_radius.powi(2))
+    }
+
+    /// Returns the magnetisation vector $`\mathbf{J}`$ in tesla at a point
+    /// within the ring, in the global frame
+    pub fn magnetisation_at(&self, point: &Point2) -> Point2 {
+        let radial = (*point - self.center).unit();
+        match self.magnetisation {
+            RingMagnetisation::Diametric => {
+                let angle = self.phi.to_radians() + self.alpha.to_radians();
+                Point2::new(angle.cos(), angle.sin()).scale(self.jr)
+            }
+            RingMagnetisation::Radial => radial.scale(self.jr),
+            RingMagnetisation::Azimuthal => Point2::new(-radial.y, radial.x).scale(self.jr),
+        }
+    }
+}
+
+/// Implements Display for Ring magnets.
+///
+/// Example:
+///
+impl fmt::Display for Ring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: ({}, {})\tc: {},\talpha:{}\tJ ({:.3}, {:.3}, {:?})]",
+            self.inner_radius,
+            self.outer_radius,
+            self.center,
+            self.alpha.to_degrees(),
+            self.jr,
+            self.phi.to_degrees(),
+            self.magnetisation
+        )
+    }
+}
+
+impl Contains<&Point2> for Ring {
+    /// Returns true if a point lies inside the material of the ring
+    fn contains(&self, point: &Point2) -> bool {
+        let dist_sq = (*point - self.center).magnitude_squared();
+        dist_sq > self.inner_radius.powi(2) && dist_sq < self.outer_radius.powi(2)
+    }
+}
+
+impl GetField<&Point2, Result<Point2, MagnetError>> for Ring {
+    /// Returns the magnetic field of a ring magnet at a Point2 struct {x,y}
+    fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
+        get_field_ring(self, point)
+    }
+}
+
+impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Ring {
+    /// Returns the magnetic field of a ring magnet at a 2-element tuple (x,y)
+    fn field(&self, point: &(f64, f64)) -> Result<(f64, f64), MagnetError> {
+        let field_vec = get_field_ring(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok((field_vec.x, field_vec.y))
+    }
+}
+
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Ring {
+    /// Returns the field gradient of a ring magnet at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_ring(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Ring {
+    /// Returns the field gradient of a ring magnet at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_ring(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Ring {
+    /// Returns the vector potential of a ring magnet at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_ring(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Ring {
+    /// Returns the vector potential of a ring magnet at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_ring(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
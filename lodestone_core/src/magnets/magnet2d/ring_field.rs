@@ -0,0 +1,189 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Fields of ring magnets
+//!
+//! A diametrically magnetised ring is the difference of two uniformly
+//! magnetised rods, so its field is the difference of their `Circle` solutions.
+//!
+//! A radially magnetised ring, $`\mathbf{J} = J \mathbf{\hat{r}}`$, is
+//! equivalent to surface charges $`\pm J`$ on its outer and inner surfaces,
+//! and a volume charge $`-\nabla \cdot \mathbf{J} = -J / r`$. By Gauss's law the
+//! charge enclosed within a radius $`r`$ is $`-2 \pi J r`$ inside the ring, and
+//! zero elsewhere, so $`\mu_0 \mathbf{H} = -\mathbf{J}`$ in the ring and
+//! $`\mathbf{B} = \mu_0 \mathbf{H} + \mathbf{J}`$ vanishes everywhere.
+//!
+//! An azimuthally magnetised ring, $`\mathbf{J} = J \boldsymbol{\hat{\phi}}`$,
+//! has neither volume nor surface charges, so $`\mathbf{H} = 0`$ and the field
+//! $`\mathbf{B} = \mathbf{J}`$ is confined to the ring.
+
+use crate::magnets::magnet2d::{
+    get_field_circle, get_gradient_circle, get_potential_circle, Gradient2, Ring, RingMagnetisation,
+};
+use crate::points::{Point2, Points, Points2};
+use crate::MagnetError;
+
+/// Returns the magnetic field vector due to a ring magnet at a point `point`
+pub fn get_field_ring(magnet: &Ring, point: &Point2) -> Result<Point2, MagnetError> {
+    match magnet.magnetisation {
+        RingMagnetisation::Diametric => Ok(get_field_circle(&magnet.outer_circle(), point)?
+            - get_field_circle(&magnet.inner_circle(), point)?),
+        RingMagnetisation::Radial => Ok(Point2::zero()),
+        RingMagnetisation::Azimuthal => {
+            let local_point = *point - magnet.center;
+            let dist = local_point.magnitude();
+            if dist > magnet.inner_radius && dist < magnet.outer_radius {
+                Ok(Point2::new(-local_point.y, local_point.x).scale(magnet.jr / dist))
+            } else {
+                Ok(Point2::zero())
+            }
+        }
+    }
+}
+
+/// Returns the field gradient tensor due to a ring magnet at a point `point`
+pub fn get_gradient_ring(magnet: &Ring, point: &Point2) -> Result<Gradient2, MagnetError> {
+    match magnet.magnetisation {
+        RingMagnetisation::Diametric => {
+            let outer = get_gradient_circle(&magnet.outer_circle(), point)?;
+            let inner = get_gradient_circle(&magnet.inner_circle(), point)?;
+            Ok(Gradient2::new(
+                outer.xx - inner.xx,
+                outer.xy - inner.xy,
+                outer.yx - inner.yx,
+                outer.yy - inner.yy,
+            ))
+        }
+        RingMagnetisation::Radial => Ok(Gradient2::zero()),
+        RingMagnetisation::Azimuthal => {
+            let local_point = *point - magnet.center;
+            let dist = local_point.magnitude();
+            if dist > magnet.inner_radius && dist < magnet.outer_radius {
+                let prefac = magnet.jr / dist.powi(3);
+                let (x, y) = local_point.as_tuple();
+                Ok(Gradient2::new(
+                    prefac * x * y,
+                    -prefac * x * x,
+                    prefac * y * y,
+                    -prefac * x * y,
+                ))
+            } else {
+                Ok(Gradient2::zero())
+            }
+        }
+    }
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to a ring magnet at
+/// a point `point`
+pub fn get_potential_ring(magnet: &Ring, point: &Point2) -> Result<f64, MagnetError> {
+    match magnet.magnetisation {
+        RingMagnetisation::Diametric => Ok(get_potential_circle(&magnet.outer_circle(), point)?
+            - get_potential_circle(&magnet.inner_circle(), point)?),
+        RingMagnetisation::Radial => Ok(0.0),
+        RingMagnetisation::Azimuthal => {
+            // B = J in the ring is -dA/dr, taking A = 0 outside the ring
+            let dist = (*point - magnet.center)
+                .magnitude()
+                .max(magnet.inner_radius)
+                .min(magnet.outer_radius);
+            Ok(magnet.jr * (magnet.outer_radius - dist))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::test_utils::{assert_derivatives, polygon_from_ring};
+    use crate::magnets::{loop_field_2d, Magnet2D};
+    use crate::points::Points;
+    use crate::utils::conversions::Angle;
+    use crate::M2_PI;
+
+    fn ring(magnetisation: RingMagnetisation) -> Ring {
+        Ring::new(
+            0.5,
+            1.0,
+            (0.2, -0.1),
+            Angle::Degrees(20.0),
+            1.2,
+            Angle::Degrees(35.0),
+            magnetisation,
+        )
+    }
+
+    /// Approximates a ring by trapezoidal segments, each uniformly magnetised
+    /// as at its center
+    fn segmented_ring(magnet: &Ring, count: usize) -> Vec<Magnet2D> {
+        let d_theta = M2_PI / count as f64;
+        (0..count)
+            .map(|i| {
+                let theta = (i as f64 + 0.5) * d_theta;
+                let vertex = |r: f64, angle: f64| {
+                    magnet.center + Point2::new(angle.cos(), angle.sin()).scale(r)
+                };
+                let vertices = [
+                    vertex(magnet.inner_radius, theta - d_theta / 2.0),
+                    vertex(magnet.inner_radius, theta + d_theta / 2.0),
+                    vertex(magnet.outer_radius, theta + d_theta / 2.0),
+                    vertex(magnet.outer_radius, theta - d_theta / 2.0),
+                ];
+                let mid_radius = (magnet.inner_radius + magnet.outer_radius) / 2.0;
+                let magnetisation = magnet.magnetisation_at(&vertex(mid_radius, theta));
+                Magnet2D::Polygon(polygon_from_ring(
+                    &vertices,
+                    vertex(mid_radius, theta),
+                    magnetisation.magnitude(),
+                    magnetisation.y.atan2(magnetisation.x),
+                ))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_ring_matches_segments() {
+        let points = [
+            Point2::new(0.2, 0.0),
+            Point2::new(1.0, 0.3),
+            Point2::new(-1.5, 1.0),
+        ];
+        for &magnetisation in &[
+            RingMagnetisation::Diametric,
+            RingMagnetisation::Radial,
+            RingMagnetisation::Azimuthal,
+        ] {
+            let magnet = ring(magnetisation);
+            let segments = segmented_ring(&magnet, 720);
+            for point in &points {
+                let field = get_field_ring(&magnet, point).unwrap();
+                let comp_field = loop_field_2d(&segments, point).unwrap();
+                assert!((field - comp_field).magnitude() < 1e-2 * magnet.jr);
+            }
+        }
+    }
+
+    #[test]
+    fn test_diametric_bore_field() {
+        // The bore of a uniformly magnetised ring is field free
+        let magnet = ring(RingMagnetisation::Diametric);
+        let field = get_field_ring(&magnet, &(magnet.center + Point2::new(0.1, 0.3))).unwrap();
+        assert!(field.magnitude() < 1e-12);
+    }
+
+    #[test]
+    fn test_ring_derivatives() {
+        let point = Point2::new(0.4, 0.6);
+        for &magnetisation in &[RingMagnetisation::Diametric, RingMagnetisation::Azimuthal] {
+            let magnet = ring(magnetisation);
+            assert_derivatives(
+                |p| get_field_ring(&magnet, p),
+                |p| get_gradient_ring(&magnet, p),
+                |p| get_potential_ring(&magnet, p),
+                &point,
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! Helpers shared by the tests of the 2D field routines
+
+use crate::magnets::magnet2d::{Gradient2, Polygon, Vertices};
+use crate::points::{Point2, PointVec2, Points, Points2};
+use crate::utils::conversions::Angle;
+use crate::MagnetError;
+
+/// Returns a polygon with vertices `ring` and centre `center`, magnetised
+/// with `jr` at angle `phi` in radians
+pub(crate) fn polygon_from_ring(ring: &[Point2], center: Point2, jr: f64, phi: f64) -> Polygon {
+    Polygon::new(
+        center,
+        Angle::Radians(0.0),
+        jr,
+        Angle::Radians(phi),
+        Vertices::Custom(PointVec2::new(
+            ring.iter().map(|v| v.x).collect(),
+            ring.iter().map(|v| v.y).collect(),
+        )),
+    )
+    .unwrap()
+}
+
+/// Checks that central differences of the potential and field at `point`
+/// give the field and gradient, relative to the magnitude of the field
+pub(crate) fn assert_derivatives<F, G, V>(field: F, gradient: G, potential: V, point: &Point2)
+where
+    F: Fn(&Point2) -> Result<Point2, MagnetError>,
+    G: Fn(&Point2) -> Result<Gradient2, MagnetError>,
+    V: Fn(&Point2) -> Result<f64, MagnetError>,
+{
+    let step = 1e-6;
+    let dx = Point2::new(step, 0.0);
+    let dy = Point2::new(0.0, step);
+    let centre_diff = |f: &dyn Fn(&Point2) -> Point2, d: &Point2| {
+        (f(&(*point + *d)) - f(&(*point - *d))).scale(1.0 / (2.0 * step))
+    };
+
+    let value = field(point).unwrap();
+    let scale = value.magnitude();
+    let d_potential = |d: &Point2| {
+        (potential(&(*point + *d)).unwrap() - potential(&(*point - *d)).unwrap()) / (2.0 * step)
+    };
+    assert!((value.x - d_potential(&dy)).abs() < 1e-6 * scale);
+    assert!((value.y + d_potential(&dx)).abs() < 1e-6 * scale);
+
+    let value = gradient(point).unwrap();
+    let field_dx = centre_diff(&|p| field(p).unwrap(), &dx);
+    let field_dy = centre_diff(&|p| field(p).unwrap(), &dy);
+    assert!((value.xx - field_dx.x).abs() < 1e-5 * scale);
+    assert!((value.yx - field_dx.y).abs() < 1e-5 * scale);
+    assert!((value.xy - field_dy.x).abs() < 1e-5 * scale);
+    assert!((value.yy - field_dy.y).abs() < 1e-5 * scale);
+}
@@ -0,0 +1,223 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::wire_field::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::utils::conversions::Angle;
+
+use crate::magnets::magnet2d::Gradient2;
+use crate::magnets::{Contains, GetCenter, GetField, GetGradient, GetPotential};
+use crate::points::{Point2, Points2};
+use crate::{MagnetError, FP_CUTOFF, PI};
+use std::fmt;
+
+/// Enum for the cross-section of a wire, over which the current density is uniform
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum WireSection {
+    /// Line current of zero thickness
+    Filament,
+    /// Rectangular cross-section of (width, height)
+    Rectangle(f64, f64),
+    /// Circular cross-section of radius
+    Circle(f64),
+}
+
+/// An infinitely long straight wire, carrying a current along the z-axis
+///
+/// Arguments using `new()` method:
+///     - center (Point2): center of the wire
+///     - alpha (f64): rotation of a rectangular cross-section
+///     - current (f64): current in A, positive out of the plane
+///     - section (WireSection): cross-section of the wire
+///
+/// Lengths are in m, and fields are returned in T.
+///
+/// The default method generates a filament at (0.0, 0.0) carrying 1.0 A
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::{Wire, WireSection};
+/// use lodestone_core::utils::conversions::Angle;
+/// let wire_1 = Wire::default();
+/// println!("Wire 1:{}", wire_1);
+/// let wire_2 = Wire::new((0.0, -1.0), Angle::Degrees(0.0), 10.0, WireSection::Circle(0.01));
+/// println!("Wire 2:{}", wire_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Wire {
+    pub center: Point2,
+    pub alpha: Angle,
+    pub current: f64,
+    pub section: WireSection,
+}
+
+impl Default for Wire {
+    /// Default method for Wire.
+    ///
+    /// Generates a filament at (0,0) carrying 1 A out of the plane
+    fn default() -> Self {
+        Wire {
+            center: Point2::new(0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            current: 1.0,
+            section: WireSection::Filament,
+        }
+    }
+}
+
+impl Wire {
+    /// Constructor for a 2D Wire
+    pub fn new<C>(center: C, alpha: Angle, current: f64, section: WireSection) -> Wire
+    where
+        C: GetCenter<Point2>,
+    {
+        Wire {
+            center: center.center(),
+            alpha,
+            current,
+            section,
+        }
+    }
+
+    /// Returns the area of the cross-section
+    pub fn area(&self) -> f64 {
+        match self.section {
+            WireSection::Filament => 0.0,
+            WireSection::Rectangle(width, height) => width * height,
+            WireSection::Circle(radius) => PI * radius.powi(2),
+        }
+    }
+
+    /// Returns the current density in A/m^2, or zero for a filament
+    pub fn current_density(&self) -> f64 {
+        match self.section {
+            WireSection::Filament => 0.0,
+            _ => self.current / self.area(),
+        }
+    }
+
+    /// Translates and rotates a point into the local frame of the cross-section
+    pub(crate) fn local_point(&self, point: &Point2) -> Point2 {
+        let local_point = *point - self.center;
+        if self.alpha.to_radians().abs() > FP_CUTOFF {
+            local_point.rotate(&self.alpha.to_radians())
+        } else {
+            local_point
+        }
+    }
+}
+
+/// Implements Display for Wires.
+///
+/// Example:
+///
+impl fmt::Display for Wire {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[c: {},\talpha:{}\tI: {:.3}\tsection: {:?}]",
+            self.center,
+            self.alpha.to_degrees(),
+            self.current,
+            self.section
+        )
+    }
+}
+
+impl Contains<&Point2> for Wire {
+    /// Returns true if a point lies inside the cross-section of the wire
+    fn contains(&self, point: &Point2) -> bool {
+        let local_point = self.local_point(point);
+        match self.section {
+            WireSection::Filament => false,
+            WireSection::Rectangle(width, height) => {
+                local_point.x.abs() < width / 2.0 && local_point.y.abs() < height / 2.0
+            }
+            WireSection::Circle(radius) => local_point.magnitude_squared() < radius.powi(2),
+        }
+    }
+}
+
+impl GetField<&Point2, Result<Point2, MagnetError>> for Wire {
+    /// Returns the magnetic field of a wire at a Point2 struct {x,y}
+    fn field(&self, point: &Point2) -> Result<Point2, MagnetError> {
+        get_field_wire(self, point)
+    }
+}
+
+impl GetField<&(f64, f64), Result<(f64, f64), MagnetError>> for Wire {
+    /// Returns the magnetic field of a wire at a 2-element tuple (x,y)
+    fn field(&self, point: &(f64, f64)) -> Result<(f64, f64), MagnetError> {
+        let field_vec = get_field_wire(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok((field_vec.x, field_vec.y))
+    }
+}
+
+impl GetGradient<&Point2, Result<Gradient2, MagnetError>> for Wire {
+    /// Returns the field gradient of a wire at a Point2 struct {x,y}
+    fn gradient(&self, point: &Point2) -> Result<Gradient2, MagnetError> {
+        get_gradient_wire(self, point)
+    }
+}
+
+impl GetGradient<&(f64, f64), Result<(f64, f64, f64, f64), MagnetError>> for Wire {
+    /// Returns the field gradient of a wire at a 2-element tuple (x,y),
+    /// as a tuple (xx, xy, yx, yy)
+    fn gradient(&self, point: &(f64, f64)) -> Result<(f64, f64, f64, f64), MagnetError> {
+        let gradient = get_gradient_wire(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )?;
+        Ok(gradient.as_tuple())
+    }
+}
+
+impl GetPotential<&Point2, Result<f64, MagnetError>> for Wire {
+    /// Returns the vector potential of a wire at a Point2 struct {x,y}
+    fn potential(&self, point: &Point2) -> Result<f64, MagnetError> {
+        get_potential_wire(self, point)
+    }
+}
+
+impl GetPotential<&(f64, f64), Result<f64, MagnetError>> for Wire {
+    /// Returns the vector potential of a wire at a 2-element tuple (x,y)
+    fn potential(&self, point: &(f64, f64)) -> Result<f64, MagnetError> {
+        get_potential_wire(
+            self,
+            &Point2 {
+                x: point.0,
+                y: point.1,
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wire_contains() {
+        let wire = Wire::new(
+            (1.0, 0.0),
+            Angle::Degrees(90.0),
+            1.0,
+            WireSection::Rectangle(0.2, 0.1),
+        );
+        assert!(wire.contains(&Point2::new(1.0, 0.09)));
+        assert!(!wire.contains(&Point2::new(1.09, 0.0)));
+        assert!(!Wire::default().contains(&Point2::new(0.0, 0.0)));
+    }
+}
@@ -0,0 +1,285 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Fields of current carrying wires
+//!
+//! Outside a filament, or a wire of circular cross-section, the field is
+//! $`\mathbf{B} = \frac{\mu_0 I}{2 \pi \rho} \boldsymbol{\hat{\phi}}`$, with vector
+//! potential $`A_z = -\frac{\mu_0 I}{2 \pi} \ln \rho`$ referred to $`\rho = 1`$ m.
+//!
+//! For a rectangular cross-section of uniform current density $`j`$, integrating
+//! the potential of a filament over the conductor gives
+//!
+//! ```math
+//! A_z = -\frac{\mu_0 j}{4 \pi} \sum_{k} s_k P(u_k, v_k)
+//! ```
+//!
+//! where the sum is over the corners $`(x_k, y_k)`$ of the rectangle, with
+//! $`u_k = x - x_k`$, $`v_k = y - y_k`$, sign $`s_k = +1`$ on one diagonal and
+//! $`-1`$ on the other, and
+//! $`P(u, v) = uv \left[ \ln(u^2 + v^2) - 3 \right] + u^2 \arctan(v / u) + v^2 \arctan(u / v)`$.
+
+#![allow(clippy::many_single_char_names)]
+use crate::magnets::magnet2d::{sheet_primitive, Gradient2, Wire, WireSection};
+use crate::points::{Point2, Points2};
+use crate::{MagnetError, FP_CUTOFF, I_2PI, I_4PI, M2_PI, MU0};
+
+/// Returns the corners of a rectangle of width `2a` and height `2b` relative
+/// to a point, as (u, v, sign)
+fn rectangle_corners(point: &Point2, a: f64, b: f64) -> [(f64, f64, f64); 4] {
+    [
+        (point.x + a, point.y + b, 1.0),
+        (point.x + a, point.y - b, -1.0),
+        (point.x - a, point.y + b, -1.0),
+        (point.x - a, point.y - b, 1.0),
+    ]
+}
+
+/// Returns the antiderivative of $`\ln(u^2 + v^2)`$ with respect to both `u` and `v`
+fn rectangle_primitive(u: f64, v: f64) -> f64 {
+    let dist_sq = u.powi(2) + v.powi(2);
+    if dist_sq <= 0.0 {
+        return 0.0;
+    }
+    let mut primitive = u * v * (dist_sq.ln() - 3.0);
+    if u != 0.0 {
+        primitive += u.powi(2) * (v / u).atan();
+    }
+    if v != 0.0 {
+        primitive += v.powi(2) * (u / v).atan();
+    }
+    primitive
+}
+
+/// Returns the field of a filament carrying a current `current`, at a point
+/// relative to the filament
+fn filament_field(current: f64, point: &Point2) -> Point2 {
+    let dist_sq = point.magnitude_squared();
+    if dist_sq <= 0.0 {
+        return Point2::zero();
+    }
+    let prefac = MU0 * current * I_2PI / dist_sq;
+    Point2::new(-prefac * point.y, prefac * point.x)
+}
+
+/// Returns the field gradient of a filament carrying a current `current`, at
+/// a point relative to the filament
+fn filament_gradient(current: f64, point: &Point2) -> Gradient2 {
+    let dist_sq = point.magnitude_squared();
+    if dist_sq <= 0.0 {
+        return Gradient2::zero();
+    }
+    let prefac = MU0 * current * I_2PI / dist_sq.powi(2);
+    let diagonal = 2.0 * prefac * point.x * point.y;
+    let off_diagonal = prefac * (point.y.powi(2) - point.x.powi(2));
+    Gradient2::new(diagonal, off_diagonal, off_diagonal, -diagonal)
+}
+
+/// Returns the magnetic field vector due to an infinite wire, carrying a
+/// current out of the plane, at a point `point`
+pub fn get_field_wire(wire: &Wire, point: &Point2) -> Result<Point2, MagnetError> {
+    let local_point = wire.local_point(point);
+
+    let field = match wire.section {
+        WireSection::Filament => filament_field(wire.current, &local_point),
+        WireSection::Circle(radius) => {
+            if local_point.magnitude_squared() < radius.powi(2) {
+                // Only the current enclosed within rho contributes
+                let prefac = MU0 * wire.current * I_2PI / radius.powi(2);
+                Point2::new(-prefac * local_point.y, prefac * local_point.x)
+            } else {
+                filament_field(wire.current, &local_point)
+            }
+        }
+        WireSection::Rectangle(width, height) => {
+            let prefac = MU0 * wire.current_density() * I_4PI;
+            let mut field = Point2::zero();
+            for (u, v, sign) in &rectangle_corners(&local_point, width / 2.0, height / 2.0) {
+                field.x -= sign * sheet_primitive(v, u);
+                field.y += sign * sheet_primitive(u, v);
+            }
+            Point2::new(prefac * field.x, prefac * field.y)
+        }
+    };
+
+    if wire.alpha.to_radians().abs() > FP_CUTOFF {
+        let reverse_alpha = M2_PI - wire.alpha.to_radians();
+        Ok(field.rotate(&reverse_alpha))
+    } else {
+        Ok(field)
+    }
+}
+
+/// Returns the field gradient tensor due to an infinite wire at a point `point`
+pub fn get_gradient_wire(wire: &Wire, point: &Point2) -> Result<Gradient2, MagnetError> {
+    let local_point = wire.local_point(point);
+
+    let gradient = match wire.section {
+        WireSection::Filament => filament_gradient(wire.current, &local_point),
+        WireSection::Circle(radius) => {
+            if local_point.magnitude_squared() < radius.powi(2) {
+                let prefac = MU0 * wire.current * I_2PI / radius.powi(2);
+                Gradient2::new(0.0, -prefac, prefac, 0.0)
+            } else {
+                filament_gradient(wire.current, &local_point)
+            }
+        }
+        WireSection::Rectangle(width, height) => {
+            let prefac = MU0 * wire.current_density() * I_4PI;
+            let mut log_sum = 0.0;
+            let mut xy = 0.0;
+            let mut yx = 0.0;
+            for (u, v, sign) in &rectangle_corners(&local_point, width / 2.0, height / 2.0) {
+                log_sum += sign * (u.powi(2) + v.powi(2)).ln();
+                xy -= sign * 2.0 * (u / v).atan();
+                yx += sign * 2.0 * (v / u).atan();
+            }
+            Gradient2::new(
+                -prefac * log_sum,
+                prefac * xy,
+                prefac * yx,
+                prefac * log_sum,
+            )
+            .bind_singular()
+        }
+    };
+
+    if wire.alpha.to_radians().abs() > FP_CUTOFF {
+        let reverse_alpha = M2_PI - wire.alpha.to_radians();
+        Ok(gradient.rotate(&reverse_alpha))
+    } else {
+        Ok(gradient)
+    }
+}
+
+/// Returns the out-of-plane vector potential $`A_z`$ due to an infinite wire
+/// at a point `point`
+pub fn get_potential_wire(wire: &Wire, point: &Point2) -> Result<f64, MagnetError> {
+    let local_point = wire.local_point(point);
+    let dist_sq = local_point.magnitude_squared();
+    let prefac = MU0 * wire.current * I_4PI;
+
+    Ok(match wire.section {
+        WireSection::Filament => {
+            if dist_sq <= 0.0 {
+                0.0
+            } else {
+                -prefac * dist_sq.ln()
+            }
+        }
+        WireSection::Circle(radius) => {
+            let radius_sq = radius.powi(2);
+            if dist_sq < radius_sq {
+                -prefac * (radius_sq.ln() + dist_sq / radius_sq - 1.0)
+            } else {
+                -prefac * dist_sq.ln()
+            }
+        }
+        WireSection::Rectangle(width, height) => {
+            let prefac = MU0 * wire.current_density() * I_4PI;
+            -prefac
+                * rectangle_corners(&local_point, width / 2.0, height / 2.0)
+                    .iter()
+                    .map(|(u, v, sign)| sign * rectangle_primitive(*u, *v))
+                    .sum::<f64>()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet2d::test_utils::assert_derivatives;
+    use crate::points::Points;
+    use crate::utils::conversions::Angle;
+    use crate::PI;
+
+    fn wires() -> Vec<Wire> {
+        vec![
+            Wire::new((0.1, -0.2), Angle::Degrees(0.0), 2.0, WireSection::Filament),
+            Wire::new(
+                (0.1, -0.2),
+                Angle::Degrees(0.0),
+                2.0,
+                WireSection::Circle(0.3),
+            ),
+            Wire::new(
+                (0.1, -0.2),
+                Angle::Degrees(25.0),
+                2.0,
+                WireSection::Rectangle(0.4, 0.2),
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_filament_field() {
+        // B = mu0 I / (2 pi rho)
+        let wire = Wire::default();
+        let field = get_field_wire(&wire, &Point2::new(0.0, 2.0)).unwrap();
+        assert!((field.x + MU0 / (4.0 * PI)).abs() < 1e-20);
+        assert!(field.y.abs() < 1e-20);
+    }
+
+    #[test]
+    fn test_far_field_matches_filament() {
+        let point = Point2::new(3.0, 4.0);
+        let comp_field = get_field_wire(&wires()[0], &point).unwrap();
+        for wire in &wires()[1..] {
+            let field = get_field_wire(wire, &point).unwrap();
+            assert!((field - comp_field).magnitude() < 1e-3 * comp_field.magnitude());
+        }
+    }
+
+    #[test]
+    fn test_amperes_law() {
+        // The circulation of B around a loop is mu0 times the enclosed current
+        let num_nodes = 4000;
+        for wire in &wires()[1..] {
+            for &radius in &[0.05, 0.15, 1.0] {
+                let circulation: f64 = (0..num_nodes)
+                    .map(|i| {
+                        let theta = M2_PI * (i as f64 + 0.5) / num_nodes as f64;
+                        let tangent = Point2::new(-theta.sin(), theta.cos());
+                        let point =
+                            wire.center + Point2::new(theta.cos(), theta.sin()).scale(radius);
+                        get_field_wire(wire, &point).unwrap().dot(&tangent) * radius * M2_PI
+                            / num_nodes as f64
+                    })
+                    .sum();
+
+                // Both sections fully enclose the loop, or are fully enclosed by it
+                let enclosed = if radius < 0.1 {
+                    wire.current_density() * PI * radius.powi(2)
+                } else if radius > 0.5 {
+                    wire.current
+                } else {
+                    continue;
+                };
+                assert!((circulation - MU0 * enclosed).abs() < 1e-6 * MU0 * wire.current);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wire_derivatives() {
+        // Finite differences of the potential and field give the field and gradient
+        let points = [
+            Point2::new(1.0, 0.7),
+            Point2::new(0.15, -0.15),
+            Point2::new(-0.4, -0.5),
+        ];
+        for wire in &wires() {
+            for point in &points {
+                assert_derivatives(
+                    |p| get_field_wire(wire, p),
+                    |p| get_gradient_wire(wire, p),
+                    |p| get_potential_wire(wire, p),
+                    point,
+                );
+            }
+        }
+    }
+}
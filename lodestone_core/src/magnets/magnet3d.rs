@@ -3,31 +3,73 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 Copyright 2021 Peter Dunne */
 //! # Magnet3D - Field calculations in 3D
-/// This consists of modules for calculating magnetic fields due to
-///
+//! This consists of modules for calculating magnetic fields due to magnetic
+//! objects in 3D, including:
+//! - Prisms (cuboids)
+//! - Cylinders
+//! - Spheres
+//! - Arbitrary polyhedra, from triangulated surfaces
+//! - Solenoids and thick coils
+//! - Circular current loops, and Helmholtz, anti-Helmholtz, and Maxwell coils
+//!
+//! along with the forces and torques between them.
+//!
 use serde_derive::{Deserialize, Serialize};
 
 use super::MagnetTrait;
 
 pub mod bulirsch;
+mod coil_assemblies;
+mod current_loop;
+mod cylinder;
+mod field_loop_3d;
+mod force_3d;
+mod polyhedron;
 mod prism;
 mod solenoid;
+mod sphere;
+mod triangle;
+
+mod current_loop_field;
+mod cylinder_field;
+mod prism_field;
+mod solenoid_field;
+mod sphere_field;
+mod triangle_field;
 
+pub use coil_assemblies::*;
+pub use current_loop::*;
+pub use cylinder::*;
+pub use field_loop_3d::*;
+pub use force_3d::*;
+pub use polyhedron::*;
 pub use prism::*;
 pub use solenoid::*;
+pub use sphere::*;
+pub use triangle::*;
 
 /// Enum to store the different 3D magnet types.
 ///
-/// This allows us to create a Vec<MagnetType2D>, and access the fields of the magnet
+/// This allows us to create a Vec<MagnetType3D>, and access the fields of the magnet
 /// struct with a match routine.
 ///
 #[derive(Debug, Deserialize, Serialize, PartialEq)]
 pub enum Magnet3D {
+    /// 3D prismatic (cuboid) magnet
     Prism(Prism),
-    Cylinder,
-    Sphere,
-    Custom,
+    /// 3D cylindrical magnet
+    Cylinder(Cylinder),
+    /// 3D spherical magnet
+    Sphere(Sphere),
+    /// 3D arbitrary polyhedron
+    Polyhedron(Polyhedron),
+    /// Thin solenoid
+    Solenoid(Solenoid),
+    /// Thick multi-layer coil
+    ThickCoil(ThickCoil),
+    /// Thin circular current loop
+    CurrentLoop(CurrentLoop),
 }
 
-/// Magnet2D Traits
+/// Magnet3D Traits
 pub trait MagnetTrait3D<POINT, CENTER, SIZE, MAG>: MagnetTrait<POINT, CENTER, SIZE, MAG> {}
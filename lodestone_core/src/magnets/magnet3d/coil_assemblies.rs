@@ -0,0 +1,239 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! Builders for standard coil assemblies made of circular current loops
+//!
+//! - Helmholtz pairs, for a uniform field
+//! - Anti-Helmholtz pairs, for a uniform field gradient
+//! - Maxwell coils, for a more uniform field than a Helmholtz pair
+//! - Three-axis Helmholtz cages, for an arbitrary uniform field
+//!
+//! Each coil is a single thin loop, so for a winding of N turns the current
+//! should be multiplied by N. The axis of the assembly is the local z-axis,
+//! oriented using the Euler angles alpha, beta, gamma, as for 3D magnets.
+
+use crate::magnets::magnet3d::{CurrentLoop, Magnet3D};
+use crate::magnets::{GetCenter, MagnetVec3D};
+use crate::points::{Point3, Points3};
+use crate::utils::conversions::Angle;
+use crate::utils::quaternion::Quaternion;
+
+/// Returns a loop whose center is offset by `offset` along the axis of the assembly
+fn coaxial_loop(
+    radius: f64,
+    current: f64,
+    center: &Point3,
+    offset: f64,
+    angles: &(Angle, Angle, Angle),
+) -> Magnet3D {
+    let orientation = Quaternion::from_euler(&angles.0, &angles.1, &angles.2);
+    let loop_center = *center + orientation.rotate(&Point3::k_hat().scale(offset));
+    Magnet3D::CurrentLoop(CurrentLoop::new(
+        radius,
+        loop_center,
+        angles.0,
+        angles.1,
+        angles.2,
+        current,
+    ))
+}
+
+/// Returns a Helmholtz pair, two coaxial loops of radius `radius` separated
+/// by `radius`, carrying the same current
+pub fn helmholtz_pair<C>(
+    radius: f64,
+    current: f64,
+    center: C,
+    alpha: Angle,
+    beta: Angle,
+    gamma: Angle,
+) -> MagnetVec3D
+where
+    C: GetCenter<Point3>,
+{
+    let center = center.center();
+    let angles = (alpha, beta, gamma);
+    vec![
+        coaxial_loop(radius, current, &center, -radius / 2.0, &angles),
+        coaxial_loop(radius, current, &center, radius / 2.0, &angles),
+    ]
+}
+
+/// Returns an anti-Helmholtz pair, two coaxial loops of radius `radius`
+/// separated by $`\sqrt{3} R`$, carrying opposite currents.
+///
+/// The loop at positive z carries `current`, producing a field gradient
+/// $`\partial B_z/\partial z > 0`$ at the center for positive currents.
+pub fn anti_helmholtz_pair<C>(
+    radius: f64,
+    current: f64,
+    center: C,
+    alpha: Angle,
+    beta: Angle,
+    gamma: Angle,
+) -> MagnetVec3D
+where
+    C: GetCenter<Point3>,
+{
+    let center = center.center();
+    let angles = (alpha, beta, gamma);
+    let offset = 3.0_f64.sqrt() * radius / 2.0;
+    vec![
+        coaxial_loop(radius, -current, &center, -offset, &angles),
+        coaxial_loop(radius, current, &center, offset, &angles),
+    ]
+}
+
+/// Returns a Maxwell coil, a loop of radius `radius` carrying `current`,
+/// between two loops of radius $`\sqrt{4/7} R`$ at $`\pm\sqrt{3/7} R`$,
+/// each carrying $`49/64`$ of the current
+pub fn maxwell_coil<C>(
+    radius: f64,
+    current: f64,
+    center: C,
+    alpha: Angle,
+    beta: Angle,
+    gamma: Angle,
+) -> MagnetVec3D
+where
+    C: GetCenter<Point3>,
+{
+    let center = center.center();
+    let angles = (alpha, beta, gamma);
+    let outer_radius = (4.0_f64 / 7.0).sqrt() * radius;
+    let offset = (3.0_f64 / 7.0).sqrt() * radius;
+    let outer_current = current * 49.0 / 64.0;
+    vec![
+        coaxial_loop(outer_radius, outer_current, &center, -offset, &angles),
+        coaxial_loop(radius, current, &center, 0.0, &angles),
+        coaxial_loop(outer_radius, outer_current, &center, offset, &angles),
+    ]
+}
+
+/// Returns a three-axis Helmholtz cage, three orthogonal Helmholtz pairs of
+/// radius `radius` with their axes along x, y, and z, carrying the currents
+/// `currents` = (Ix, Iy, Iz)
+pub fn helmholtz_cage<C>(radius: f64, currents: (f64, f64, f64), center: C) -> MagnetVec3D
+where
+    C: GetCenter<Point3>,
+{
+    let center = center.center();
+    let zero = Angle::Degrees(0.0);
+
+    let mut cage = helmholtz_pair(radius, currents.0, center, zero, Angle::Degrees(90.0), zero);
+    cage.extend(helmholtz_pair(
+        radius,
+        currents.1,
+        center,
+        zero,
+        zero,
+        Angle::Degrees(-90.0),
+    ));
+    cage.extend(helmholtz_pair(radius, currents.2, center, zero, zero, zero));
+    cage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::loop_field_3d;
+    use crate::MU0;
+
+    /// Field at the center of a Helmholtz pair, (4/5)^(3/2) mu0 I / R
+    fn helmholtz_field(radius: f64, current: f64) -> f64 {
+        0.8_f64.powf(1.5) * MU0 * current / radius
+    }
+
+    #[test]
+    fn helmholtz_center_field() {
+        let coils = helmholtz_pair(
+            0.2,
+            10.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+        );
+        assert_eq!(coils.len(), 2);
+        let field = loop_field_3d(&coils, &Point3::zero()).unwrap();
+        let comp_field = helmholtz_field(0.2, 10.0);
+        assert!(((field.z - comp_field) / comp_field).abs() < 1e-12);
+    }
+
+    #[test]
+    fn anti_helmholtz_gradient() {
+        let coils = anti_helmholtz_pair(
+            1.0,
+            1.0,
+            (1.0, 2.0, 3.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+        );
+        let center = loop_field_3d(&coils, &Point3::new(1.0, 2.0, 3.0)).unwrap();
+        assert!(center.magnitude() < 1e-15);
+
+        // The gradient along the axis is twice that in the radial direction
+        let delta = 1e-3;
+        let axial = loop_field_3d(&coils, &Point3::new(1.0, 2.0, 3.0 + delta)).unwrap();
+        let radial = loop_field_3d(&coils, &Point3::new(1.0 + delta, 2.0, 3.0)).unwrap();
+        assert!(axial.z > 0.0);
+        assert!(((axial.z / radial.x) + 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn maxwell_is_more_uniform_than_helmholtz() {
+        let angles = (
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+        );
+        let maxwell = maxwell_coil(1.0, 1.0, (0.0, 0.0, 0.0), angles.0, angles.1, angles.2);
+        let helmholtz = helmholtz_pair(1.0, 1.0, (0.0, 0.0, 0.0), angles.0, angles.1, angles.2);
+
+        let variation = |coils: &[Magnet3D]| {
+            let center = loop_field_3d(coils, &Point3::zero()).unwrap();
+            let off_center = loop_field_3d(coils, &Point3::new(0.0, 0.0, 0.1)).unwrap();
+            ((off_center.z - center.z) / center.z).abs()
+        };
+
+        assert_eq!(maxwell.len(), 3);
+        assert!(variation(&maxwell) < 1e-5);
+        assert!(variation(&maxwell) < variation(&helmholtz) / 10.0);
+    }
+
+    #[test]
+    fn rotated_helmholtz_pair() {
+        // Tilting the pair by 90° about y aligns its axis with x
+        let coils = helmholtz_pair(
+            0.5,
+            2.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+        );
+        match &coils[1] {
+            Magnet3D::CurrentLoop(coil) => assert!((coil.center.x - 0.25).abs() < 1e-15),
+            _ => panic!("helmholtz_pair should return current loops"),
+        }
+        let field = loop_field_3d(&coils, &Point3::zero()).unwrap();
+        let comp_field = helmholtz_field(0.5, 2.0);
+        assert!(((field.x - comp_field) / comp_field).abs() < 1e-12);
+        assert!(field.z.abs() < 1e-15);
+    }
+
+    #[test]
+    fn cage_center_field() {
+        let cage = helmholtz_cage(0.5, (1.0, -2.0, 3.0), (0.0, 0.0, 0.0));
+        assert_eq!(cage.len(), 6);
+
+        let field = loop_field_3d(&cage, &Point3::zero()).unwrap();
+        let unit_field = helmholtz_field(0.5, 1.0);
+        assert!(((field.x - unit_field) / unit_field).abs() < 1e-12);
+        assert!(((field.y + 2.0 * unit_field) / unit_field).abs() < 1e-12);
+        assert!(((field.z - 3.0 * unit_field) / unit_field).abs() < 1e-12);
+    }
+}
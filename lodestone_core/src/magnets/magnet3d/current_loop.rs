@@ -0,0 +1,156 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::current_loop_field::*;
+
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::magnets::{GetCenter, GetField};
+use crate::points::Point3;
+use crate::utils::conversions::Angle;
+use crate::utils::quaternion::Quaternion;
+use crate::MagnetError;
+
+/// A thin circular current loop
+///
+/// Arguments using `new()` method:
+///     - radius (f64): loop radius in m
+///     - center (Point3): loop center
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
+///     - current (f64): current in A
+///
+/// The loop lies in the local xy-plane, and a positive current circulates
+/// anti-clockwise about the local z-axis, producing a field in +z at its center.
+///
+/// The default method generates a loop of radius 1.0 centred at
+/// (0.0, 0.0, 0.0), in the xy-plane, carrying 1 A
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::CurrentLoop;
+/// use lodestone_core::utils::conversions::Angle;
+/// let current_loop = CurrentLoop::new(
+///     0.05,
+///     (0.0, 0.0, 0.025),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     2.0,
+/// );
+/// println!("Loop:{}", current_loop);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CurrentLoop {
+    pub radius: f64,
+    pub center: Point3,
+    pub alpha: Angle,
+    pub beta: Angle,
+    pub gamma: Angle,
+    pub current: f64,
+}
+
+impl Default for CurrentLoop {
+    /// Default method for CurrentLoop.
+    ///
+    /// Generates a loop of radius 1.0, centred at (0,0,0), in the xy-plane,
+    /// carrying 1 A
+    fn default() -> Self {
+        CurrentLoop {
+            radius: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            beta: Angle::Degrees(0.0),
+            gamma: Angle::Degrees(0.0),
+            current: 1.0,
+        }
+    }
+}
+
+impl CurrentLoop {
+    /// Constructor for a CurrentLoop
+    pub fn new<C>(
+        radius: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        current: f64,
+    ) -> CurrentLoop
+    where
+        C: GetCenter<Point3>,
+    {
+        CurrentLoop {
+            radius,
+            center: center.center(),
+            alpha,
+            beta,
+            gamma,
+            current,
+        }
+    }
+
+    /// Returns the orientation of the loop as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+}
+
+/// Implements Display for CurrentLoops.
+impl fmt::Display for CurrentLoop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: {},\tc: {},\talpha:{}\tbeta:{}\tgamma:{}\tI: {} A]",
+            self.radius,
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.current,
+        )
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for CurrentLoop {
+    /// Returns the magnetic field of a current loop at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_current_loop(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for CurrentLoop {
+    /// Returns the magnetic field of a current loop at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_current_loop(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{M2_PI, MU0};
+
+    #[test]
+    fn test_tuple_loop_field() {
+        // Far from the loop the field is that of a dipole m = I pi a^2
+        let current_loop = CurrentLoop::default();
+        let distance = 100.0_f64;
+        let field = current_loop.field(&(0.0, 0.0, distance)).unwrap();
+        let dipole = MU0 * crate::PI / (M2_PI * distance.powi(3));
+        assert!(((field.2 - dipole) / dipole).abs() < 1e-3);
+    }
+}
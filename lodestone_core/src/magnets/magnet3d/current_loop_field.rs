@@ -0,0 +1,188 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for circular current loops
+//!
+//! The exact off-axis field of a thin circular loop of radius `a`, carrying a
+//! current `I`, is written in terms of the complete elliptic integrals of the
+//! first and second kind, $`K = C(k_c, 1, 1, 1)`$ and $`E = C(k_c, 1, 1, k_c^2)`$,
+//! with $`k_c^2 = ((a - \rho)^2 + z^2)/((a + \rho)^2 + z^2)`$:
+//!
+//! ```math
+//! B_z = \frac{\mu_0 I}{2\pi \sqrt{(a+\rho)^2 + z^2}}
+//! \left[ K + \frac{a^2 - \rho^2 - z^2}{(a-\rho)^2 + z^2} E \right]
+//! ```
+//!
+//! ```math
+//! B_\rho = \frac{\mu_0 I z}{2\pi \rho \sqrt{(a+\rho)^2 + z^2}}
+//! \left[ -K + \frac{a^2 + \rho^2 + z^2}{(a-\rho)^2 + z^2} E \right]
+//! ```
+//!
+//! Close to the axis the radial component suffers from cancellation, so its
+//! leading order expansion in $`\rho`$ is used instead.
+
+use crate::magnets::magnet3d::bulirsch::cel;
+use crate::magnets::magnet3d::CurrentLoop;
+use crate::points::{Point2, Point3};
+use crate::{MagnetError, FP_CUTOFF, MU0, PI};
+
+/// Below this fraction of the radius, the radial field is evaluated using its
+/// series expansion about the axis
+const NEAR_AXIS: f64 = 1e-4;
+
+/// Returns the magnetic field (Bz, Bρ) as a Point2, due to a loop of radius
+/// `a` centred at the origin in the xy-plane, carrying a current `current`.
+///
+/// On the wire itself the field is singular and is bound to 0.0.
+pub fn current_loop_field(a: f64, current: f64, rho: f64, z: f64) -> Result<Point2, MagnetError> {
+    let b_0 = MU0 * current;
+    let sum_sq = (a + rho).powi(2) + z.powi(2);
+    let diff_sq = (a - rho).powi(2) + z.powi(2);
+
+    // Points on the wire are singular, bind to 0.0
+    if diff_sq < (FP_CUTOFF * a).powi(2) {
+        return Ok(Point2::new(0.0, 0.0));
+    }
+
+    // On the axis, Bz = mu0 I a^2 / 2 (a^2 + z^2)^(3/2)
+    if rho / a < FP_CUTOFF {
+        let b_z = b_0 * a.powi(2) / (2.0 * (a.powi(2) + z.powi(2)).powf(1.5));
+        return Ok(Point2::new(b_z, 0.0));
+    }
+
+    let kc = (diff_sq / sum_sq).sqrt();
+    let k_1 = cel(kc, 1.0, 1.0, 1.0);
+    let e_2 = cel(kc, 1.0, 1.0, kc.powi(2));
+
+    let prefactor = b_0 / (2.0 * PI * sum_sq.sqrt());
+
+    let b_z = prefactor * (k_1 + (a.powi(2) - rho.powi(2) - z.powi(2)) / diff_sq * e_2);
+
+    let b_rho = if rho / a < NEAR_AXIS {
+        // Leading order term, 3 mu0 I a^2 z rho / 4 (a^2 + z^2)^(5/2)
+        3.0 * b_0 * a.powi(2) * z * rho / (4.0 * (a.powi(2) + z.powi(2)).powf(2.5))
+    } else {
+        prefactor * z / rho * (-k_1 + (a.powi(2) + rho.powi(2) + z.powi(2)) / diff_sq * e_2)
+    };
+
+    Ok(Point2::new(b_z, b_rho))
+}
+
+/// Returns the magnetic field vector at a point due to a current loop
+pub fn get_field_current_loop(
+    current_loop: &CurrentLoop,
+    point: &Point3,
+) -> Result<Point3, MagnetError> {
+    // Translate and rotate into the local coordinates of the loop
+    let orientation = current_loop.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - current_loop.center));
+
+    let rho = (local_point.x.powi(2) + local_point.y.powi(2)).sqrt();
+    let field = current_loop_field(
+        current_loop.radius,
+        current_loop.current,
+        rho,
+        local_point.z,
+    )?;
+
+    let field = if field.y == 0.0 {
+        Point3::new(0.0, 0.0, field.x)
+    } else {
+        Point3::new(
+            field.y * local_point.x / rho,
+            field.y * local_point.y / rho,
+            field.x,
+        )
+    };
+
+    // Rotate the field back into the global frame
+    Ok(orientation.rotate(&field))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::magnets::magnet3d::current_loop_field::*;
+    use crate::points::Points3;
+    use crate::utils::conversions::Angle;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() <= 1e-10 * b.abs().max(1e-12)
+    }
+
+    /// Brute force Biot-Savart integration around a loop of unit radius in
+    /// the xy-plane, carrying 1 A
+    fn biot_savart(point: &Point3) -> Point3 {
+        let steps = 20_000;
+        let d_theta = 2.0 * PI / steps as f64;
+        let mut field = Point3::zero();
+        for i in 0..steps {
+            let theta = (i as f64 + 0.5) * d_theta;
+            let position = Point3::new(theta.cos(), theta.sin(), 0.0);
+            let dl = Point3::new(-theta.sin(), theta.cos(), 0.0).scale(d_theta);
+            let r = *point - position;
+            field += dl.cross(&r).scale(MU0 / (4.0 * PI * r.magnitude().powi(3)));
+        }
+        field
+    }
+
+    #[test]
+    fn field_on_axis() {
+        let current_loop = CurrentLoop::default();
+        for &z in &[0.0_f64, 0.5, -2.0] {
+            let field = get_field_current_loop(&current_loop, &Point3::new(0.0, 0.0, z)).unwrap();
+            let comp_field = MU0 / (2.0 * (1.0 + z * z).powf(1.5));
+            assert!(close(field.z, comp_field));
+            assert_eq!(field.x, 0.0);
+            assert_eq!(field.y, 0.0);
+        }
+    }
+
+    #[test]
+    fn field_off_axis_matches_biot_savart() {
+        let current_loop = CurrentLoop::default();
+        for point in &[
+            Point3::new(0.3, 0.4, 0.2),
+            Point3::new(1.5, -0.5, -0.7),
+            Point3::new(0.9, 0.0, 0.05),
+        ] {
+            let field = get_field_current_loop(&current_loop, point).unwrap();
+            let comp_field = biot_savart(point);
+            assert!((field.x - comp_field.x).abs() < 1e-12);
+            assert!((field.y - comp_field.y).abs() < 1e-12);
+            assert!((field.z - comp_field.z).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn field_near_axis_is_continuous() {
+        let z = 0.3;
+        let inner = current_loop_field(1.0, 1.0, 0.99 * NEAR_AXIS, z).unwrap();
+        let outer = current_loop_field(1.0, 1.0, 1.01 * NEAR_AXIS, z).unwrap();
+        assert!(((inner.y / 0.99 - outer.y / 1.01) / outer.y).abs() < 1e-6);
+        assert!(((inner.x - outer.x) / outer.x).abs() < 1e-6);
+    }
+
+    #[test]
+    fn field_on_wire_is_finite() {
+        let current_loop = CurrentLoop::default();
+        let field = get_field_current_loop(&current_loop, &Point3::new(1.0, 0.0, 0.0)).unwrap();
+        assert_eq!(field, Point3::zero());
+
+        let field = get_field_current_loop(&current_loop, &Point3::new(1.0, 0.0, 1e-3)).unwrap();
+        assert!(field.x.is_finite() && field.z.is_finite());
+    }
+
+    #[test]
+    fn rotated_loop_field() {
+        // Rotating by 90° about x maps the loop axis onto -y
+        let current_loop = CurrentLoop {
+            gamma: Angle::Degrees(90.0),
+            ..Default::default()
+        };
+        let field = get_field_current_loop(&current_loop, &Point3::new(0.0, -0.5, 0.0)).unwrap();
+        let comp_field = MU0 / (2.0 * 1.25_f64.powf(1.5));
+        assert!(close(-field.y, comp_field));
+    }
+}
@@ -0,0 +1,275 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::cylinder_field::*;
+
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::magnets::magnet3d::magnetisation_components;
+use crate::magnets::{GetCenter, GetField, MagnetTrait};
+use crate::points::{Point3, Points3};
+use crate::utils::conversions::Angle;
+use crate::utils::quaternion::Quaternion;
+use crate::MagnetError;
+
+/// A 3D Cylindrical magnet
+///
+/// Arguments using `new()` method:
+///     - radius (f64): magnet radius
+///     - length (f64): magnet length (z)
+///     - center (Point3): magnet center
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
+///     - jr (f64): remnant magnetisation in T
+///     - phi (f64): angle of magnetisation in the local xy-plane w.r.t. local x-axis
+///     - theta (f64): angle of magnetisation w.r.t. local z-axis (cylinder axis)
+///
+/// The magnet is rotated about its center by $`R = R_z(\alpha) R_y(\beta) R_x(\gamma)`$,
+/// and the magnetisation rotates with it.
+///
+/// A magnetisation at an arbitrary angle is treated as the sum of an axial
+/// (z) and a diametric (xy-plane) magnetisation.
+///
+/// The default method generates a cylinder of radius 0.5 and length 1.0 centred
+/// at (0.0, 0.0, 0.0), magnetised in z with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::Cylinder;
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = Cylinder::default();
+/// println!("Magnet 1:{}", magnet_1);
+/// let magnet_2 = Cylinder::new(
+///     0.5,
+///     2.0,
+///     (0.0, 0.0, -1.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     1.0,
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(90.0),
+/// );
+/// println!("Magnet 2:{}", magnet_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Cylinder {
+    pub radius: f64,
+    pub length: f64,
+    pub center: Point3,
+    pub alpha: Angle,
+    pub beta: Angle,
+    pub gamma: Angle,
+
+    pub jr: f64,
+    pub phi: Angle,
+    pub theta: Angle,
+
+    pub b: f64,
+
+    pub jx: f64,
+    pub jy: f64,
+    pub jz: f64,
+}
+
+impl Default for Cylinder {
+    /// Default method for Cylinder.
+    ///
+    /// Generates a cylinder of radius 0.5 and length 1.0, centred at (0,0,0),
+    /// with a magnetisation of 1 tesla in z
+    fn default() -> Self {
+        Cylinder {
+            radius: 0.5,
+            length: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            beta: Angle::Degrees(0.0),
+            gamma: Angle::Degrees(0.0),
+            jr: 1.0,
+            phi: Angle::Degrees(90.0),
+            theta: Angle::Degrees(0.0),
+            b: 0.5,
+            jx: 0.0,
+            jy: 0.0,
+            jz: 1.0,
+        }
+    }
+}
+
+impl Cylinder {
+    /// Constructor for 3D Cylinder magnet
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<C>(
+        radius: f64,
+        length: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        jr: f64,
+        phi: Angle,
+        theta: Angle,
+    ) -> Cylinder
+    where
+        C: GetCenter<Point3>,
+    {
+        let (jx, jy, jz) = magnetisation_components(jr, &phi, &theta);
+
+        Cylinder {
+            radius,
+            length,
+            center: center.center(),
+            alpha,
+            beta,
+            gamma,
+            jr,
+            phi,
+            theta,
+            b: length / 2.0,
+            jx,
+            jy,
+            jz,
+        }
+    }
+
+    /// Returns the orientation of the magnet as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+}
+
+/// Implements Display for Cylinder magnets.
+impl fmt::Display for Cylinder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: {},\tl: {},\tc: {},\talpha:{}\tbeta:{}\tgamma:{}\tJ ({:.3}, {:.3}, {:.3})]",
+            self.radius,
+            self.length,
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.jr,
+            self.phi.to_degrees(),
+            self.theta.to_degrees(),
+        )
+    }
+}
+
+impl MagnetTrait<Point3, Point3, [f64; 2], Point3> for Cylinder {
+    /// Returns the center of a Cylinder
+    fn center(&self) -> Point3 {
+        self.center
+    }
+
+    /// Returns the size of a Cylinder: [radius, length]
+    fn size(&self) -> [f64; 2] {
+        [self.radius, self.length]
+    }
+
+    /// Returns the magnetisation vector of a Cylinder: Point3 (jx, jy, jz)
+    fn magnetisation(self) -> Point3 {
+        Point3::new(self.jx, self.jy, self.jz)
+    }
+
+    /// Sets the magnet center
+    fn set_center(&mut self, point: Point3) {
+        self.center = point;
+    }
+
+    /// Sets the radius and length of a cylinder, and the internal value self.b
+    fn set_size(&mut self, size: [f64; 2]) {
+        self.radius = size[0];
+        self.length = size[1];
+        self.b = self.length / 2.0;
+    }
+
+    /// Set the magnetisation of the magnet using a cartesian vector.
+    ///
+    /// This method also updates self.jr, self.phi, and self.theta
+    fn set_magnetisation(&mut self, magnetisation: Point3) {
+        self.jr = magnetisation.magnitude();
+        self.phi = Angle::Radians(magnetisation.y.atan2(magnetisation.x));
+        self.theta = Angle::Radians(if self.jr > 0.0 {
+            (magnetisation.z / self.jr).acos()
+        } else {
+            0.0
+        });
+        self.jx = magnetisation.x;
+        self.jy = magnetisation.y;
+        self.jz = magnetisation.z;
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Cylinder {
+    /// Returns the magnetic field of a cylindrical magnet at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_cylinder(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Cylinder {
+    /// Returns the magnetic field of a cylindrical magnet at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_cylinder(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::comparison::nearly_equal;
+
+    use super::*;
+
+    #[test]
+    fn test_point3_cylinder_field() {
+        let magnet = Cylinder::default();
+
+        let point = Point3::new(0.0, 0.0, 0.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.x, 0.0_f64));
+        assert!(nearly_equal(field.y, 0.0_f64));
+        assert!(nearly_equal(field.z, 1.0 / 2.0_f64.sqrt()));
+    }
+
+    #[test]
+    fn test_tuple_cylinder_field() {
+        let magnet = Cylinder::default();
+
+        let point = (0.0, 0.0, 0.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.0, 0.0_f64));
+        assert!(nearly_equal(field.1, 0.0_f64));
+        assert!(nearly_equal(field.2, 1.0 / 2.0_f64.sqrt()));
+    }
+
+    #[test]
+    fn test_set_size() {
+        let mut magnet = Cylinder::default();
+        magnet.set_size([1.0, 4.0]);
+        assert!(nearly_equal(magnet.b, 2.0));
+    }
+
+    #[test]
+    fn test_set_magnetisation() {
+        let mut magnet = Cylinder::default();
+        magnet.set_magnetisation(Point3::new(0.0, 1.0, 0.0));
+        assert!(nearly_equal(magnet.jr, 1.0));
+        assert!(nearly_equal(magnet.theta.to_degrees(), 90.0));
+        assert!(nearly_equal(magnet.phi.to_degrees(), 90.0));
+    }
+}
@@ -0,0 +1,362 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for cylinders
+//!
+//! An axially magnetised cylinder is equivalent to a solenoid with a surface
+//! current density $`K = J_r/\mu_0`$ on its curved face, so its field is
+//! calculated using the solenoid routine, which is written in terms of
+//! Bulirsch's complete elliptic integral.
+//!
+//! A diametrically magnetised cylinder has a surface charge density
+//! $`\sigma = J \cos \varphi`$ on its curved face. Its field is reduced to
+//! complete elliptic integrals following Caciagli et al., J. Magn. Magn. Mater.
+//! 456, 423 (2018), again evaluated using Bulirsch's `cel`.
+//!
+//! A magnet with a magnetisation at an arbitrary angle is the sum of the two.
+
+#![allow(clippy::many_single_char_names)]
+use crate::magnets::magnet3d::bulirsch::cel;
+use crate::magnets::magnet3d::{solenoid_field_cartesian, Cylinder};
+use crate::points::{Point3, Points3};
+use crate::{MagnetError, FP_CUTOFF, I_4PI, M2_PI};
+
+/// Below this fraction of the radius, the diametric field is evaluated by
+/// quadrature, as the elliptic integral expressions suffer from cancellation
+const NEAR_AXIS: f64 = 0.1;
+
+/// Number of quadrature points for the diametric field near the axis
+const NEAR_AXIS_STEPS: usize = 32;
+
+/// Returns the magnetic field vector due to a cylinder of radius `R` and
+/// length `2b` centered at the origin, with an arbitrary magnetisation
+/// $`\mathbf{J} = J_x \mathbf{\hat{x}} + J_y \mathbf{\hat{y}} + J_z \mathbf{\hat{z}}`$
+///
+pub fn get_field_cylinder(magnet: &Cylinder, point: &Point3) -> Result<Point3, MagnetError> {
+    let mut field = Point3::zero();
+
+    // Translate and rotate into the local coordinates of the magnet
+    let orientation = magnet.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - magnet.center));
+
+    field += if (magnet.jz / magnet.jr).abs() > FP_CUTOFF {
+        axial_field(magnet, &local_point)?
+    } else {
+        Point3::zero()
+    };
+
+    let j_diametric = (magnet.jx.powi(2) + magnet.jy.powi(2)).sqrt();
+    field += if (j_diametric / magnet.jr).abs() > FP_CUTOFF {
+        diametric_field(magnet, &local_point)?
+    } else {
+        Point3::zero()
+    };
+
+    // Rotate the field back into the global frame
+    Ok(orientation.rotate(&field))
+}
+
+/// Returns the magnetic field vector at a point due to an axially magnetised
+/// cylinder, converting the cylindrical (Bz, Br) components to cartesian.
+fn axial_field(magnet: &Cylinder, point: &Point3) -> Result<Point3, MagnetError> {
+    solenoid_field_cartesian(magnet.radius, magnet.b, magnet.jz, point)
+}
+
+/// Returns the magnetic field vector at a point due to a diametrically
+/// magnetised cylinder.
+///
+/// The point is rotated about z so that the magnetisation lies along x, the
+/// field is calculated, and then rotated back.
+fn diametric_field(magnet: &Cylinder, point: &Point3) -> Result<Point3, MagnetError> {
+    let j_diametric = (magnet.jx.powi(2) + magnet.jy.powi(2)).sqrt();
+    let cos_m = magnet.jx / j_diametric;
+    let sin_m = magnet.jy / j_diametric;
+
+    let x = point.x * cos_m + point.y * sin_m;
+    let y = -point.x * sin_m + point.y * cos_m;
+
+    let field = diametric_field_x(magnet.radius, magnet.b, j_diametric, x, y, point.z)?;
+
+    Ok(Point3::new(
+        field.x * cos_m - field.y * sin_m,
+        field.x * sin_m + field.y * cos_m,
+        field.z,
+    ))
+}
+
+/// Returns the magnetic field vector at a point due to a cylinder of radius
+/// `radius` and length `2b`, magnetised along x with magnitude `j`
+fn diametric_field_x(
+    radius: f64,
+    b: f64,
+    j: f64,
+    x: f64,
+    y: f64,
+    z: f64,
+) -> Result<Point3, MagnetError> {
+    let rho = (x.powi(2) + y.powi(2)).sqrt();
+
+    let mut field = if rho / radius < NEAR_AXIS {
+        diametric_field_near_axis(radius, b, j, x, y, z)
+    } else {
+        let cos_phi = x / rho;
+        let sin_phi = y / rho;
+
+        let mut h_rho = 0.0;
+        let mut h_phi = 0.0;
+        let mut h_z = 0.0;
+
+        for &(zeta, sign) in &[(z + b, 1.0), (z - b, -1.0)] {
+            let (i_1, i_2, i_3) = diametric_integrals(radius, rho, zeta);
+            h_rho += sign
+                * zeta
+                * ((rho.powi(2) - radius.powi(2)) / (2.0 * rho) * i_1 + i_2 / (2.0 * rho));
+            h_phi += sign * zeta * i_3;
+            h_z -= sign * i_2;
+        }
+
+        let b_rho = cos_phi * h_rho * j * radius * I_4PI;
+        let b_phi = sin_phi * h_phi * j * radius.powi(2) * I_4PI;
+        let b_z = cos_phi * h_z * j * radius * I_4PI;
+
+        Point3::new(
+            b_rho * cos_phi - b_phi * sin_phi,
+            b_rho * sin_phi + b_phi * cos_phi,
+            b_z,
+        )
+    };
+
+    // Points on the edges of the magnet are singular, bind to 0.0
+    if !field.x.is_finite() {
+        field.x = 0.0;
+    }
+    if !field.y.is_finite() {
+        field.y = 0.0;
+    }
+    if !field.z.is_finite() {
+        field.z = 0.0;
+    }
+
+    // Include the magnetisation inside the magnet, to return B rather than μ0 H
+    if rho < radius && z.abs() < b {
+        field.x += j;
+    }
+
+    Ok(field)
+}
+
+/// Returns the three integrals over the azimuth of the charged surface needed
+/// for the diametric field, in terms of Bulirsch's complete elliptic integral
+fn diametric_integrals(radius: f64, rho: f64, zeta: f64) -> (f64, f64, f64) {
+    let q = (rho + radius).powi(2) + zeta.powi(2);
+    let sqrt_q = q.sqrt();
+    let kc = (((rho - radius).powi(2) + zeta.powi(2)) / q).sqrt();
+    let gamma_sq = ((radius - rho) / (radius + rho)).powi(2);
+
+    let i_1 = 4.0 * cel(kc, gamma_sq, -1.0, 1.0) / ((rho + radius).powi(2) * sqrt_q);
+    let i_2 = 4.0 * cel(kc, 1.0, -1.0, 1.0) / sqrt_q;
+    let i_3 = (4.0 / sqrt_q)
+        * (cel(kc, 1.0, 0.0, 1.0) / (rho * radius)
+            + (radius - rho).powi(2) / (4.0 * rho.powi(2) * radius.powi(2))
+                * (cel(kc, 1.0, 1.0, 1.0) - cel(kc, gamma_sq, 1.0, 1.0)));
+
+    (i_1, i_2, i_3)
+}
+
+/// Returns $`\mu_0 \mathbf{H}`$ near the axis of a diametrically magnetised
+/// cylinder, by integrating over the length analytically and over the azimuth
+/// numerically using the trapezoidal rule, which converges rapidly for this
+/// periodic integrand away from the curved surface.
+fn diametric_field_near_axis(radius: f64, b: f64, j: f64, x: f64, y: f64, z: f64) -> Point3 {
+    let mut field = Point3::zero();
+    let steps = NEAR_AXIS_STEPS as f64;
+
+    for i in 0..NEAR_AXIS_STEPS {
+        let psi = M2_PI * (i as f64 + 0.5) / steps;
+        let dx = x - radius * psi.cos();
+        let dy = y - radius * psi.sin();
+        let d_sq = dx.powi(2) + dy.powi(2);
+        let weight = j * radius * psi.cos() / (2.0 * steps);
+
+        let top = (d_sq + (z + b).powi(2)).sqrt();
+        let bottom = (d_sq + (z - b).powi(2)).sqrt();
+        let planar = ((z + b) / top - (z - b) / bottom) / d_sq;
+
+        field.x += weight * dx * planar;
+        field.y += weight * dy * planar;
+        field.z += weight * (1.0 / bottom - 1.0 / top);
+    }
+
+    field
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::magnets::magnet3d::cylinder_field::*;
+    use crate::points::Point3;
+    use crate::utils::comparison::nearly_equal;
+    use crate::utils::conversions::Angle;
+    use crate::M4_PI;
+
+    fn rod(phi: f64, theta: f64) -> Cylinder {
+        Cylinder::new(
+            1.0,
+            4.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(phi),
+            Angle::Degrees(theta),
+        )
+    }
+
+    /// On-axis field of an axially magnetised cylinder
+    fn on_axis(radius: f64, b: f64, z: f64) -> f64 {
+        0.5 * ((z + b) / ((z + b).powi(2) + radius.powi(2)).sqrt()
+            - (z - b) / ((z - b).powi(2) + radius.powi(2)).sqrt())
+    }
+
+    #[test]
+    fn field_on_axis() {
+        let magnet = rod(0.0, 0.0);
+        for &z in &[0.0, 1.0, 2.5, -3.0, 10.0] {
+            let field = get_field_cylinder(&magnet, &Point3::new(0.0, 0.0, z)).unwrap();
+            assert!(nearly_equal(field.x, 0.0));
+            assert!(nearly_equal(field.y, 0.0));
+            assert!(nearly_equal(field.z, on_axis(1.0, 2.0, z)));
+        }
+    }
+
+    #[test]
+    fn field_off_axis_is_axisymmetric() {
+        let magnet = rod(0.0, 0.0);
+        let field_x = get_field_cylinder(&magnet, &Point3::new(1.5, 0.0, 2.5)).unwrap();
+        let field_y = get_field_cylinder(&magnet, &Point3::new(0.0, 1.5, 2.5)).unwrap();
+
+        assert!(field_x.x > 0.0);
+        assert!(nearly_equal(field_x.y, 0.0));
+        assert!(nearly_equal(field_x.x, field_y.y));
+        assert!(nearly_equal(field_x.z, field_y.z));
+    }
+
+    #[test]
+    fn far_field_dipole() {
+        let magnet = Cylinder::default();
+        let distance = 50.0_f64;
+        let volume = crate::PI * magnet.radius.powi(2) * magnet.length;
+        let dipole = volume / (M4_PI * distance.powi(3));
+
+        let along = get_field_cylinder(&magnet, &Point3::new(0.0, 0.0, distance)).unwrap();
+        let side = get_field_cylinder(&magnet, &Point3::new(distance, 0.0, 0.0)).unwrap();
+
+        assert!(((along.z - 2.0 * dipole) / dipole).abs() < 1e-3);
+        assert!(((side.z + dipole) / dipole).abs() < 1e-3);
+        assert!((side.x / dipole).abs() < 1e-3);
+    }
+
+    #[test]
+    fn field_on_edge_is_finite() {
+        let magnet = rod(30.0, 45.0);
+        for point in &[Point3::new(1.0, 0.0, 2.0), Point3::new(0.0, 1.0, 0.0)] {
+            let field = get_field_cylinder(&magnet, point).unwrap();
+            assert!(field.x.is_finite() && field.y.is_finite() && field.z.is_finite());
+        }
+    }
+
+    #[test]
+    fn diametric_field_on_axis() {
+        // Inside the magnet B = J - (J/2) * (on-axis field of the axial case)
+        let magnet = rod(0.0, 90.0);
+        for &(z, inside) in &[(0.0, 1.0), (1.5, 1.0), (-3.0, 0.0), (10.0, 0.0)] {
+            let field = get_field_cylinder(&magnet, &Point3::new(0.0, 0.0, z)).unwrap();
+            assert!(nearly_equal(field.x, inside - on_axis(1.0, 2.0, z) / 2.0));
+            assert!(nearly_equal(field.y, 0.0));
+            assert!(nearly_equal(field.z, 0.0));
+        }
+    }
+
+    #[test]
+    fn diametric_closed_form_matches_quadrature() {
+        for point in &[
+            Point3::new(0.3, 0.2, 0.2),
+            Point3::new(0.2, -0.3, 2.5),
+            Point3::new(-0.35, 0.1, -1.9),
+        ] {
+            let closed = diametric_field_x(1.0, 2.0, 1.0, point.x, point.y, point.z).unwrap();
+            let mut quadrature =
+                diametric_field_near_axis(1.0, 2.0, 1.0, point.x, point.y, point.z);
+            if point.z.abs() < 2.0 {
+                quadrature.x += 1.0;
+            }
+            assert!((closed.x - quadrature.x).abs() < 1e-8);
+            assert!((closed.y - quadrature.y).abs() < 1e-8);
+            assert!((closed.z - quadrature.z).abs() < 1e-8);
+        }
+    }
+
+    #[test]
+    fn diametric_far_field_dipole() {
+        let magnet = Cylinder::new(
+            0.5,
+            1.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(90.0),
+        );
+        let distance = 50.0_f64;
+        let volume = crate::PI * magnet.radius.powi(2) * magnet.length;
+        let dipole = volume / (M4_PI * distance.powi(3));
+
+        let along = get_field_cylinder(&magnet, &Point3::new(0.0, distance, 0.0)).unwrap();
+        let side = get_field_cylinder(&magnet, &Point3::new(0.0, 0.0, distance)).unwrap();
+
+        assert!(((along.y - 2.0 * dipole) / dipole).abs() < 1e-3);
+        assert!(((side.y + dipole) / dipole).abs() < 1e-3);
+    }
+
+    #[test]
+    fn tilted_field_is_sum_of_parts() {
+        let point = Point3::new(0.7, -1.2, 1.4);
+        let tilted = get_field_cylinder(&rod(60.0, 30.0), &point).unwrap();
+        let axial = get_field_cylinder(&rod(0.0, 0.0), &point).unwrap();
+        let diametric = get_field_cylinder(&rod(60.0, 90.0), &point).unwrap();
+
+        let cos_t = crate::PI_6.cos();
+        let sin_t = crate::PI_6.sin();
+        assert!(nearly_equal(
+            tilted.x,
+            axial.x * cos_t + diametric.x * sin_t
+        ));
+        assert!(nearly_equal(
+            tilted.y,
+            axial.y * cos_t + diametric.y * sin_t
+        ));
+        assert!(nearly_equal(
+            tilted.z,
+            axial.z * cos_t + diametric.z * sin_t
+        ));
+    }
+
+    #[test]
+    fn rotated_cylinder_field() {
+        // Rotating by -90° about x maps the cylinder axis onto y, so
+        // B(x, y, z) = (Bx, Bz, -By) evaluated at (x, -z, y) in the magnet frame
+        let mut magnet = rod(30.0, 45.0);
+        magnet.gamma = Angle::Degrees(-90.0);
+        let field = get_field_cylinder(&magnet, &Point3::new(0.3, 1.5, 0.2)).unwrap();
+        let comp_field =
+            get_field_cylinder(&rod(30.0, 45.0), &Point3::new(0.3, -0.2, 1.5)).unwrap();
+
+        assert!((field.x - comp_field.x).abs() < 1e-12);
+        assert!((field.y - comp_field.z).abs() < 1e-12);
+        assert!((field.z + comp_field.y).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,85 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+use crate::magnets::{GetField, Magnet3D};
+use crate::points::{Point3, Points3};
+use crate::MagnetError;
+
+/// Loops over every 3D Magnet and gets the field at a point `point` .
+///
+/// By using an enum `Magnet3D` we can match each magnet type to access the underlying methods.
+pub fn loop_field_3d(magnet_list: &[Magnet3D], point: &Point3) -> Result<Point3, MagnetError> {
+    let mut local_field = Point3::zero();
+
+    // loop over magnets in list
+    for mag in magnet_list {
+        // sum fields for each magnet type
+        match mag {
+            Magnet3D::Prism(magnet) => local_field += magnet.field(point)?,
+            Magnet3D::Cylinder(magnet) => local_field += magnet.field(point)?,
+            Magnet3D::Sphere(magnet) => local_field += magnet.field(point)?,
+            Magnet3D::Polyhedron(magnet) => local_field += magnet.field(point)?,
+            Magnet3D::Solenoid(coil) => local_field += coil.field(point)?,
+            Magnet3D::ThickCoil(coil) => local_field += coil.field(point)?,
+            Magnet3D::CurrentLoop(coil) => local_field += coil.field(point)?,
+        }
+    }
+    Ok(local_field)
+}
+
+/// Returns the magnetic field due to an array of magnets `magnet_list`, at a point `point`
+pub fn get_field_3d(
+    magnet_list: &[Magnet3D],
+    point: (&f64, &f64, &f64),
+) -> Result<(f64, f64, f64), MagnetError> {
+    let point = Point3::new(*point.0, *point.1, *point.2);
+    let local_field = loop_field_3d(magnet_list, &point)?;
+
+    Ok(local_field.as_tuple())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        magnets::{Prism, Sphere},
+        utils::{comparison::nearly_equal, conversions::Angle},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_loop_3d() {
+        let mut magnet_list = Vec::<Magnet3D>::new();
+
+        // Create Magnets
+        let m1 = Prism::default();
+        magnet_list.push(Magnet3D::Prism(m1));
+
+        let m2 = Sphere::new(
+            1.0,
+            (0.0, 0.0, 3.0),
+            -1.0,
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+        );
+        magnet_list.push(Magnet3D::Sphere(m2));
+
+        // Create Test Point
+        let point = Point3::new(0.0, 0.0, 0.0);
+
+        // Get local field for all magnets in the registry
+        let local_field = loop_field_3d(&magnet_list, &point).unwrap();
+        let comp_field = m1.field(&point).unwrap().z + m2.field(&point).unwrap().z;
+        assert!(nearly_equal(local_field.z, comp_field));
+        assert!(nearly_equal(local_field.z, 2.0 / 3.0 - 2.0 / 81.0));
+    }
+
+    #[test]
+    fn test_get_field_3d() {
+        let magnet_list = vec![Magnet3D::Sphere(Sphere::default())];
+        let field = get_field_3d(&magnet_list, (&0.0, &0.0, &0.5)).unwrap();
+        assert!(nearly_equal(field.2, 2.0 / 3.0));
+    }
+}
@@ -0,0 +1,844 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! # Forces and torques between 3D magnets
+//!
+//! A uniformly magnetised magnet is equivalent to a surface charge density
+//! $`\sigma = \mathbf{J} \cdot \mathbf{\hat{n}}`$ on its boundary, so the force
+//! on it due to an external field $`\mathbf{B}`$ is
+//!
+//! ```math
+//! \mathbf{F} = \frac{1}{\mu_0} \oint \sigma \mathbf{B} \, dA
+//! ```
+//!
+//! and the torque about a pivot $`\mathbf{p}`$ is
+//! $`\boldsymbol{\tau} = \frac{1}{\mu_0} \oint \sigma (\mathbf{r} - \mathbf{p}) \times \mathbf{B} \, dA`$.
+//! Coils carry no magnetic charge, and instead feel the Lorentz force
+//! $`\mathbf{F} = \oint I \, d\mathbf{l} \times \mathbf{B}`$.
+//!
+//! ## Closed form for prisms
+//!
+//! When the edges of two prisms are parallel, each pair of charged faces
+//! interacts with a force given by the closed form of Akoun and Yonnet,
+//! IEEE Trans. Magn. 20, 1962 (1984), extended to perpendicular faces so that
+//! both magnetisations may point in any direction. With
+//! $`(u, v, w) = \mathbf{r}_2 - \mathbf{r}_1`$ evaluated at the corners of the
+//! two faces, parallel faces normal to $`w`$ use
+//!
+//! ```math
+//! \phi_u = \frac{v^2 - w^2}{2} \ln(r - u) + uv \ln(r - v) + vw \tan^{-1}\frac{uv}{rw} + \frac{ru}{2}
+//! ```
+//!
+//! ```math
+//! \phi_w = -uw \ln(r - u) - vw \ln(r - v) + uv \tan^{-1}\frac{uv}{rw} - rw
+//! ```
+//!
+//! while perpendicular faces also use the triple integral of $`1/r`$,
+//!
+//! ```math
+//! \phi_{uvw} = uv \ln(w + r) + vw \ln(u + r) + wu \ln(v + r)
+//! - \frac{u^2}{2} \tan^{-1}\frac{vw}{ur} - \frac{v^2}{2} \tan^{-1}\frac{uw}{vr} - \frac{w^2}{2} \tan^{-1}\frac{uv}{wr}
+//! ```
+//!
+//! The closed form loses precision through cancellation when the separation
+//! is much larger than the magnets, where the point dipole approximation applies.
+//!
+//! Prisms with misaligned edges, and all other magnets, fall back on Gauss-Legendre
+//! quadrature over their surfaces. With lengths in m and magnetisations in T,
+//! forces are returned in N and torques in N m.
+
+#![allow(clippy::many_single_char_names)]
+use crate::magnets::magnet2d::GAUSS_NODES;
+use crate::magnets::magnet3d::{
+    CurrentLoop, Cylinder, Polyhedron, Prism, Solenoid, Sphere, ThickCoil,
+};
+use crate::magnets::{loop_field_3d, Magnet3D};
+use crate::points::{Point3, Points3};
+use crate::utils::quaternion::Quaternion;
+use crate::{MagnetError, FP_CUTOFF, I_4PI, M2_PI, MU0, PI_2};
+
+/// Number of segments each edge of a surface is divided into for quadrature
+const FACE_SEGMENTS: usize = 4;
+
+/// Number of quadrature points around the circumference of curved surfaces
+const AZIMUTHAL_NODES: usize = 128;
+
+/// Returns the force on `magnet_2` due to `magnet_1`.
+///
+/// The closed form is used if the edges of the two prisms are parallel,
+/// otherwise the force is found by surface integration.
+pub fn force_between(magnet_1: &Prism, magnet_2: &Prism) -> Result<Point3, MagnetError> {
+    match parallel_axes(&magnet_1.orientation(), &magnet_2.orientation()) {
+        Some(axes) => Ok(akoun_yonnet_force(magnet_1, magnet_2, &axes)),
+        None => {
+            let (force, _) = numerical_force_torque(
+                &Magnet3D::Prism(*magnet_1),
+                &Magnet3D::Prism(*magnet_2),
+                &magnet_2.center,
+            )?;
+            Ok(force)
+        }
+    }
+}
+
+/// Returns the torque about `pivot` on `magnet_2` due to `magnet_1`, found by
+/// surface integration
+pub fn torque_between(
+    magnet_1: &Prism,
+    magnet_2: &Prism,
+    pivot: &Point3,
+) -> Result<Point3, MagnetError> {
+    let (_, torque) = numerical_force_torque(
+        &Magnet3D::Prism(*magnet_1),
+        &Magnet3D::Prism(*magnet_2),
+        pivot,
+    )?;
+    Ok(torque)
+}
+
+/// Returns the force on `magnet_2` due to `magnet_1`, and the torque about
+/// `pivot`
+pub fn force_torque_between(
+    magnet_1: &Prism,
+    magnet_2: &Prism,
+    pivot: &Point3,
+) -> Result<(Point3, Point3), MagnetError> {
+    pair_force_torque(
+        &Magnet3D::Prism(*magnet_1),
+        &Magnet3D::Prism(*magnet_2),
+        pivot,
+    )
+}
+
+/// Returns the force on `target` due to `source`, and the torque about
+/// `pivot`, by integrating over the surface charges or currents of the target
+pub fn numerical_force_torque(
+    source: &Magnet3D,
+    target: &Magnet3D,
+    pivot: &Point3,
+) -> Result<(Point3, Point3), MagnetError> {
+    let source = std::slice::from_ref(source);
+
+    let mut force = Point3::zero();
+    let mut torque = Point3::zero();
+    for element in force_elements(target) {
+        let field = loop_field_3d(source, &element.position)?;
+        let element_force = element.force(&field);
+        force += element_force;
+        torque += (element.position - *pivot).cross(&element_force);
+    }
+    Ok((force, torque))
+}
+
+/// Returns the force on `target` due to `source`, and the torque about
+/// `pivot`, using the closed form force between prisms where possible
+fn pair_force_torque(
+    source: &Magnet3D,
+    target: &Magnet3D,
+    pivot: &Point3,
+) -> Result<(Point3, Point3), MagnetError> {
+    if let (Magnet3D::Prism(magnet_1), Magnet3D::Prism(magnet_2)) = (source, target) {
+        if let Some(axes) = parallel_axes(&magnet_1.orientation(), &magnet_2.orientation()) {
+            // Only the torque needs the surface integration
+            let (_, torque) = numerical_force_torque(source, target, pivot)?;
+            return Ok((akoun_yonnet_force(magnet_1, magnet_2, &axes), torque));
+        }
+    }
+    numerical_force_torque(source, target, pivot)
+}
+
+/// Returns the force on `target` due to `source`, using the closed form force
+/// between prisms where possible
+fn pair_force(source: &Magnet3D, target: &Magnet3D) -> Result<Point3, MagnetError> {
+    match (source, target) {
+        (Magnet3D::Prism(magnet_1), Magnet3D::Prism(magnet_2)) => force_between(magnet_1, magnet_2),
+        _ => Ok(numerical_force_torque(source, target, &Point3::zero())?.0),
+    }
+}
+
+/// Returns the matrix of pairwise forces between the magnets in a list, where
+/// element `[i][j]` is the force on magnet `i` due to magnet `j`
+pub fn get_force_matrix_3d(magnet_list: &[Magnet3D]) -> Result<Vec<Vec<Point3>>, MagnetError> {
+    magnet_list
+        .iter()
+        .enumerate()
+        .map(|(i, target)| {
+            magnet_list
+                .iter()
+                .enumerate()
+                .map(|(j, source)| {
+                    if i == j {
+                        Ok(Point3::zero())
+                    } else {
+                        pair_force(source, target)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the force and the torque about `pivot` on the magnet at `index`,
+/// due to all other magnets in the list
+pub fn get_force_torque_3d(
+    magnet_list: &[Magnet3D],
+    index: usize,
+    pivot: &Point3,
+) -> Result<(Point3, Point3), MagnetError> {
+    let target = magnet_list
+        .get(index)
+        .ok_or(MagnetError::MagnetIndexError(index))?;
+
+    let mut force = Point3::zero();
+    let mut torque = Point3::zero();
+    for (j, source) in magnet_list.iter().enumerate() {
+        if j != index {
+            let (pair_force, pair_torque) = pair_force_torque(source, target, pivot)?;
+            force += pair_force;
+            torque += pair_torque;
+        }
+    }
+    Ok((force, torque))
+}
+
+/// Returns, for each local axis of the second orientation, the local axis of
+/// the first it lies along and its direction, or None if the axes are not parallel
+fn parallel_axes(
+    orientation_1: &Quaternion,
+    orientation_2: &Quaternion,
+) -> Option<[(usize, f64); 3]> {
+    let relative = orientation_1.inverse() * *orientation_2;
+
+    let mut axes = [(0, 1.0); 3];
+    for (i, unit) in [Point3::i_hat(), Point3::j_hat(), Point3::k_hat()]
+        .iter()
+        .enumerate()
+    {
+        let image = relative.rotate(unit).as_array();
+        let axis = (0..3).fold(0, |best, axis| {
+            if image[axis].abs() > image[best].abs() {
+                axis
+            } else {
+                best
+            }
+        });
+        if 1.0 - image[axis].abs() > FP_CUTOFF * FP_CUTOFF {
+            return None;
+        }
+        axes[i] = (axis, image[axis].signum());
+    }
+    Some(axes)
+}
+
+/// A uniformly charged face of a prism, normal to `axis` at `position`,
+/// spanning `lower` to `upper` along the other two axes
+struct Face {
+    axis: usize,
+    position: f64,
+    lower: [f64; 3],
+    upper: [f64; 3],
+    sigma: f64,
+}
+
+/// Returns the charged faces of a prism with half sizes `half` and
+/// magnetisation `magnetisation`, centred at `center`
+fn prism_faces(center: &[f64; 3], half: &[f64; 3], magnetisation: &[f64; 3]) -> Vec<Face> {
+    let mut faces = Vec::with_capacity(6);
+    for axis in 0..3 {
+        for &sign in &[-1.0, 1.0] {
+            let sigma = sign * magnetisation[axis];
+            if sigma == 0.0 {
+                continue;
+            }
+            let mut lower = [0.0; 3];
+            let mut upper = [0.0; 3];
+            for other in 0..3 {
+                lower[other] = center[other] - half[other];
+                upper[other] = center[other] + half[other];
+            }
+            faces.push(Face {
+                axis,
+                position: center[axis] + sign * half[axis],
+                lower,
+                upper,
+                sigma,
+            });
+        }
+    }
+    faces
+}
+
+/// Returns the closed form force on `magnet_2` due to `magnet_1`, given the
+/// alignment of their axes from `parallel_axes`
+fn akoun_yonnet_force(magnet_1: &Prism, magnet_2: &Prism, axes: &[(usize, f64); 3]) -> Point3 {
+    // Work in the local frame of the first magnet
+    let orientation = magnet_1.orientation();
+    let displacement = orientation
+        .rotate_inverse(&(magnet_2.center - magnet_1.center))
+        .as_array();
+
+    let half_1 = [magnet_1.a, magnet_1.b, magnet_1.c];
+    let magnetisation_1 = [magnet_1.jx, magnet_1.jy, magnet_1.jz];
+
+    let sizes = [magnet_2.a, magnet_2.b, magnet_2.c];
+    let components = [magnet_2.jx, magnet_2.jy, magnet_2.jz];
+    let mut half_2 = [0.0; 3];
+    let mut magnetisation_2 = [0.0; 3];
+    for (i, &(axis, sign)) in axes.iter().enumerate() {
+        half_2[axis] = sizes[i];
+        magnetisation_2[axis] = sign * components[i];
+    }
+
+    // Which side of the first magnet the second lies on, resolving touching faces
+    let mut side = [0.0; 3];
+    for axis in 0..3 {
+        if displacement[axis] != 0.0 {
+            side[axis] = displacement[axis].signum();
+        }
+    }
+
+    let faces_1 = prism_faces(&[0.0; 3], &half_1, &magnetisation_1);
+    let faces_2 = prism_faces(&displacement, &half_2, &magnetisation_2);
+
+    let mut force = [0.0; 3];
+    for face_1 in &faces_1 {
+        for face_2 in &faces_2 {
+            let integral = face_pair_integral(face_1, face_2, &side);
+            for axis in 0..3 {
+                force[axis] += face_1.sigma * face_2.sigma * integral[axis];
+            }
+        }
+    }
+
+    let force = Point3::new(force[0], force[1], force[2]).scale(I_4PI / MU0);
+    orientation.rotate(&force)
+}
+
+/// Returns $`\int\int (\mathbf{r}_2 - \mathbf{r}_1)/|\mathbf{r}_2 - \mathbf{r}_1|^3 dA_1 dA_2`$
+/// over two faces, as a sum over their corners
+fn face_pair_integral(face_1: &Face, face_2: &Face, side: &[f64; 3]) -> [f64; 3] {
+    // Along each axis, the differences between the ends of the faces and their signs
+    let mut corners: Vec<Vec<(f64, f64)>> = Vec::with_capacity(3);
+    for axis in 0..3 {
+        let ends_1 = if axis == face_1.axis {
+            vec![(face_1.position, -1.0)]
+        } else {
+            vec![(face_1.lower[axis], -1.0), (face_1.upper[axis], 1.0)]
+        };
+        let ends_2 = if axis == face_2.axis {
+            vec![(face_2.position, 1.0)]
+        } else {
+            vec![(face_2.lower[axis], -1.0), (face_2.upper[axis], 1.0)]
+        };
+
+        let mut differences = Vec::with_capacity(4);
+        for &(end_1, sign_1) in &ends_1 {
+            for &(end_2, sign_2) in &ends_2 {
+                differences.push((end_2 - end_1, -sign_1 * sign_2));
+            }
+        }
+        corners.push(differences);
+    }
+
+    let i = face_1.axis;
+    let j = face_2.axis;
+    let mut integral = [0.0; 3];
+    for &(d_0, s_0) in &corners[0] {
+        for &(d_1, s_1) in &corners[1] {
+            for &(d_2, s_2) in &corners[2] {
+                let d = [d_0, d_1, d_2];
+                let sign = s_0 * s_1 * s_2;
+                if i == j {
+                    // Parallel faces
+                    let (p, q) = ((i + 1) % 3, (i + 2) % 3);
+                    integral[p] += sign * phi_tangential(d[p], d[q], d[i]);
+                    integral[q] += sign * phi_tangential(d[q], d[p], d[i]);
+                    integral[i] += sign * phi_normal(d[p], d[q], d[i], side[i]);
+                } else {
+                    // Perpendicular faces, sharing the axis k
+                    let k = 3 - i - j;
+                    integral[i] += sign * phi_tangential(d[j], d[k], d[i]);
+                    integral[k] -= sign * phi_triple(d[i], d[k], d[j]);
+                    integral[j] += sign * phi_tangential(d[i], d[k], d[j]);
+                }
+            }
+        }
+    }
+    integral
+}
+
+/// Returns $`r - u`$, where `rest_sq` is $`r^2 - u^2`$, avoiding the cancellation for u > 0
+fn r_minus(u: f64, r: f64, rest_sq: f64) -> f64 {
+    if u > 0.0 {
+        rest_sq / (r + u)
+    } else {
+        r - u
+    }
+}
+
+/// Returns $`r + u`$, where `rest_sq` is $`r^2 - u^2`$, avoiding the cancellation for u < 0
+fn r_plus(u: f64, r: f64, rest_sq: f64) -> f64 {
+    if u < 0.0 {
+        rest_sq / (r - u)
+    } else {
+        r + u
+    }
+}
+
+/// Returns `coefficient * ln(argument)`, which vanishes with its coefficient
+fn x_ln(coefficient: f64, argument: f64) -> f64 {
+    if coefficient == 0.0 {
+        0.0
+    } else {
+        coefficient * argument.ln()
+    }
+}
+
+/// Returns `coefficient * atan(numerator / denominator)`, which vanishes with its coefficient
+fn x_atan(coefficient: f64, numerator: f64, denominator: f64) -> f64 {
+    if coefficient == 0.0 {
+        0.0
+    } else {
+        coefficient * (numerator / denominator).atan()
+    }
+}
+
+/// Akoun-Yonnet function $`\phi_u`$, whose derivative
+/// $`\partial_u \partial_v^2 \phi_u = -1/r`$
+fn phi_tangential(u: f64, v: f64, w: f64) -> f64 {
+    let (u_sq, v_sq, w_sq) = (u * u, v * v, w * w);
+    let r = (u_sq + v_sq + w_sq).sqrt();
+    x_ln(0.5 * (v_sq - w_sq), r_minus(u, r, v_sq + w_sq))
+        + x_ln(u * v, r_minus(v, r, u_sq + w_sq))
+        + x_atan(v * w, u * v, r * w)
+        + 0.5 * r * u
+}
+
+/// Akoun-Yonnet function $`\phi_w`$, whose derivative
+/// $`\partial_u^2 \partial_v^2 \phi_w = w/r^3`$.
+///
+/// For coplanar faces, w = 0, the limit is taken from the `side` of the
+/// plane the second face lies on.
+fn phi_normal(u: f64, v: f64, w: f64, side: f64) -> f64 {
+    let (u_sq, v_sq, w_sq) = (u * u, v * v, w * w);
+    let r = (u_sq + v_sq + w_sq).sqrt();
+    let angle_term = if w == 0.0 {
+        side * (u * v).abs() * PI_2
+    } else {
+        x_atan(u * v, u * v, r * w)
+    };
+    -x_ln(u * w, r_minus(u, r, v_sq + w_sq)) - x_ln(v * w, r_minus(v, r, u_sq + w_sq)) + angle_term
+        - r * w
+}
+
+/// Returns $`\phi_{uvw}`$, whose derivative $`\partial_u \partial_v \partial_w \phi_{uvw} = 1/r`$
+fn phi_triple(u: f64, v: f64, w: f64) -> f64 {
+    let (u_sq, v_sq, w_sq) = (u * u, v * v, w * w);
+    let r = (u_sq + v_sq + w_sq).sqrt();
+    x_ln(u * v, r_plus(w, r, u_sq + v_sq))
+        + x_ln(v * w, r_plus(u, r, v_sq + w_sq))
+        + x_ln(w * u, r_plus(v, r, u_sq + w_sq))
+        - 0.5 * x_atan(u_sq, v * w, u * r)
+        - 0.5 * x_atan(v_sq, u * w, v * r)
+        - 0.5 * x_atan(w_sq, u * v, w * r)
+}
+
+/// A quadrature point on a magnet or coil, carrying a magnetic charge
+/// $`\sigma dA`$ in T m², and a current element $`I d\mathbf{l}`$ in A m
+struct ForceElement {
+    position: Point3,
+    charge: f64,
+    current: Point3,
+}
+
+impl ForceElement {
+    fn charge(position: Point3, charge: f64) -> ForceElement {
+        ForceElement {
+            position,
+            charge,
+            current: Point3::zero(),
+        }
+    }
+
+    fn current(position: Point3, current: Point3) -> ForceElement {
+        ForceElement {
+            position,
+            charge: 0.0,
+            current,
+        }
+    }
+
+    /// Moves an element from the local frame of a magnet into the global frame
+    fn transform(self, orientation: &Quaternion, center: &Point3) -> ForceElement {
+        ForceElement {
+            position: *center + orientation.rotate(&self.position),
+            charge: self.charge,
+            current: orientation.rotate(&self.current),
+        }
+    }
+
+    /// Returns the force on the element due to a field `field`
+    fn force(&self, field: &Point3) -> Point3 {
+        field.scale(self.charge / MU0) + self.current.cross(field)
+    }
+}
+
+/// Returns the Gauss-Legendre nodes and weights on [start, end], which is
+/// divided into `segments`
+fn gauss_points(start: f64, end: f64, segments: usize) -> Vec<(f64, f64)> {
+    let length = (end - start) / segments as f64;
+    let mut points = Vec::with_capacity(segments * GAUSS_NODES.len());
+    for segment in 0..segments {
+        let midpoint = start + length * (segment as f64 + 0.5);
+        for &(node, weight) in &GAUSS_NODES {
+            points.push((midpoint + node * length / 2.0, weight * length / 2.0));
+        }
+    }
+    points
+}
+
+/// Returns the azimuthal angles and their spacing for the trapezoidal rule
+fn azimuthal_points() -> (Vec<f64>, f64) {
+    let d_phi = M2_PI / AZIMUTHAL_NODES as f64;
+    let angles = (0..AZIMUTHAL_NODES).map(|i| i as f64 * d_phi).collect();
+    (angles, d_phi)
+}
+
+/// Returns the quadrature points of a magnet or coil, in the global frame
+fn force_elements(magnet: &Magnet3D) -> Vec<ForceElement> {
+    let (elements, orientation, center) = match magnet {
+        Magnet3D::Prism(magnet) => (prism_elements(magnet), magnet.orientation(), magnet.center),
+        Magnet3D::Cylinder(magnet) => (
+            cylinder_elements(magnet),
+            magnet.orientation(),
+            magnet.center,
+        ),
+        Magnet3D::Sphere(magnet) => (
+            sphere_elements(magnet),
+            Quaternion::identity(),
+            magnet.center,
+        ),
+        Magnet3D::Polyhedron(magnet) => (
+            polyhedron_elements(magnet),
+            magnet.orientation(),
+            magnet.center,
+        ),
+        Magnet3D::Solenoid(coil) => (solenoid_elements(coil), coil.orientation(), coil.center),
+        Magnet3D::ThickCoil(coil) => (thick_coil_elements(coil), coil.orientation(), coil.center),
+        Magnet3D::CurrentLoop(coil) => {
+            (current_loop_elements(coil), coil.orientation(), coil.center)
+        }
+    };
+    elements
+        .into_iter()
+        .map(|element| element.transform(&orientation, &center))
+        .collect()
+}
+
+fn prism_elements(magnet: &Prism) -> Vec<ForceElement> {
+    let half = [magnet.a, magnet.b, magnet.c];
+    let magnetisation = [magnet.jx, magnet.jy, magnet.jz];
+
+    let mut elements = Vec::new();
+    for axis in 0..3 {
+        for &sign in &[-1.0, 1.0] {
+            let sigma = sign * magnetisation[axis];
+            if sigma == 0.0 {
+                continue;
+            }
+            let (p, q) = ((axis + 1) % 3, (axis + 2) % 3);
+            for (u, weight_u) in gauss_points(-half[p], half[p], FACE_SEGMENTS) {
+                for (v, weight_v) in gauss_points(-half[q], half[q], FACE_SEGMENTS) {
+                    let mut position = [0.0; 3];
+                    position[axis] = sign * half[axis];
+                    position[p] = u;
+                    position[q] = v;
+                    elements.push(ForceElement::charge(
+                        Point3::new(position[0], position[1], position[2]),
+                        sigma * weight_u * weight_v,
+                    ));
+                }
+            }
+        }
+    }
+    elements
+}
+
+fn cylinder_elements(magnet: &Cylinder) -> Vec<ForceElement> {
+    let (angles, d_phi) = azimuthal_points();
+    let mut elements = Vec::new();
+
+    // End faces, charged by the axial magnetisation
+    for &sign in &[-1.0, 1.0] {
+        let sigma = sign * magnet.jz;
+        if sigma == 0.0 {
+            continue;
+        }
+        for (rho, weight) in gauss_points(0.0, magnet.radius, FACE_SEGMENTS) {
+            for phi in &angles {
+                elements.push(ForceElement::charge(
+                    Point3::new(rho * phi.cos(), rho * phi.sin(), sign * magnet.b),
+                    sigma * rho * weight * d_phi,
+                ));
+            }
+        }
+    }
+
+    // Curved surface, charged by the diametric magnetisation
+    if magnet.jx != 0.0 || magnet.jy != 0.0 {
+        for phi in &angles {
+            let sigma = magnet.jx * phi.cos() + magnet.jy * phi.sin();
+            for (z, weight) in gauss_points(-magnet.b, magnet.b, FACE_SEGMENTS) {
+                elements.push(ForceElement::charge(
+                    Point3::new(magnet.radius * phi.cos(), magnet.radius * phi.sin(), z),
+                    sigma * magnet.radius * weight * d_phi,
+                ));
+            }
+        }
+    }
+    elements
+}
+
+fn sphere_elements(magnet: &Sphere) -> Vec<ForceElement> {
+    let (angles, d_phi) = azimuthal_points();
+    let magnetisation = Point3::new(magnet.jx, magnet.jy, magnet.jz);
+
+    let mut elements = Vec::new();
+    for (cos_theta, weight) in gauss_points(-1.0, 1.0, FACE_SEGMENTS) {
+        let sin_theta = (1.0 - cos_theta.powi(2)).sqrt();
+        for phi in &angles {
+            let normal = Point3::new(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta);
+            elements.push(ForceElement::charge(
+                normal.scale(magnet.radius),
+                magnetisation.dot(&normal) * magnet.radius.powi(2) * weight * d_phi,
+            ));
+        }
+    }
+    elements
+}
+
+fn polyhedron_elements(magnet: &Polyhedron) -> Vec<ForceElement> {
+    let segments = FACE_SEGMENTS;
+    let mut elements = Vec::new();
+
+    for triangle in &magnet.triangle_array {
+        if triangle.sigma == 0.0 {
+            continue;
+        }
+        // Divide each face into segments^2 similar triangles, each using the
+        // three point rule at (2/3, 1/6, 1/6)
+        let origin = triangle.vertices[0];
+        let step_1 = (triangle.vertices[1] - origin).scale(1.0 / segments as f64);
+        let step_2 = (triangle.vertices[2] - origin).scale(1.0 / segments as f64);
+        let charge = triangle.sigma * triangle.area / (3 * segments * segments) as f64;
+        let grid = |i: usize, j: usize| origin + step_1.scale(i as f64) + step_2.scale(j as f64);
+
+        let mut sub_triangles = Vec::new();
+        for i in 0..segments {
+            for j in 0..segments - i {
+                sub_triangles.push([grid(i, j), grid(i + 1, j), grid(i, j + 1)]);
+                if i + j + 1 < segments {
+                    sub_triangles.push([grid(i + 1, j), grid(i + 1, j + 1), grid(i, j + 1)]);
+                }
+            }
+        }
+
+        for corners in &sub_triangles {
+            for k in 0..3 {
+                let position = corners[k].scale(2.0 / 3.0)
+                    + (corners[(k + 1) % 3] + corners[(k + 2) % 3]).scale(1.0 / 6.0);
+                elements.push(ForceElement::charge(position, charge));
+            }
+        }
+    }
+    elements
+}
+
+/// Returns the current elements of a cylindrical current sheet of radius
+/// `radius` and half length `b`, with a surface current density `current_density`
+fn current_sheet_elements(radius: f64, b: f64, current_density: f64) -> Vec<ForceElement> {
+    let (angles, d_phi) = azimuthal_points();
+    let mut elements = Vec::new();
+    for phi in &angles {
+        let direction = Point3::new(-phi.sin(), phi.cos(), 0.0);
+        for (z, weight) in gauss_points(-b, b, FACE_SEGMENTS) {
+            elements.push(ForceElement::current(
+                Point3::new(radius * phi.cos(), radius * phi.sin(), z),
+                direction.scale(current_density * radius * weight * d_phi),
+            ));
+        }
+    }
+    elements
+}
+
+fn solenoid_elements(coil: &Solenoid) -> Vec<ForceElement> {
+    current_sheet_elements(coil.radius, coil.b, coil.current_density)
+}
+
+fn thick_coil_elements(coil: &ThickCoil) -> Vec<ForceElement> {
+    // Each layer carries an equal share of the total current
+    let thickness = (coil.outer_radius - coil.inner_radius) / coil.layers as f64;
+    let current_density = coil.turns * coil.current / (coil.length * coil.layers as f64);
+    (0..coil.layers)
+        .flat_map(|layer| {
+            let radius = coil.inner_radius + (layer as f64 + 0.5) * thickness;
+            current_sheet_elements(radius, coil.b, current_density)
+        })
+        .collect()
+}
+
+fn current_loop_elements(coil: &CurrentLoop) -> Vec<ForceElement> {
+    let (angles, d_phi) = azimuthal_points();
+    angles
+        .iter()
+        .map(|phi| {
+            ForceElement::current(
+                Point3::new(coil.radius * phi.cos(), coil.radius * phi.sin(), 0.0),
+                Point3::new(-phi.sin(), phi.cos(), 0.0).scale(coil.current * coil.radius * d_phi),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::conversions::Angle;
+    use crate::PI;
+
+    fn prism(center: (f64, f64, f64), angles: (f64, f64, f64), phi: f64, theta: f64) -> Prism {
+        Prism::new(
+            1.0,
+            0.8,
+            0.6,
+            center,
+            Angle::Degrees(angles.0),
+            Angle::Degrees(angles.1),
+            Angle::Degrees(angles.2),
+            1.2,
+            Angle::Degrees(phi),
+            Angle::Degrees(theta),
+        )
+    }
+
+    fn sphere(center: (f64, f64, f64), phi: f64, theta: f64) -> Sphere {
+        Sphere::new(0.5, center, 1.0, Angle::Degrees(phi), Angle::Degrees(theta))
+    }
+
+    fn assert_close(value: &Point3, comp_value: &Point3, tolerance: f64) {
+        let scale = comp_value.magnitude();
+        assert!((*value - *comp_value).magnitude() < tolerance * scale);
+    }
+
+    #[test]
+    fn closed_form_matches_surface_integration() {
+        // Arbitrary magnetisations, with the second prism turned by 90° so
+        // that its edges remain parallel to those of the first
+        let magnet_1 = prism((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), 30.0, 50.0);
+        let magnet_2 = prism((0.4, 0.3, 1.5), (90.0, 0.0, 0.0), -70.0, 120.0);
+        assert!(parallel_axes(&magnet_1.orientation(), &magnet_2.orientation()).is_some());
+
+        let force = force_between(&magnet_1, &magnet_2).unwrap();
+        let (comp_force, _) = numerical_force_torque(
+            &Magnet3D::Prism(magnet_1),
+            &Magnet3D::Prism(magnet_2),
+            &Point3::zero(),
+        )
+        .unwrap();
+        assert_close(&force, &comp_force, 1e-6);
+    }
+
+    #[test]
+    fn stacked_cubes_attract() {
+        // Touching cubes, and the dipole limit 3 mu0 m^2 / (2 pi d^4)
+        let cube = |z: f64| {
+            Prism::new(
+                1.0,
+                1.0,
+                1.0,
+                (0.0, 0.0, z),
+                Angle::Degrees(0.0),
+                Angle::Degrees(0.0),
+                Angle::Degrees(0.0),
+                1.0,
+                Angle::Degrees(0.0),
+                Angle::Degrees(0.0),
+            )
+        };
+        let touching = force_between(&cube(0.0), &cube(1.0)).unwrap();
+        let nearly_touching = force_between(&cube(0.0), &cube(1.0 + 1e-9)).unwrap();
+        assert!(touching.z < 0.0);
+        assert!(((touching.z - nearly_touching.z) / touching.z).abs() < 1e-6);
+
+        let distance = 10.0_f64;
+        let force = force_between(&cube(0.0), &cube(distance)).unwrap();
+        let comp_force = -3.0 / (2.0 * PI * MU0 * distance.powi(4));
+        assert!(((force.z - comp_force) / comp_force).abs() < 1e-3);
+    }
+
+    #[test]
+    fn misaligned_prisms_obey_newtons_third_law() {
+        let magnet_1 = prism((0.0, 0.0, 0.0), (30.0, 0.0, 0.0), 0.0, 0.0);
+        let magnet_2 = prism((0.3, 0.2, 1.8), (0.0, 20.0, 10.0), 45.0, 90.0);
+        assert!(parallel_axes(&magnet_1.orientation(), &magnet_2.orientation()).is_none());
+
+        let pivot = Point3::new(0.1, 0.1, 0.9);
+        let (force_2, torque_2) = force_torque_between(&magnet_1, &magnet_2, &pivot).unwrap();
+        let (force_1, torque_1) = force_torque_between(&magnet_2, &magnet_1, &pivot).unwrap();
+        assert_close(&force_1, &force_2.neg_p(), 1e-5);
+        assert_close(&torque_1, &torque_2.neg_p(), 1e-5);
+    }
+
+    #[test]
+    fn dipole_force_and_torque_between_spheres() {
+        // m = 4 pi R^3 J / (3 mu0), F = 3 mu0 m^2 / (2 pi d^4) along the axis
+        let moment = 4.0 * PI * 0.5_f64.powi(3) / (3.0 * MU0);
+        let distance = 2.0_f64;
+        let magnet_list = vec![
+            Magnet3D::Sphere(sphere((0.0, 0.0, 0.0), 0.0, 0.0)),
+            Magnet3D::Sphere(sphere((0.0, 0.0, distance), 0.0, 0.0)),
+        ];
+        let (force, _) = get_force_torque_3d(&magnet_list, 1, &Point3::zero()).unwrap();
+        let comp_force = -3.0 * MU0 * moment.powi(2) / (2.0 * PI * distance.powi(4));
+        assert!(((force.z - comp_force) / comp_force).abs() < 1e-6);
+
+        // A perpendicular dipole feels a torque m x B, with B = mu0 m / (4 pi d^3)
+        let magnet_list = vec![
+            Magnet3D::Sphere(sphere((0.0, 0.0, 0.0), 0.0, 0.0)),
+            Magnet3D::Sphere(sphere((distance, 0.0, 0.0), 0.0, 90.0)),
+        ];
+        let (_, torque) =
+            get_force_torque_3d(&magnet_list, 1, &Point3::new(distance, 0.0, 0.0)).unwrap();
+        let comp_torque = MU0 * moment.powi(2) / (4.0 * PI * distance.powi(3));
+        assert!(((torque.y - comp_torque) / comp_torque).abs() < 1e-6);
+    }
+
+    #[test]
+    fn force_matrix() {
+        let magnet_list = vec![
+            Magnet3D::Prism(prism((0.0, 0.0, 0.0), (0.0, 0.0, 0.0), 0.0, 0.0)),
+            Magnet3D::Sphere(sphere((0.0, 0.0, 2.0), 0.0, 0.0)),
+            Magnet3D::CurrentLoop(CurrentLoop::new(
+                0.3,
+                (1.5, 0.0, 0.0),
+                Angle::Degrees(0.0),
+                Angle::Degrees(0.0),
+                Angle::Degrees(0.0),
+                1e5,
+            )),
+        ];
+        let matrix = get_force_matrix_3d(&magnet_list).unwrap();
+        for (i, row) in matrix.iter().enumerate() {
+            assert_eq!(row[i], Point3::zero());
+            for (j, force) in row.iter().enumerate().take(i) {
+                assert_close(force, &matrix[j][i].neg_p(), 1e-4);
+            }
+        }
+
+        let (force, _) = get_force_torque_3d(&magnet_list, 0, &Point3::zero()).unwrap();
+        assert_close(&force, &(matrix[0][1] + matrix[0][2]), 1e-12);
+        assert!(get_force_torque_3d(&magnet_list, 3, &Point3::zero()).is_err());
+    }
+}
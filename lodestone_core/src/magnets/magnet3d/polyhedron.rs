@@ -0,0 +1,523 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+use crate::magnets::magnet3d::triangle_field::solid_angle;
+use crate::magnets::magnet3d::{generate_triangle_array, magnetisation_components, TriangleVec};
+use crate::magnets::{GetCenter, GetField};
+use crate::points::{Point3, Points3};
+use crate::utils::conversions::Angle;
+use crate::utils::quaternion::Quaternion;
+use crate::{MagnetError, ERR_CUTOFF, M2_PI};
+
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A 3D polyhedral magnet, defined by a closed triangulated surface
+///
+/// Arguments using `new()` method:
+///     - center (Point3): magnet center, the origin of the vertex coordinates
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
+///     - jr (f64): remnant magnetisation in T
+///     - phi (f64): angle of magnetisation in the local xy-plane w.r.t. local x-axis
+///     - theta (f64): angle of magnetisation w.r.t. local z-axis
+///     - vertices (Vec<Point3>): vertices relative to the center
+///     - faces (Vec<[usize; 3]>): indices of the vertices of each triangular face
+///
+/// The magnet is rotated about its center by $`R = R_z(\alpha) R_y(\beta) R_x(\gamma)`$,
+/// and the magnetisation rotates with it.
+///
+/// The faces may be ordered either clockwise or anti-clockwise when viewed from
+/// outside, as long as the ordering is consistent.
+///
+/// The default method generates a cubic magnet of size 1.0 x 1.0 x 1.0 centred at
+/// (0.0, 0.0, 0.0), magnetised in z with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::Polyhedron;
+/// use lodestone_core::points::Point3;
+/// use lodestone_core::utils::conversions::Angle;
+/// let vertices = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0),
+/// ];
+/// let faces = vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+/// let magnet = Polyhedron::new(
+///     (0.0, 0.0, 0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     1.0,
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     vertices,
+///     faces,
+/// )
+/// .unwrap();
+/// println!("Magnet:{}", magnet);
+/// ```
+///
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Polyhedron {
+    pub center: Point3,
+    pub alpha: Angle,
+    pub beta: Angle,
+    pub gamma: Angle,
+    pub jr: f64,
+    pub phi: Angle,
+    pub theta: Angle,
+    pub jx: f64,
+    pub jy: f64,
+    pub jz: f64,
+    pub vertices: Vec<Point3>,
+    pub faces: Vec<[usize; 3]>,
+    pub volume: f64,
+    pub triangle_array: TriangleVec,
+}
+
+impl Default for Polyhedron {
+    /// Default method for Polyhedron.
+    ///
+    /// Generates a cubic magnet of size 1.0 x 1.0 x 1.0, centred at (0,0,0),
+    /// with a magnetisation of 1 tesla in z
+    fn default() -> Self {
+        let (vertices, faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        Polyhedron::new(
+            Point3::zero(),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            vertices,
+            faces,
+        )
+        .unwrap()
+    }
+}
+
+/// Returns true if the faces form a closed surface with a consistent ordering,
+/// i.e. every edge is shared by exactly two faces which traverse it in opposite
+/// directions, and no face has zero area
+fn closed_surface(vertices: &[Point3], faces: &[[usize; 3]]) -> bool {
+    let degenerate = faces.iter().any(|face| {
+        let edge_1 = vertices[face[1]] - vertices[face[0]];
+        let edge_2 = vertices[face[2]] - vertices[face[0]];
+        edge_1.cross(&edge_2).magnitude() <= ERR_CUTOFF * edge_1.magnitude() * edge_2.magnitude()
+    });
+    if degenerate {
+        return false;
+    }
+
+    // Count the directed edges of every face
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for face in faces {
+        for i in 0..3 {
+            *edges.entry((face[i], face[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    edges
+        .iter()
+        .all(|(&(start, end), &count)| count == 1 && edges.get(&(end, start)) == Some(&1))
+}
+
+impl Polyhedron {
+    /// Creates a new Polyhedron
+    ///
+    /// Returns an error if a face refers to a vertex that does not exist, has
+    /// zero area, or if the faces do not form a closed, consistently ordered
+    /// surface enclosing a volume.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<C>(
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        jr: f64,
+        phi: Angle,
+        theta: Angle,
+        vertices: Vec<Point3>,
+        faces: Vec<[usize; 3]>,
+    ) -> Result<Polyhedron, MagnetError>
+    where
+        C: GetCenter<Point3>,
+    {
+        let num_vertices = vertices.len();
+        if faces.len() < 4
+            || faces.iter().flatten().any(|&index| index >= num_vertices)
+            || !closed_surface(&vertices, &faces)
+        {
+            return Err(MagnetError::PolyhedronFaceError());
+        }
+
+        let center = center.center();
+        let (jx, jy, jz) = magnetisation_components(jr, &phi, &theta);
+        let magnetisation = Point3::new(jx, jy, jz);
+
+        // The triangles are stored in the local frame of the magnet
+        let mut faces = faces;
+        let (mut triangle_array, mut volume, _) =
+            generate_triangle_array(&vertices, &faces, &magnetisation);
+
+        if !volume.is_finite() || volume == 0.0 {
+            return Err(MagnetError::PolyhedronFaceError());
+        }
+
+        // Reorder clockwise faces so that all normals point outwards
+        if volume < 0.0 {
+            for face in faces.iter_mut() {
+                face.swap(1, 2);
+            }
+            let reordered = generate_triangle_array(&vertices, &faces, &magnetisation);
+            triangle_array = reordered.0;
+            volume = reordered.1;
+        }
+
+        Ok(Polyhedron {
+            center,
+            alpha,
+            beta,
+            gamma,
+            jr,
+            phi,
+            theta,
+            jx,
+            jy,
+            jz,
+            vertices,
+            faces,
+            volume,
+            triangle_array,
+        })
+    }
+
+    /// Returns the orientation of the magnet as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+
+    /// Returns true if a point lies inside the polyhedron, using the total solid
+    /// angle subtended by its faces, which is $`4\pi`$ inside and 0 outside.
+    pub fn contains(&self, point: &Point3) -> bool {
+        let local_point = self.orientation().rotate_inverse(&(*point - self.center));
+        self.contains_local(&local_point)
+    }
+
+    /// Returns true if a point in the local frame of the magnet lies inside it
+    fn contains_local(&self, point: &Point3) -> bool {
+        let total: f64 = self
+            .triangle_array
+            .iter()
+            .map(|triangle| solid_angle(&triangle.vertices, point))
+            .sum();
+        total.abs() > M2_PI
+    }
+}
+
+/// Returns the vertices and triangular faces of a cuboid of size
+/// `width x depth x height` centred at the origin, with the faces ordered
+/// anti-clockwise when viewed from outside
+pub fn cuboid_mesh(width: f64, depth: f64, height: f64) -> (Vec<Point3>, Vec<[usize; 3]>) {
+    let (a, b, c) = (width / 2.0, depth / 2.0, height / 2.0);
+    let vertices = vec![
+        Point3::new(-a, -b, -c),
+        Point3::new(a, -b, -c),
+        Point3::new(a, b, -c),
+        Point3::new(-a, b, -c),
+        Point3::new(-a, -b, c),
+        Point3::new(a, -b, c),
+        Point3::new(a, b, c),
+        Point3::new(-a, b, c),
+    ];
+    let faces = vec![
+        [0, 2, 1],
+        [0, 3, 2],
+        [4, 5, 6],
+        [4, 6, 7],
+        [0, 1, 5],
+        [0, 5, 4],
+        [1, 2, 6],
+        [1, 6, 5],
+        [2, 3, 7],
+        [2, 7, 6],
+        [3, 0, 4],
+        [3, 4, 7],
+    ];
+    (vertices, faces)
+}
+
+impl fmt::Display for Polyhedron {
+    /// Implements Display for Polyhedral magnets.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[c: {},\talpha:{}\tbeta:{}\tgamma:{}\tJ ({:.3}, {:.3}, {:.3})\nNo. faces: {}]",
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.jr,
+            self.phi.to_degrees(),
+            self.theta.to_degrees(),
+            self.faces.len(),
+        )
+    }
+}
+
+/// Return the magnetic field at a point due to a polyhedron
+pub fn get_field_polyhedron(magnet: &Polyhedron, point: &Point3) -> Result<Point3, MagnetError> {
+    let mut field = Point3::zero();
+
+    // Translate and rotate into the local coordinates of the magnet
+    let orientation = magnet.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - magnet.center));
+
+    for triangle in &magnet.triangle_array {
+        field += triangle.field(&local_point)?;
+    }
+
+    // Include the magnetisation inside the magnet, to return B rather than μ0 H
+    if magnet.contains_local(&local_point) {
+        field += Point3::new(magnet.jx, magnet.jy, magnet.jz);
+    }
+
+    // Rotate the field back into the global frame
+    Ok(orientation.rotate(&field))
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Polyhedron {
+    /// Returns the magnetic field of a polyhedral magnet at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_polyhedron(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Polyhedron {
+    /// Returns the magnetic field of a polyhedral magnet at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_polyhedron(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::magnet3d::Prism;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-10
+    }
+
+    #[test]
+    fn test_cube_matches_prism() {
+        let prism = Prism::new(
+            1.0,
+            2.0,
+            0.5,
+            (0.5, -0.5, 1.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+            Angle::Degrees(60.0),
+        );
+        let (vertices, faces) = cuboid_mesh(1.0, 2.0, 0.5);
+        let magnet = Polyhedron::new(
+            (0.5, -0.5, 1.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(30.0),
+            Angle::Degrees(60.0),
+            vertices,
+            faces,
+        )
+        .unwrap();
+
+        for point in &[
+            Point3::new(0.5, -0.5, 1.0),
+            Point3::new(0.7, 0.1, 1.1),
+            Point3::new(2.0, 1.0, -1.0),
+            Point3::new(-0.3, -0.5, 1.6),
+        ] {
+            let comp_field = prism.field(point).unwrap();
+            let field = magnet.field(point).unwrap();
+            assert!(close(field.x, comp_field.x));
+            assert!(close(field.y, comp_field.y));
+            assert!(close(field.z, comp_field.z));
+        }
+    }
+
+    #[test]
+    fn test_face_order_is_normalised() {
+        let (vertices, faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        let reversed: Vec<[usize; 3]> = faces.iter().map(|f| [f[0], f[2], f[1]]).collect();
+        let magnet = Polyhedron::new(
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            vertices,
+            reversed,
+        )
+        .unwrap();
+
+        assert!(close(magnet.volume, 1.0));
+        let field = magnet.field(&Point3::zero()).unwrap();
+        assert!(close(field.z, 2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_invalid_faces() {
+        let (vertices, mut faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        faces[0] = [0, 1, 8];
+        let magnet = Polyhedron::new(
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            vertices,
+            faces,
+        );
+        assert!(magnet.is_err());
+    }
+
+    fn cube_with_faces(faces: Vec<[usize; 3]>) -> Result<Polyhedron, MagnetError> {
+        let (vertices, _) = cuboid_mesh(1.0, 1.0, 1.0);
+        Polyhedron::new(
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            vertices,
+            faces,
+        )
+    }
+
+    #[test]
+    fn test_flipped_face() {
+        let (_, mut faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        faces[3].swap(1, 2);
+        assert!(matches!(
+            cube_with_faces(faces),
+            Err(MagnetError::PolyhedronFaceError())
+        ));
+    }
+
+    #[test]
+    fn test_open_mesh() {
+        let (_, mut faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        faces.remove(5);
+        assert!(matches!(
+            cube_with_faces(faces),
+            Err(MagnetError::PolyhedronFaceError())
+        ));
+    }
+
+    #[test]
+    fn test_zero_area_face() {
+        // Splitting a face at the midpoint of an edge, and closing the gap with
+        // a sliver, leaves every edge shared by two opposite faces
+        let (mut vertices, mut faces) = cuboid_mesh(1.0, 1.0, 1.0);
+        let [a, b, c] = faces[0];
+        vertices.push((vertices[a] + vertices[b]).scale(0.5));
+        let midpoint = vertices.len() - 1;
+        faces[0] = [a, midpoint, c];
+        faces.push([midpoint, b, c]);
+        faces.push([a, b, midpoint]);
+
+        let magnet = Polyhedron::new(
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            vertices,
+            faces,
+        );
+        assert!(matches!(magnet, Err(MagnetError::PolyhedronFaceError())));
+    }
+
+    #[test]
+    fn test_contains() {
+        let magnet = Polyhedron::default();
+        assert!(magnet.contains(&Point3::new(0.2, -0.3, 0.4)));
+        assert!(!magnet.contains(&Point3::new(0.2, -0.3, 0.6)));
+    }
+
+    #[test]
+    fn test_rotated_cube_matches_prism() {
+        let (alpha, beta, gamma) = (
+            Angle::Degrees(20.0),
+            Angle::Degrees(-35.0),
+            Angle::Degrees(70.0),
+        );
+        let prism = Prism::new(
+            1.0,
+            2.0,
+            0.5,
+            (0.5, -0.5, 1.0),
+            alpha,
+            beta,
+            gamma,
+            1.0,
+            Angle::Degrees(30.0),
+            Angle::Degrees(60.0),
+        );
+        let (vertices, faces) = cuboid_mesh(1.0, 2.0, 0.5);
+        let magnet = Polyhedron::new(
+            (0.5, -0.5, 1.0),
+            alpha,
+            beta,
+            gamma,
+            1.0,
+            Angle::Degrees(30.0),
+            Angle::Degrees(60.0),
+            vertices,
+            faces,
+        )
+        .unwrap();
+
+        for point in &[
+            Point3::new(0.5, -0.5, 1.0),
+            Point3::new(0.7, 0.1, 1.1),
+            Point3::new(2.0, 1.0, -1.0),
+        ] {
+            let comp_field = prism.field(point).unwrap();
+            let field = magnet.field(point).unwrap();
+            assert!(close(field.x, comp_field.x));
+            assert!(close(field.y, comp_field.y));
+            assert!(close(field.z, comp_field.z));
+        }
+        assert!(magnet.contains(&Point3::new(0.5, -0.5, 1.0)));
+    }
+}
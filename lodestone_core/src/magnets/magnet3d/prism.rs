@@ -3,49 +3,61 @@ License, v. 2.0. If a copy of the MPL was not distributed with this
 file, You can obtain one at https://mozilla.org/MPL/2.0/.
 Copyright 2021 Peter Dunne */
 
-// pub use super::rectangle_field::*;
+pub use super::prism_field::*;
 
-// use anyhow::Result;
 use serde_derive::{Deserialize, Serialize};
-// use std::fmt;
-// use std::ops::{Add, Mul};
+use std::fmt;
 
-// use crate::magnets::{GetCenter, GetField, MagnetTrait};
-use crate::points::Point3;
+use crate::magnets::{GetCenter, GetField, MagnetTrait};
+use crate::points::{Point3, Points3};
 use crate::utils::conversions::Angle;
-// use crate::MagnetError;
+use crate::utils::quaternion::Quaternion;
+use crate::MagnetError;
 
-//TODO: Finish docs
 /// A 3D Prismatic magnet
 ///
 /// Arguments using `new()` method:
-///     - width (f64): magnet width
-///     - height (f64): magnet height
-///     - depth (f64): magnet depth
+///     - width (f64): magnet width (x)
+///     - depth (f64): magnet depth (y)
+///     - height (f64): magnet height (z)
 ///     - center (Point3): magnet center
-///     - alpha (f64):
-///     - beta (f64):
-///     - gamma (f64):
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
 ///     - jr (f64): remnant magnetisation in T
-///     - phi (f64): angle of magnetisation w.r.t. x-axis
-///     - theta (f64): angle of magnetisation w.r.t. z-axis
-
+///     - phi (f64): angle of magnetisation in the local xy-plane w.r.t. local x-axis
+///     - theta (f64): angle of magnetisation w.r.t. local z-axis
+///
+/// The magnet is rotated about its center by $`R = R_z(\alpha) R_y(\beta) R_x(\gamma)`$,
+/// and the magnetisation rotates with it.
 ///
-/// The default method generates a square magnet of size 1.0 x 1.0, x 1.0 centred at
+/// The default method generates a cubic magnet of size 1.0 x 1.0 x 1.0 centred at
 /// (0.0, 0.0, 0.0), magnetised in z with a remnant magnetisation of 1.0 T
 /// # Example
 /// ```rust
-/// use magnet_rs::magnets::Prism;
-/// use magnet_rs::utils::conversions::Angle;
-/// let magnet_1 = Rectangle::default();
+/// use lodestone_core::magnets::Prism;
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = Prism::default();
 /// println!("Magnet 1:{}", magnet_1);
-/// let magnet_2 = Rectangle::new(1.0, 1.0, (0.0, -1.0 / 2.0), Angle::Degrees(0.0), 1.0, Angle::Degrees(90.0));
+/// let magnet_2 = Prism::new(
+///     1.0,
+///     1.0,
+///     1.0,
+///     (0.0, 0.0, -1.0 / 2.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     1.0,
+///     Angle::Degrees(90.0),
+///     Angle::Degrees(0.0),
+/// );
 /// println!("Magnet 2:{}", magnet_2);
 /// ```
 ///
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Prism {
     pub width: f64,
+    pub depth: f64,
     pub height: f64,
     pub center: Point3,
     pub alpha: Angle,
@@ -64,3 +76,237 @@ pub struct Prism {
     pub jy: f64,
     pub jz: f64,
 }
+
+impl GetCenter<Point3> for Point3 {
+    fn center(&self) -> Point3 {
+        *self
+    }
+}
+
+impl<T: Into<f64> + Copy, U: Into<f64> + Copy, V: Into<f64> + Copy> GetCenter<Point3>
+    for (T, U, V)
+{
+    /// Converts a 3 element tuple to a Point3
+    fn center(&self) -> Point3 {
+        Point3 {
+            x: self.0.into(),
+            y: self.1.into(),
+            z: self.2.into(),
+        }
+    }
+}
+
+impl Default for Prism {
+    /// Default method for Prism.
+    ///
+    /// Generates a cubic magnet of size 1.0 x 1.0 x 1.0, centred at (0,0,0),
+    /// with a magnetisation of 1 tesla in z
+    fn default() -> Self {
+        Prism {
+            width: 1.0,
+            depth: 1.0,
+            height: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            beta: Angle::Degrees(0.0),
+            gamma: Angle::Degrees(0.0),
+            jr: 1.0,
+            phi: Angle::Degrees(90.0),
+            theta: Angle::Degrees(0.0),
+            a: 0.5,
+            b: 0.5,
+            c: 0.5,
+            jx: 0.0,
+            jy: 0.0,
+            jz: 1.0,
+        }
+    }
+}
+
+impl Prism {
+    /// Constructor for 3D Prism magnet
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<C>(
+        width: f64,
+        depth: f64,
+        height: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        jr: f64,
+        phi: Angle,
+        theta: Angle,
+    ) -> Prism
+    where
+        C: GetCenter<Point3>,
+    {
+        let (jx, jy, jz) = magnetisation_components(jr, &phi, &theta);
+
+        Prism {
+            width,
+            depth,
+            height,
+            center: center.center(),
+            alpha,
+            beta,
+            gamma,
+            jr,
+            phi,
+            theta,
+            a: width / 2.0,
+            b: depth / 2.0,
+            c: height / 2.0,
+            jx,
+            jy,
+            jz,
+        }
+    }
+
+    /// Returns the orientation of the magnet as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+}
+
+/// Returns the cartesian components (jx, jy, jz) of a magnetisation vector of
+/// magnitude `jr`, with azimuthal angle `phi` and polar angle `theta`
+pub fn magnetisation_components(jr: f64, phi: &Angle, theta: &Angle) -> (f64, f64, f64) {
+    let phi_rad = phi.to_radians();
+    let theta_rad = theta.to_radians();
+    (
+        jr * theta_rad.sin() * phi_rad.cos(),
+        jr * theta_rad.sin() * phi_rad.sin(),
+        jr * theta_rad.cos(),
+    )
+}
+
+/// Implements Display for Prism magnets.
+impl fmt::Display for Prism {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[w: {},\td: {},\th: {},\tc: {},\talpha:{}\tbeta:{}\tgamma:{}\tJ ({:.3}, {:.3}, {:.3})]",
+            self.width,
+            self.depth,
+            self.height,
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.jr,
+            self.phi.to_degrees(),
+            self.theta.to_degrees(),
+        )
+    }
+}
+
+impl MagnetTrait<Point3, Point3, [f64; 3], Point3> for Prism {
+    /// Returns the center of a Prism
+    fn center(&self) -> Point3 {
+        self.center
+    }
+
+    /// Returns the size of a Prism: [width, depth, height]
+    fn size(&self) -> [f64; 3] {
+        [self.width, self.depth, self.height]
+    }
+
+    /// Returns the magnetisation vector of a Prism: Point3 (jx, jy, jz)
+    fn magnetisation(self) -> Point3 {
+        Point3::new(self.jx, self.jy, self.jz)
+    }
+
+    /// Sets the magnet center
+    fn set_center(&mut self, point: Point3) {
+        self.center = point;
+    }
+
+    /// Sets the width, depth, and height of a prism, and the internal
+    /// values self.a, self.b, and self.c
+    fn set_size(&mut self, size: [f64; 3]) {
+        self.width = size[0];
+        self.depth = size[1];
+        self.height = size[2];
+        self.a = self.width / 2.0;
+        self.b = self.depth / 2.0;
+        self.c = self.height / 2.0;
+    }
+
+    /// Set the magnetisation of the magnet using a cartesian vector.
+    ///
+    /// This method also updates self.jr, self.phi, and self.theta
+    fn set_magnetisation(&mut self, magnetisation: Point3) {
+        self.jr = magnetisation.magnitude();
+        self.phi = Angle::Radians(magnetisation.y.atan2(magnetisation.x));
+        self.theta = Angle::Radians(if self.jr > 0.0 {
+            (magnetisation.z / self.jr).acos()
+        } else {
+            0.0
+        });
+        self.jx = magnetisation.x;
+        self.jy = magnetisation.y;
+        self.jz = magnetisation.z;
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Prism {
+    /// Returns the magnetic field of a prism magnet at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_prism(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Prism {
+    /// Returns the magnetic field of a prism magnet at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_prism(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::comparison::nearly_equal;
+
+    use super::*;
+
+    #[test]
+    fn test_point3_prism_field() {
+        let magnet = Prism::default();
+
+        let point = Point3::new(0.0, 0.0, 0.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.x, 0.0_f64));
+        assert!(nearly_equal(field.y, 0.0_f64));
+        assert!(nearly_equal(field.z, 2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_tuple_prism_field() {
+        let magnet = Prism::default();
+
+        let point = (0.0, 0.0, 0.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.0, 0.0_f64));
+        assert!(nearly_equal(field.1, 0.0_f64));
+        assert!(nearly_equal(field.2, 2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_set_magnetisation() {
+        let mut magnet = Prism::default();
+        magnet.set_magnetisation(Point3::new(1.0, 0.0, 0.0));
+        assert!(nearly_equal(magnet.jr, 1.0));
+        assert!(nearly_equal(magnet.theta.to_degrees(), 90.0));
+        assert!(nearly_equal(magnet.phi.to_degrees(), 0.0));
+    }
+}
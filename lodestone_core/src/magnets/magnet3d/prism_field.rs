@@ -0,0 +1,260 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for prisms (cuboids)
+//!
+//! The field of a uniformly magnetised prism is calculated from the two
+//! uniformly charged faces perpendicular to each component of the magnetisation.
+
+#![allow(clippy::many_single_char_names)]
+use crate::magnets::magnet3d::Prism;
+use crate::points::{Point3, Points3};
+use crate::{MagnetError, FP_CUTOFF, I_4PI};
+
+/// Returns the magnetic field vector due to a prism of width `2a`, depth `2b`,
+/// and height `2c` centered at the origin,
+///
+/// with an arbitrary magnetisation $`\mathbf{J} = J_x \mathbf{\hat{x}} + J_y \mathbf{\hat{y}} + J_z \mathbf{\hat{z}}`$
+///
+pub fn get_field_prism(magnet: &Prism, point: &Point3) -> Result<Point3, MagnetError> {
+    let mut field = Point3::zero();
+
+    // Translate and rotate into the local coordinates of the magnet
+    let orientation = magnet.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - magnet.center));
+
+    field += if (magnet.jx / magnet.jr).abs() > FP_CUTOFF {
+        magnetic_field_x(magnet, &local_point)?
+    } else {
+        Point3::zero()
+    };
+
+    field += if (magnet.jy / magnet.jr).abs() > FP_CUTOFF {
+        magnetic_field_y(magnet, &local_point)?
+    } else {
+        Point3::zero()
+    };
+
+    field += if (magnet.jz / magnet.jr).abs() > FP_CUTOFF {
+        magnetic_field_z(magnet, &local_point)?
+    } else {
+        Point3::zero()
+    };
+
+    // Rotate the field back into the global frame
+    Ok(orientation.rotate(&field))
+}
+
+/// Returns the magnetic field vector at a point due to a prism magnetised in x.
+///
+/// This is the z-magnetised solution with the axes cyclically permuted
+/// (x, y, z) -> (y, z, x)
+fn magnetic_field_x(magnet: &Prism, point: &Point3) -> Result<Point3, MagnetError> {
+    let field = field_for_z_mag(
+        point.y, point.z, point.x, magnet.b, magnet.c, magnet.a, magnet.jx,
+    )?;
+    Ok(Point3::new(field.z, field.x, field.y))
+}
+
+/// Returns the magnetic field vector at a point due to a prism magnetised in y.
+///
+/// This is the z-magnetised solution with the axes cyclically permuted
+/// (x, y, z) -> (z, x, y)
+fn magnetic_field_y(magnet: &Prism, point: &Point3) -> Result<Point3, MagnetError> {
+    let field = field_for_z_mag(
+        point.z, point.x, point.y, magnet.c, magnet.a, magnet.b, magnet.jy,
+    )?;
+    Ok(Point3::new(field.y, field.z, field.x))
+}
+
+/// Returns the magnetic field vector at a point due to a prism magnetised in z
+fn magnetic_field_z(magnet: &Prism, point: &Point3) -> Result<Point3, MagnetError> {
+    field_for_z_mag(
+        point.x, point.y, point.z, magnet.a, magnet.b, magnet.c, magnet.jz,
+    )
+}
+
+/// Returns the field of a prism of size `2a x 2b x 2c` magnetised in z, as the
+/// sum of the fields of the charged sheets at z = c ($`\sigma = +J`$) and
+/// z = -c ($`\sigma = -J`$)
+fn field_for_z_mag(
+    x: f64,
+    y: f64,
+    z: f64,
+    a: f64,
+    b: f64,
+    c: f64,
+    j: f64,
+) -> Result<Point3, MagnetError> {
+    let top = charged_sheet_field(x, y, z - c, a, b);
+    let bottom = charged_sheet_field(x, y, z + c, a, b);
+
+    Ok((top - bottom).scale(j * I_4PI))
+}
+
+/// Returns the integral $`\int (\mathbf{r} - \mathbf{r'})/|\mathbf{r} - \mathbf{r'}|^3 dA'`$
+/// over a rectangle of size `2a x 2b` lying in the plane w = 0.
+///
+/// The z-component is evaluated with `atan2`, so that the jump of the
+/// magnetisation across the sheet is included, i.e. the prism routines return
+/// B rather than $`\mu_0`$ H inside the magnet.
+fn charged_sheet_field(x: f64, y: f64, w: f64, a: f64, b: f64) -> Point3 {
+    let mut field = Point3::zero();
+    let w_sq = w.powi(2);
+
+    for &(x_corner, sign_x) in &[(a, 1.0), (-a, -1.0)] {
+        let u = x - x_corner;
+        let v_top = y - b;
+        let v_bottom = y + b;
+        let r_top = (u.powi(2) + v_top.powi(2) + w_sq).sqrt();
+        let r_bottom = (u.powi(2) + v_bottom.powi(2) + w_sq).sqrt();
+
+        field.x -= sign_x * log_difference(v_top, r_top, v_bottom, r_bottom);
+        field.z += sign_x * ((u * v_top).atan2(w * r_top) - (u * v_bottom).atan2(w * r_bottom));
+    }
+
+    for &(y_corner, sign_y) in &[(b, 1.0), (-b, -1.0)] {
+        let v = y - y_corner;
+        let u_right = x - a;
+        let u_left = x + a;
+        let r_right = (u_right.powi(2) + v.powi(2) + w_sq).sqrt();
+        let r_left = (u_left.powi(2) + v.powi(2) + w_sq).sqrt();
+
+        field.y -= sign_y * log_difference(u_right, r_right, u_left, r_left);
+    }
+
+    // Points lying on an edge of the sheet are singular, so bind them to 0.0
+    if !field.x.is_finite() {
+        field.x = 0.0;
+    }
+    if !field.y.is_finite() {
+        field.y = 0.0;
+    }
+
+    field
+}
+
+/// Returns $`\ln(v_1 + r_1) - \ln(v_2 + r_2)`$, avoiding the cancellation in
+/// $`v + r`$ when both `v` are negative
+fn log_difference(v_1: f64, r_1: f64, v_2: f64, r_2: f64) -> f64 {
+    if v_1 < 0.0 && v_2 < 0.0 {
+        ((r_2 - v_2) / (r_1 - v_1)).ln()
+    } else {
+        ((v_1 + r_1) / (v_2 + r_2)).ln()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::magnets::magnet3d::prism_field::*;
+    use crate::points::Point3;
+    use crate::utils::comparison::nearly_equal;
+    use crate::utils::conversions::Angle;
+    use crate::M4_PI;
+
+    fn cube(phi: f64, theta: f64) -> Prism {
+        Prism::new(
+            2.0,
+            2.0,
+            2.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(phi),
+            Angle::Degrees(theta),
+        )
+    }
+
+    #[test]
+    fn center_field_cube_z() {
+        let field = get_field_prism(&cube(0.0, 0.0), &Point3::zero()).unwrap();
+        assert!(nearly_equal(field.x, 0.0));
+        assert!(nearly_equal(field.y, 0.0));
+        assert!(nearly_equal(field.z, 2.0 / 3.0));
+    }
+
+    #[test]
+    fn center_field_cube_x() {
+        let field = get_field_prism(&cube(0.0, 90.0), &Point3::zero()).unwrap();
+        assert!(nearly_equal(field.x, 2.0 / 3.0));
+        assert!(nearly_equal(field.y, 0.0));
+        assert!(nearly_equal(field.z, 0.0));
+    }
+
+    #[test]
+    fn center_field_cube_y() {
+        let field = get_field_prism(&cube(90.0, 90.0), &Point3::zero()).unwrap();
+        assert!(nearly_equal(field.x, 0.0));
+        assert!(nearly_equal(field.y, 2.0 / 3.0));
+        assert!(nearly_equal(field.z, 0.0));
+    }
+
+    #[test]
+    fn surface_field_cube_z() {
+        // Field at the center of the top face of a cube: 1/2 - atan(1/(2 sqrt(6)))/pi
+        let field = get_field_prism(&cube(0.0, 0.0), &Point3::new(0.0, 0.0, 1.0)).unwrap();
+        let comp_field = 0.5 - (1.0 / (2.0 * 6.0_f64.sqrt())).atan() / crate::PI;
+        assert!(nearly_equal(field.z, comp_field));
+    }
+
+    #[test]
+    fn far_field_dipole() {
+        // A unit cube magnetised in z behaves as a point dipole far away
+        let magnet = Prism::default();
+        let distance = 50.0_f64;
+        let on_axis = get_field_prism(&magnet, &Point3::new(0.0, 0.0, distance)).unwrap();
+        let side = get_field_prism(&magnet, &Point3::new(distance, 0.0, 0.0)).unwrap();
+
+        let dipole = 1.0 / (M4_PI * distance.powi(3));
+        assert!(((on_axis.z - 2.0 * dipole) / dipole).abs() < 1e-3);
+        assert!(((side.z + dipole) / dipole).abs() < 1e-3);
+    }
+
+    #[test]
+    fn field_on_edge_extension_is_finite() {
+        let field = get_field_prism(&cube(45.0, 45.0), &Point3::new(1.0, 1.0, 3.0)).unwrap();
+        assert!(field.x.is_finite() && field.y.is_finite() && field.z.is_finite());
+    }
+
+    #[test]
+    fn rotated_prism_matches_x_magnetised() {
+        // Rotating a z-magnetised 1 x 2 x 3 prism by 90° about y gives a
+        // 3 x 2 x 1 prism magnetised in x
+        let rotated = Prism::new(
+            1.0,
+            2.0,
+            3.0,
+            (0.5, -0.5, 1.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(90.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+        );
+        let comp_magnet = Prism::new(
+            3.0,
+            2.0,
+            1.0,
+            (0.5, -0.5, 1.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1.0,
+            Angle::Degrees(0.0),
+            Angle::Degrees(90.0),
+        );
+
+        for point in &[Point3::new(0.7, 0.1, 1.1), Point3::new(2.0, 1.0, -1.0)] {
+            let field = get_field_prism(&rotated, point).unwrap();
+            let comp_field = get_field_prism(&comp_magnet, point).unwrap();
+            assert!((field.x - comp_field.x).abs() < 1e-12);
+            assert!((field.y - comp_field.y).abs() < 1e-12);
+            assert!((field.z - comp_field.z).abs() < 1e-12);
+        }
+    }
+}
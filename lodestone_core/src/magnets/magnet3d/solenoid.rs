@@ -1,34 +1,390 @@
-use crate::magnets::magnet3d::bulirsch::cel;
-use crate::points::Point2;
-use crate::{MagnetError, PI};
-// use crate::magnets::
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
 
-/// Calculate the magnetic field due to a solenoid at any point
-/// returns Bz,Br as a Point2 structure
-pub fn solenoid_field(a: f64, b: f64, rho: f64, z: f64, b_0: f64) -> Result<Point2, MagnetError> {
-    let zp = z + b;
-    let zn = z - b;
+pub use super::solenoid_field::*;
 
-    let rho_a = rho + a;
-    let rho_na = rho - a;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
 
-    let alpha_p = a / (zp.powi(2) + (rho_a).powi(2)).sqrt();
-    let alpha_n = a / (zn.powi(2) + (rho_a).powi(2)).sqrt();
+use crate::magnets::{GetCenter, GetField};
+use crate::points::Point3;
+use crate::utils::conversions::Angle;
+use crate::utils::quaternion::Quaternion;
+use crate::MagnetError;
 
-    let beta_p = zp / (zp.powi(2) + (rho_a).powi(2)).sqrt();
-    let beta_n = zn / (zn.powi(2) + (rho_a).powi(2)).sqrt();
+/// A thin solenoid, i.e. a cylindrical current sheet
+///
+/// Arguments using `new()` method:
+///     - radius (f64): solenoid radius in m
+///     - length (f64): solenoid length in m
+///     - center (Point3): solenoid center
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
+///     - turns (f64): number of turns
+///     - current (f64): current in A
+///
+/// Alternatively, `with_current_density()` takes the surface current density
+/// in A/m in place of the turns and current.
+///
+/// The axis of the solenoid lies along the local z-axis, and a positive
+/// current produces a field in +z.
+///
+/// The default method generates a solenoid of radius 0.5 and length 1.0 centred
+/// at (0.0, 0.0, 0.0), with 100 turns carrying 1 A
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::Solenoid;
+/// use lodestone_core::utils::conversions::Angle;
+/// let solenoid = Solenoid::new(
+///     0.1,
+///     0.5,
+///     (0.0, 0.0, 0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(90.0),
+///     Angle::Degrees(0.0),
+///     1000.0,
+///     2.0,
+/// );
+/// println!("Solenoid:{}", solenoid);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Solenoid {
+    pub radius: f64,
+    pub length: f64,
+    pub center: Point3,
+    pub alpha: Angle,
+    pub beta: Angle,
+    pub gamma: Angle,
 
-    let gamma = (rho_na) / (rho_a);
+    /// surface current density in A/m
+    pub current_density: f64,
 
-    let kp = ((zp.powi(2) + (rho_na).powi(2)) / (zp.powi(2) + (rho_a).powi(2))).sqrt();
+    pub b: f64,
+}
+
+impl Default for Solenoid {
+    /// Default method for Solenoid.
+    ///
+    /// Generates a solenoid of radius 0.5 and length 1.0, centred at (0,0,0),
+    /// with its axis in z and a surface current density of 100 A/m
+    fn default() -> Self {
+        Solenoid {
+            radius: 0.5,
+            length: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            beta: Angle::Degrees(0.0),
+            gamma: Angle::Degrees(0.0),
+            current_density: 100.0,
+            b: 0.5,
+        }
+    }
+}
+
+impl Solenoid {
+    /// Constructor for a Solenoid from the number of turns and the current
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<C>(
+        radius: f64,
+        length: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        turns: f64,
+        current: f64,
+    ) -> Solenoid
+    where
+        C: GetCenter<Point3>,
+    {
+        Solenoid::with_current_density(
+            radius,
+            length,
+            center,
+            alpha,
+            beta,
+            gamma,
+            turns * current / length,
+        )
+    }
+
+    /// Constructor for a Solenoid from the surface current density in A/m
+    pub fn with_current_density<C>(
+        radius: f64,
+        length: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        current_density: f64,
+    ) -> Solenoid
+    where
+        C: GetCenter<Point3>,
+    {
+        Solenoid {
+            radius,
+            length,
+            center: center.center(),
+            alpha,
+            beta,
+            gamma,
+            current_density,
+            b: length / 2.0,
+        }
+    }
+
+    /// Returns the orientation of the solenoid as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+}
+
+/// Implements Display for Solenoids.
+impl fmt::Display for Solenoid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: {},\tl: {},\tc: {},\talpha:{}\tbeta:{}\tgamma:{}\tK: {} A/m]",
+            self.radius,
+            self.length,
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.current_density,
+        )
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Solenoid {
+    /// Returns the magnetic field of a solenoid at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_solenoid(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Solenoid {
+    /// Returns the magnetic field of a solenoid at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_solenoid(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+/// A thick, multi-layer coil with a rectangular winding cross-section
+///
+/// Arguments using `new()` method:
+///     - inner_radius (f64): inner radius of the winding in m
+///     - outer_radius (f64): outer radius of the winding in m
+///     - length (f64): coil length in m
+///     - center (Point3): coil center
+///     - alpha (f64): rotation about the z-axis
+///     - beta (f64): rotation about the rotated y-axis
+///     - gamma (f64): rotation about the rotated x-axis
+///     - turns (f64): total number of turns
+///     - current (f64): current in A
+///     - layers (usize): number of layers of the winding
+///
+/// The turns are spread uniformly over the winding, which is modelled as
+/// `layers` concentric solenoids evenly spaced between the inner and outer
+/// radii.
+///
+/// The default method generates a coil with radii 0.4 and 0.6 and length 1.0,
+/// centred at (0.0, 0.0, 0.0), with 1000 turns in 10 layers carrying 1 A
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::ThickCoil;
+/// use lodestone_core::utils::conversions::Angle;
+/// let coil = ThickCoil::new(
+///     0.02,
+///     0.04,
+///     0.1,
+///     (0.0, 0.0, 0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(0.0),
+///     800.0,
+///     1.5,
+///     8,
+/// );
+/// println!("Coil:{}", coil);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ThickCoil {
+    pub inner_radius: f64,
+    pub outer_radius: f64,
+    pub length: f64,
+    pub center: Point3,
+    pub alpha: Angle,
+    pub beta: Angle,
+    pub gamma: Angle,
+
+    pub turns: f64,
+    pub current: f64,
+    pub layers: usize,
+
+    pub b: f64,
+}
+
+impl Default for ThickCoil {
+    /// Default method for ThickCoil.
+    ///
+    /// Generates a coil with radii 0.4 and 0.6 and length 1.0, centred at
+    /// (0,0,0), with its axis in z and 1000 turns in 10 layers carrying 1 A
+    fn default() -> Self {
+        ThickCoil {
+            inner_radius: 0.4,
+            outer_radius: 0.6,
+            length: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            alpha: Angle::Degrees(0.0),
+            beta: Angle::Degrees(0.0),
+            gamma: Angle::Degrees(0.0),
+            turns: 1000.0,
+            current: 1.0,
+            layers: 10,
+            b: 0.5,
+        }
+    }
+}
+
+impl ThickCoil {
+    /// Constructor for a ThickCoil
+    ///
+    /// The number of layers is at least one.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<C>(
+        inner_radius: f64,
+        outer_radius: f64,
+        length: f64,
+        center: C,
+        alpha: Angle,
+        beta: Angle,
+        gamma: Angle,
+        turns: f64,
+        current: f64,
+        layers: usize,
+    ) -> ThickCoil
+    where
+        C: GetCenter<Point3>,
+    {
+        ThickCoil {
+            inner_radius,
+            outer_radius,
+            length,
+            center: center.center(),
+            alpha,
+            beta,
+            gamma,
+            turns,
+            current,
+            layers: layers.max(1),
+            b: length / 2.0,
+        }
+    }
+
+    /// Returns the orientation of the coil as a unit quaternion, from the
+    /// Euler angles alpha, beta, gamma (see `utils::quaternion`)
+    pub fn orientation(&self) -> Quaternion {
+        Quaternion::from_euler(&self.alpha, &self.beta, &self.gamma)
+    }
+}
+
+/// Implements Display for ThickCoils.
+impl fmt::Display for ThickCoil {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: ({}, {}),\tl: {},\tc: {},\talpha:{}\tbeta:{}\tgamma:{}\tN: {}\tI: {} A\tlayers: {}]",
+            self.inner_radius,
+            self.outer_radius,
+            self.length,
+            self.center,
+            self.alpha.to_degrees(),
+            self.beta.to_degrees(),
+            self.gamma.to_degrees(),
+            self.turns,
+            self.current,
+            self.layers,
+        )
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for ThickCoil {
+    /// Returns the magnetic field of a thick coil at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_thick_coil(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for ThickCoil {
+    /// Returns the magnetic field of a thick coil at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_thick_coil(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
 
-    let kn = ((zn.powi(2) + (rho_na).powi(2)) / (zn.powi(2) + (rho_a).powi(2))).sqrt();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MU0;
 
-    let b_r = (b_0 / PI) * (alpha_p * cel(kp, 1.0, 1.0, -1.0) - alpha_n * cel(kn, 1.0, 1.0, -1.0));
+    #[test]
+    fn test_turns_and_current_density() {
+        let solenoid_1 = Solenoid::new(
+            0.5,
+            1.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            100.0,
+            1.0,
+        );
+        assert_eq!(solenoid_1, Solenoid::default());
+    }
 
-    let b_z = ((b_0 / PI) * a / (rho_a))
-        * (beta_p * cel(kp, gamma.powi(2), 1.0, gamma)
-            - beta_n * cel(kn, gamma.powi(2), 1.0, gamma));
+    #[test]
+    fn test_tuple_solenoid_field() {
+        let solenoid = Solenoid::with_current_density(
+            1e-3,
+            1e3,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            1e4,
+        );
+        // A long solenoid has a field of mu0 K inside
+        let field = solenoid.field(&(0.0, 0.0, 0.0)).unwrap();
+        assert!(((field.2 - MU0 * 1e4) / (MU0 * 1e4)).abs() < 1e-9);
+    }
 
-    Ok(Point2::new(b_z, b_r))
+    #[test]
+    fn test_point3_coil_field() {
+        let coil = ThickCoil::default();
+        let field = coil.field(&Point3::new(0.0, 0.0, 100.0)).unwrap();
+        assert!(field.z > 0.0 && field.z.is_finite());
+    }
 }
@@ -0,0 +1,216 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for solenoids and thick coils
+//!
+//! The field of a thin solenoid (a cylindrical current sheet) is written in
+//! terms of Bulirsch's complete elliptic integral, following Derby and Olbert,
+//! Am. J. Phys. 78, 229 (2010).
+//!
+//! A thick multi-layer coil is treated as a set of concentric thin solenoids,
+//! one for each layer of the winding.
+
+use crate::magnets::magnet3d::bulirsch::cel;
+use crate::magnets::magnet3d::{Solenoid, ThickCoil};
+use crate::points::{Point2, Point3, Points3};
+use crate::{MagnetError, FP_CUTOFF, MU0, PI};
+
+/// Calculate the magnetic field due to a solenoid at any point
+/// returns Bz,Br as a Point2 structure
+pub fn solenoid_field(a: f64, b: f64, rho: f64, z: f64, b_0: f64) -> Result<Point2, MagnetError> {
+    let zp = z + b;
+    let zn = z - b;
+
+    let rho_a = rho + a;
+    let rho_na = rho - a;
+
+    let alpha_p = a / (zp.powi(2) + (rho_a).powi(2)).sqrt();
+    let alpha_n = a / (zn.powi(2) + (rho_a).powi(2)).sqrt();
+
+    let beta_p = zp / (zp.powi(2) + (rho_a).powi(2)).sqrt();
+    let beta_n = zn / (zn.powi(2) + (rho_a).powi(2)).sqrt();
+
+    // Derby and Olbert define gamma = (a - rho) / (a + rho)
+    let gamma = (a - rho) / (rho_a);
+
+    let kp = ((zp.powi(2) + (rho_na).powi(2)) / (zp.powi(2) + (rho_a).powi(2))).sqrt();
+
+    let kn = ((zn.powi(2) + (rho_na).powi(2)) / (zn.powi(2) + (rho_a).powi(2))).sqrt();
+
+    let b_r = (b_0 / PI) * (alpha_p * cel(kp, 1.0, 1.0, -1.0) - alpha_n * cel(kn, 1.0, 1.0, -1.0));
+
+    let b_z = ((b_0 / PI) * a / (rho_a))
+        * (beta_p * cel(kp, gamma.powi(2), 1.0, gamma)
+            - beta_n * cel(kn, gamma.powi(2), 1.0, gamma));
+
+    Ok(Point2::new(b_z, b_r))
+}
+
+/// Returns the cartesian magnetic field vector at a point due to a solenoid
+/// of radius `a` and length `2b`, centred at the origin with its axis along z.
+///
+/// `b_0` is $`\mu_0 K`$, where K is the surface current density in A/m.
+pub fn solenoid_field_cartesian(
+    a: f64,
+    b: f64,
+    b_0: f64,
+    point: &Point3,
+) -> Result<Point3, MagnetError> {
+    let rho = (point.x.powi(2) + point.y.powi(2)).sqrt();
+    let field = solenoid_field(a, b, rho, point.z, b_0)?;
+
+    // Points on the edges of the curved surface are singular, bind to 0.0
+    let b_z = if field.x.is_finite() { field.x } else { 0.0 };
+    let b_rho = if field.y.is_finite() { field.y } else { 0.0 };
+
+    // On the axis the radial component vanishes
+    if rho / a < FP_CUTOFF {
+        return Ok(Point3::new(0.0, 0.0, b_z));
+    }
+
+    Ok(Point3::new(
+        b_rho * point.x / rho,
+        b_rho * point.y / rho,
+        b_z,
+    ))
+}
+
+/// Returns the magnetic field vector at a point due to a solenoid
+pub fn get_field_solenoid(solenoid: &Solenoid, point: &Point3) -> Result<Point3, MagnetError> {
+    // Translate and rotate into the local coordinates of the solenoid
+    let orientation = solenoid.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - solenoid.center));
+
+    let field = solenoid_field_cartesian(
+        solenoid.radius,
+        solenoid.b,
+        MU0 * solenoid.current_density,
+        &local_point,
+    )?;
+
+    // Rotate the field back into the global frame
+    Ok(orientation.rotate(&field))
+}
+
+/// Returns the magnetic field vector at a point due to a thick coil, by
+/// summing the fields of each layer of the winding
+pub fn get_field_thick_coil(coil: &ThickCoil, point: &Point3) -> Result<Point3, MagnetError> {
+    let orientation = coil.orientation();
+    let local_point = orientation.rotate_inverse(&(*point - coil.center));
+
+    // Each layer carries an equal share of the total current
+    let thickness = (coil.outer_radius - coil.inner_radius) / coil.layers as f64;
+    let b_0 = MU0 * coil.turns * coil.current / (coil.length * coil.layers as f64);
+
+    let mut field = Point3::zero();
+    for layer in 0..coil.layers {
+        let radius = coil.inner_radius + (layer as f64 + 0.5) * thickness;
+        field += solenoid_field_cartesian(radius, coil.b, b_0, &local_point)?;
+    }
+
+    Ok(orientation.rotate(&field))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::magnets::magnet3d::current_loop_field;
+    use crate::magnets::magnet3d::solenoid_field::*;
+    use crate::utils::conversions::Angle;
+
+    #[test]
+    fn field_at_center_of_solenoid() {
+        // Bz(0) = mu0 K b / sqrt(a^2 + b^2)
+        let solenoid = Solenoid::default();
+        let field = get_field_solenoid(&solenoid, &Point3::zero()).unwrap();
+        let comp_field = MU0 * solenoid.current_density * solenoid.b
+            / (solenoid.radius.powi(2) + solenoid.b.powi(2)).sqrt();
+        assert!((field.z - comp_field).abs() < 1e-15);
+        assert!(field.x.abs() < 1e-15 && field.y.abs() < 1e-15);
+    }
+
+    #[test]
+    fn off_axis_field_matches_current_loops() {
+        // A solenoid is a stack of current loops, each carrying K dz
+        let (a, b, current_density) = (0.5, 0.4, 1000.0);
+        let num_loops = 4000;
+        let dz = 2.0 * b / num_loops as f64;
+        for &(rho, z) in &[(0.3, 0.2), (0.8, 0.1), (0.45, -0.6)] {
+            let field = solenoid_field(a, b, rho, z, MU0 * current_density).unwrap();
+            let mut comp_field = Point2::new(0.0, 0.0);
+            for i in 0..num_loops {
+                let z_loop = -b + (i as f64 + 0.5) * dz;
+                comp_field += current_loop_field(a, current_density * dz, rho, z - z_loop).unwrap();
+            }
+            assert!((field.x - comp_field.x).abs() < 1e-6 * field.x.abs().max(1e-4));
+            assert!((field.y - comp_field.y).abs() < 1e-6 * field.y.abs().max(1e-4));
+        }
+    }
+
+    #[test]
+    fn rotated_solenoid_field() {
+        // Rotating by 90° about y maps the solenoid axis onto x
+        let solenoid = Solenoid {
+            beta: Angle::Degrees(90.0),
+            ..Default::default()
+        };
+        let field = get_field_solenoid(&solenoid, &Point3::new(0.2, 0.1, 0.0)).unwrap();
+        let comp_field =
+            get_field_solenoid(&Solenoid::default(), &Point3::new(0.0, 0.1, 0.2)).unwrap();
+        assert!((field.x - comp_field.z).abs() < 1e-15);
+        assert!((field.y - comp_field.y).abs() < 1e-15);
+        assert!((field.z + comp_field.x).abs() < 1e-15);
+    }
+
+    #[test]
+    fn thick_coil_center_field() {
+        // Bz(0) = mu0 J b ln[(R2 + sqrt(R2^2 + b^2)) / (R1 + sqrt(R1^2 + b^2))]
+        let coil = ThickCoil::new(
+            0.01,
+            0.02,
+            0.05,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            500.0,
+            2.0,
+            200,
+        );
+        let field = get_field_thick_coil(&coil, &Point3::zero()).unwrap();
+
+        let j = coil.turns * coil.current / (coil.length * (coil.outer_radius - coil.inner_radius));
+        let (r_1, r_2, b) = (coil.inner_radius, coil.outer_radius, coil.b);
+        let comp_field = MU0
+            * j
+            * b
+            * ((r_2 + (r_2.powi(2) + b.powi(2)).sqrt()) / (r_1 + (r_1.powi(2) + b.powi(2)).sqrt()))
+                .ln();
+
+        assert!(((field.z - comp_field) / comp_field).abs() < 1e-5);
+    }
+
+    #[test]
+    fn single_layer_coil_matches_solenoid() {
+        let coil = ThickCoil::new(
+            0.5,
+            0.5,
+            1.0,
+            (0.0, 0.0, 0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            Angle::Degrees(0.0),
+            100.0,
+            1.0,
+            1,
+        );
+        let solenoid = Solenoid::default();
+        let point = Point3::new(0.3, -0.4, 0.8);
+        let field = get_field_thick_coil(&coil, &point).unwrap();
+        let comp_field = get_field_solenoid(&solenoid, &point).unwrap();
+        assert!((field.x - comp_field.x).abs() < 1e-15);
+        assert!((field.y - comp_field.y).abs() < 1e-15);
+        assert!((field.z - comp_field.z).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,213 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+pub use super::sphere_field::*;
+
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::magnets::magnet3d::magnetisation_components;
+use crate::magnets::{GetCenter, GetField, MagnetTrait};
+use crate::points::{Point3, Points3};
+use crate::utils::conversions::Angle;
+use crate::MagnetError;
+
+/// A 3D Spherical magnet
+///
+/// Arguments using `new()` method:
+///     - radius (f64): magnet radius
+///     - center (Point3): magnet center
+///     - jr (f64): remnant magnetisation in T
+///     - phi (f64): angle of magnetisation in the xy-plane w.r.t. x-axis
+///     - theta (f64): angle of magnetisation w.r.t. z-axis
+///
+/// The default method generates a spherical magnet of radius 1.0 centred at
+/// (0.0, 0.0, 0.0), magnetised in z with a remnant magnetisation of 1.0 T
+/// # Example
+/// ```rust
+/// use lodestone_core::magnets::Sphere;
+/// use lodestone_core::utils::conversions::Angle;
+/// let magnet_1 = Sphere::default();
+/// println!("Magnet 1:{}", magnet_1);
+/// let magnet_2 = Sphere::new(
+///     0.5,
+///     (0.0, 0.0, -1.0),
+///     1.0,
+///     Angle::Degrees(0.0),
+///     Angle::Degrees(90.0),
+/// );
+/// println!("Magnet 2:{}", magnet_2);
+/// ```
+///
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Sphere {
+    pub radius: f64,
+    pub center: Point3,
+
+    pub jr: f64,
+    pub phi: Angle,
+    pub theta: Angle,
+
+    pub jx: f64,
+    pub jy: f64,
+    pub jz: f64,
+}
+
+impl Default for Sphere {
+    /// Default method for Sphere.
+    ///
+    /// Generates a spherical magnet of radius 1.0, centred at (0,0,0),
+    /// with a magnetisation of 1 tesla in z
+    fn default() -> Self {
+        Sphere {
+            radius: 1.0,
+            center: Point3::new(0.0, 0.0, 0.0),
+            jr: 1.0,
+            phi: Angle::Degrees(90.0),
+            theta: Angle::Degrees(0.0),
+            jx: 0.0,
+            jy: 0.0,
+            jz: 1.0,
+        }
+    }
+}
+
+impl Sphere {
+    /// Constructor for 3D Sphere magnet
+    pub fn new<C>(radius: f64, center: C, jr: f64, phi: Angle, theta: Angle) -> Sphere
+    where
+        C: GetCenter<Point3>,
+    {
+        let (jx, jy, jz) = magnetisation_components(jr, &phi, &theta);
+
+        Sphere {
+            radius,
+            center: center.center(),
+            jr,
+            phi,
+            theta,
+            jx,
+            jy,
+            jz,
+        }
+    }
+}
+
+/// Implements Display for Sphere magnets.
+impl fmt::Display for Sphere {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[r: {},\tc: {},\tJ ({:.3}, {:.3}, {:.3})]",
+            self.radius,
+            self.center,
+            self.jr,
+            self.phi.to_degrees(),
+            self.theta.to_degrees(),
+        )
+    }
+}
+
+impl MagnetTrait<Point3, Point3, f64, Point3> for Sphere {
+    /// Returns the center of a Sphere
+    fn center(&self) -> Point3 {
+        self.center
+    }
+
+    /// Returns the radius of a Sphere
+    fn size(&self) -> f64 {
+        self.radius
+    }
+
+    /// Returns the magnetisation vector of a Sphere: Point3 (jx, jy, jz)
+    fn magnetisation(self) -> Point3 {
+        Point3::new(self.jx, self.jy, self.jz)
+    }
+
+    /// Sets the magnet center
+    fn set_center(&mut self, point: Point3) {
+        self.center = point;
+    }
+
+    /// Sets the radius of a Sphere
+    fn set_size(&mut self, radius: f64) {
+        self.radius = radius;
+    }
+
+    /// Set the magnetisation of the magnet using a cartesian vector.
+    ///
+    /// This method also updates self.jr, self.phi, and self.theta
+    fn set_magnetisation(&mut self, magnetisation: Point3) {
+        self.jr = magnetisation.magnitude();
+        self.phi = Angle::Radians(magnetisation.y.atan2(magnetisation.x));
+        self.theta = Angle::Radians(if self.jr > 0.0 {
+            (magnetisation.z / self.jr).acos()
+        } else {
+            0.0
+        });
+        self.jx = magnetisation.x;
+        self.jy = magnetisation.y;
+        self.jz = magnetisation.z;
+    }
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Sphere {
+    /// Returns the magnetic field of a spherical magnet at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_sphere(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Sphere {
+    /// Returns the magnetic field of a spherical magnet at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_sphere(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::comparison::nearly_equal;
+
+    use super::*;
+
+    #[test]
+    fn test_point3_sphere_field() {
+        let magnet = Sphere::default();
+
+        let point = Point3::new(0.0, 0.0, 0.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.x, 0.0_f64));
+        assert!(nearly_equal(field.y, 0.0_f64));
+        assert!(nearly_equal(field.z, 2.0 / 3.0));
+    }
+
+    #[test]
+    fn test_tuple_sphere_field() {
+        let magnet = Sphere::default();
+
+        let point = (0.0, 0.0, 2.0);
+        let field = magnet.field(&point).unwrap();
+        assert!(nearly_equal(field.0, 0.0_f64));
+        assert!(nearly_equal(field.1, 0.0_f64));
+        assert!(nearly_equal(field.2, 1.0 / 12.0));
+    }
+
+    #[test]
+    fn test_set_magnetisation() {
+        let mut magnet = Sphere::default();
+        magnet.set_magnetisation(Point3::new(0.0, 0.0, -2.0));
+        assert!(nearly_equal(magnet.jr, 2.0));
+        assert!(nearly_equal(magnet.theta.to_degrees(), 180.0));
+    }
+}
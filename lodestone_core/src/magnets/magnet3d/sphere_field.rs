@@ -0,0 +1,94 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for spheres
+//!
+//! The field of a uniformly magnetised sphere is exact: outside it is that of
+//! a point dipole at its center with moment $`\mathbf{J} V / \mu_0`$, and
+//! inside it is uniform, $`\mathbf{B} = 2 \mathbf{J} / 3`$.
+
+use crate::magnets::magnet3d::Sphere;
+use crate::points::{Point3, Points3};
+use crate::MagnetError;
+
+/// Returns the magnetic field vector due to a uniformly magnetised sphere
+/// of radius `R`, with magnetisation
+/// $`\mathbf{J} = J_x \mathbf{\hat{x}} + J_y \mathbf{\hat{y}} + J_z \mathbf{\hat{z}}`$
+///
+/// Outside the sphere:
+///
+/// $`\mathbf{B} = \frac{R^3}{3 r^3} \left[ 3 (\mathbf{J} \cdot \mathbf{\hat{r}}) \mathbf{\hat{r}} - \mathbf{J} \right]`$
+///
+/// and inside $`\mathbf{B} = 2 \mathbf{J} / 3`$
+pub fn get_field_sphere(magnet: &Sphere, point: &Point3) -> Result<Point3, MagnetError> {
+    let magnetisation = Point3::new(magnet.jx, magnet.jy, magnet.jz);
+
+    // Translate into local coordinates
+    let local_point = *point - magnet.center;
+    let r = local_point.magnitude();
+
+    if r < magnet.radius {
+        return Ok(magnetisation.scale(2.0 / 3.0));
+    }
+
+    let r_hat = local_point.scale(1.0 / r);
+    let prefac = magnet.radius.powi(3) / (3.0 * r.powi(3));
+
+    Ok((r_hat.scale(3.0 * magnetisation.dot(&r_hat)) - magnetisation).scale(prefac))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::magnets::magnet3d::sphere_field::*;
+    use crate::points::Point3;
+    use crate::utils::comparison::nearly_equal;
+    use crate::utils::conversions::Angle;
+
+    #[test]
+    fn interior_field_is_uniform() {
+        let magnet = Sphere::new(
+            2.0,
+            (1.0, -1.0, 0.5),
+            1.5,
+            Angle::Degrees(30.0),
+            Angle::Degrees(60.0),
+        );
+        let field = get_field_sphere(&magnet, &Point3::new(1.5, -0.2, 1.0)).unwrap();
+        assert!(nearly_equal(field.x, 2.0 * magnet.jx / 3.0));
+        assert!(nearly_equal(field.y, 2.0 * magnet.jy / 3.0));
+        assert!(nearly_equal(field.z, 2.0 * magnet.jz / 3.0));
+    }
+
+    #[test]
+    fn surface_field_z() {
+        // On the poles B is continuous, 2J/3, and on the equator it is -J/3
+        let magnet = Sphere::default();
+        let pole = get_field_sphere(&magnet, &Point3::new(0.0, 0.0, 1.0)).unwrap();
+        let equator = get_field_sphere(&magnet, &Point3::new(0.0, 1.0, 0.0)).unwrap();
+
+        assert!(nearly_equal(pole.z, 2.0 / 3.0));
+        assert!(nearly_equal(equator.z, -1.0 / 3.0));
+        assert!(nearly_equal(equator.y, 0.0));
+    }
+
+    #[test]
+    fn dipole_field_off_axis() {
+        let magnet = Sphere::new(
+            1.0,
+            (0.0, 0.0, 0.0),
+            1.0,
+            Angle::Degrees(0.0),
+            Angle::Degrees(90.0),
+        );
+        // Point at 45 degrees in the xy-plane, at r = 2
+        let point = Point3::new(2.0_f64.sqrt(), 2.0_f64.sqrt(), 0.0);
+        let field = get_field_sphere(&magnet, &point).unwrap();
+
+        let prefac = 1.0 / 24.0;
+        assert!(nearly_equal(field.x, prefac * 0.5));
+        assert!(nearly_equal(field.y, prefac * 1.5));
+        assert!(nearly_equal(field.z, 0.0));
+    }
+}
@@ -0,0 +1,158 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+use super::triangle_field::charged_triangle_field;
+use crate::magnets::GetField;
+use crate::points::{Point3, Points3};
+use crate::MagnetError;
+use serde_derive::{Deserialize, Serialize};
+
+/// Triangle struct for calculating the magnetic field due to a uniformly
+/// charged triangular sheet, the surface element of a polyhedron.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Triangle {
+    // vertices, ordered anti-clockwise about the normal
+    pub vertices: [Point3; 3],
+    // unit normal
+    pub normal: Point3,
+    // area of the triangle
+    pub area: f64,
+    // surface charge density in units T
+    pub sigma: f64,
+}
+
+/// Convenience type alias for a Vec of Triangle
+pub type TriangleVec = Vec<Triangle>;
+
+impl Default for Triangle {
+    /// Default method for Triangle.
+    ///
+    /// Generates a right angled triangle in the xy-plane with unit sides,
+    /// and a surface charge density equivalent to 1 tesla
+    fn default() -> Self {
+        Triangle::new(
+            [
+                Point3::new(0.0, 0.0, 0.0),
+                Point3::new(1.0, 0.0, 0.0),
+                Point3::new(0.0, 1.0, 0.0),
+            ],
+            1.0,
+        )
+    }
+}
+
+impl Triangle {
+    /// Creates a new Triangle, the normal is given by the right hand rule
+    /// from the vertex order
+    pub fn new(vertices: [Point3; 3], sigma: f64) -> Triangle {
+        let normal = (vertices[1] - vertices[0]).cross(&(vertices[2] - vertices[0]));
+        let area = normal.magnitude() / 2.0;
+        Triangle {
+            vertices,
+            normal: normal.unit(),
+            area,
+            sigma,
+        }
+    }
+}
+
+/// Returns an array of charged triangles for a closed triangulated surface,
+/// along with the signed volume and the centroid of the enclosed solid.
+///
+/// The signed volume is positive if the faces are ordered anti-clockwise when
+/// viewed from outside, i.e. the normals point outwards. The surface charge
+/// density of each triangle is $`\sigma = \mathbf{J} \cdot \mathbf{\hat{n}}`$.
+pub fn generate_triangle_array(
+    vertices: &[Point3],
+    faces: &[[usize; 3]],
+    magnetisation: &Point3,
+) -> (TriangleVec, f64, Point3) {
+    let mut triangle_array = TriangleVec::with_capacity(faces.len());
+    let mut volume = 0.0;
+    let mut centroid = Point3::zero();
+
+    for face in faces {
+        let corners = [vertices[face[0]], vertices[face[1]], vertices[face[2]]];
+
+        // Signed volume of the tetrahedron formed with the origin
+        let weight = corners[0].dot(&corners[1].cross(&corners[2])) / 6.0;
+        volume += weight;
+        centroid += (corners[0] + corners[1] + corners[2]).scale(weight / 4.0);
+
+        let mut triangle = Triangle::new(corners, 0.0);
+        triangle.sigma = magnetisation.dot(&triangle.normal);
+        triangle_array.push(triangle);
+    }
+
+    centroid = centroid.scale(1.0 / volume);
+
+    (triangle_array, volume, centroid)
+}
+
+fn get_field_triangle(magnet: &Triangle, point: &Point3) -> Result<Point3, MagnetError> {
+    Ok(charged_triangle_field(
+        &magnet.vertices,
+        &magnet.normal,
+        magnet.sigma,
+        point,
+    ))
+}
+
+impl GetField<&Point3, Result<Point3, MagnetError>> for Triangle {
+    /// Returns the magnetic field of a charged triangle at a Point3 struct {x,y,z}
+    fn field(&self, point: &Point3) -> Result<Point3, MagnetError> {
+        get_field_triangle(self, point)
+    }
+}
+
+impl GetField<&(f64, f64, f64), Result<(f64, f64, f64), MagnetError>> for Triangle {
+    /// Returns the magnetic field of a charged triangle at a 3-element tuple (x,y,z)
+    fn field(&self, point: &(f64, f64, f64)) -> Result<(f64, f64, f64), MagnetError> {
+        let field_vec = get_field_triangle(
+            self,
+            &Point3 {
+                x: point.0,
+                y: point.1,
+                z: point.2,
+            },
+        )?;
+        Ok(field_vec.as_tuple())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::comparison::nearly_equal;
+
+    #[test]
+    fn test_default_triangle() {
+        let triangle = Triangle::default();
+        assert_eq!(triangle.normal, Point3::k_hat());
+        assert!(nearly_equal(triangle.area, 0.5));
+    }
+
+    #[test]
+    fn test_generate_triangle_array_tetrahedron() {
+        let vertices = vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+            Point3::new(0.0, 0.0, 1.0),
+        ];
+        let faces = vec![[0, 2, 1], [0, 1, 3], [0, 3, 2], [1, 2, 3]];
+        let (triangle_array, volume, centroid) =
+            generate_triangle_array(&vertices, &faces, &Point3::k_hat());
+
+        assert!(nearly_equal(volume, 1.0 / 6.0));
+        assert!(nearly_equal(centroid.x, 0.25));
+        assert!(nearly_equal(centroid.y, 0.25));
+        assert!(nearly_equal(centroid.z, 0.25));
+
+        // The base has an outward normal in -z
+        assert!(nearly_equal(triangle_array[0].sigma, -1.0));
+        assert!(nearly_equal(triangle_array[1].sigma, 0.0));
+    }
+}
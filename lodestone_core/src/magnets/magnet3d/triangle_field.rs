@@ -0,0 +1,114 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+
+//! 3D Magnetic Field Routines for uniformly charged triangles
+//!
+//! These are the building blocks of polyhedral magnets, in the same way that
+//! the charged sheets of `line_field` are for 2D polygons.
+
+use crate::points::{Point3, Points3};
+use crate::I_4PI;
+
+/// Returns the field due to a triangle with a uniform surface charge
+/// density `sigma` (in T), with vertices `vertices` ordered anti-clockwise
+/// about the unit normal `normal`.
+///
+/// The integral $`\int (\mathbf{r} - \mathbf{r'})/|\mathbf{r} - \mathbf{r'}|^3 dA'`$
+/// over the triangle is
+///
+/// $`\mathbf{\hat{n}} \Omega + \sum_{e} \mathbf{\hat{m}}_e \ln \frac{R_1 + R_2 + L}{R_1 + R_2 - L}`$
+///
+/// where $`\Omega`$ is the signed solid angle subtended by the triangle, and for
+/// each edge of length $`L`$, $`\mathbf{\hat{m}}_e`$ is the in-plane outward normal,
+/// and $`R_1, R_2`$ are the distances to its end points.
+pub fn charged_triangle_field(
+    vertices: &[Point3; 3],
+    normal: &Point3,
+    sigma: f64,
+    point: &Point3,
+) -> Point3 {
+    let mut field = normal.scale(solid_angle(vertices, point));
+
+    for i in 0..3 {
+        let start = vertices[i];
+        let end = vertices[(i + 1) % 3];
+        let edge = end - start;
+        let length = edge.magnitude();
+        let r_sum = start.distance_from_point(point) + end.distance_from_point(point);
+
+        let log_term = ((r_sum + length) / (r_sum - length)).ln();
+
+        // Points on the line of an edge are singular, bind to 0.0
+        if log_term.is_finite() {
+            let edge_normal = edge.cross(normal).scale(1.0 / length);
+            field += edge_normal.scale(log_term);
+        }
+    }
+
+    field.scale(sigma * I_4PI)
+}
+
+/// Returns the solid angle subtended by a triangle at a point, using the
+/// formula of Van Oosterom and Strackee, IEEE Trans. Biomed. Eng. 30, 125 (1983).
+///
+/// It is positive when the point lies on the side the normal points to, i.e.
+/// when the vertices appear anti-clockwise from the point.
+pub fn solid_angle(vertices: &[Point3; 3], point: &Point3) -> f64 {
+    let a = vertices[0] - *point;
+    let b = vertices[1] - *point;
+    let c = vertices[2] - *point;
+    let (a_mag, b_mag, c_mag) = (a.magnitude(), b.magnitude(), c.magnitude());
+
+    let triple = a.dot(&b.cross(&c));
+    let denominator =
+        a_mag * b_mag * c_mag + a.dot(&b) * c_mag + a.dot(&c) * b_mag + b.dot(&c) * a_mag;
+
+    -2.0 * triple.atan2(denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::comparison::nearly_equal;
+    use crate::M2_PI;
+
+    fn unit_triangle() -> [Point3; 3] {
+        [
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(0.0, 1.0, 0.0),
+        ]
+    }
+
+    #[test]
+    fn solid_angle_sign() {
+        let vertices = unit_triangle();
+        let above = solid_angle(&vertices, &Point3::new(0.2, 0.2, 1.0));
+        let below = solid_angle(&vertices, &Point3::new(0.2, 0.2, -1.0));
+        assert!(above > 0.0);
+        assert!(nearly_equal(above, -below));
+    }
+
+    #[test]
+    fn solid_angle_close_to_face() {
+        // Just above the face the triangle covers half of all directions
+        let omega = solid_angle(&unit_triangle(), &Point3::new(0.2, 0.2, 1e-9));
+        assert!((omega - M2_PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn far_field_point_charge() {
+        // A charged triangle of area 1/2 looks like a point charge far away
+        let vertices = unit_triangle();
+        let distance = 1000.0_f64;
+        let point = Point3::new(1.0 / 3.0, 1.0 / 3.0, distance);
+        let field = charged_triangle_field(&vertices, &Point3::k_hat(), 1.0, &point);
+
+        let comp_field = 0.5 * I_4PI / distance.powi(2);
+        assert!(((field.z - comp_field) / comp_field).abs() < 1e-6);
+        assert!((field.x / comp_field).abs() < 1e-6);
+        assert!((field.y / comp_field).abs() < 1e-6);
+    }
+}
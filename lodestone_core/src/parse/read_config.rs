@@ -7,7 +7,11 @@ Copyright 2021 Peter Dunne */
 
 #![allow(clippy::too_many_arguments)]
 use crate::{
-    magnets::{Circle, Magnet2D, MagnetVec2D, PolyDimension, Polygon, Rectangle, Vertices},
+    magnets::{
+        alternating_array_2d, circular_halbach_2d, linear_halbach_2d, ArcSegment, Circle, Ellipse,
+        Magnet2D, MagnetVec2D, PolyDimension, Polygon, Rectangle, Ring, RingMagnetisation,
+        RotationSense, SegmentShape, Vertices, Wire, WireSection,
+    },
     points::{cart_prod_2d_vec, gen_line_2d, Point2, PointVec2},
     utils::conversions::Angle,
     MagnetError,
@@ -36,6 +40,16 @@ pub enum MagnetKind {
 
     Polygon(ReadPolygon),
     CustomPolygon(ReadCustomPolygon),
+    /// Current carrying wire
+    Wire(ReadWire),
+    /// Patterned array of magnets
+    Array(ReadArray),
+    /// Ring magnet
+    Ring(ReadRing),
+    /// Radially magnetised arc segment
+    ArcSegment(ReadArcSegment),
+    /// Elliptical magnet
+    Ellipse(ReadEllipse),
 }
 
 /// Stores input properties of a rectangular 2D magnet
@@ -200,6 +214,9 @@ pub struct ReadCustomPolygon {
     alpha: f64,
     alpha_angle: String,
     vertices: PointVec2,
+    /// Interior cut-outs, each a ring of vertices
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    holes: Vec<PointVec2>,
 }
 
 impl ReadCustomPolygon {
@@ -211,6 +228,7 @@ impl ReadCustomPolygon {
         alpha: f64,
         alpha_angle: String,
         vertices: PointVec2,
+        holes: Vec<PointVec2>,
     ) -> Self {
         ReadCustomPolygon {
             center,
@@ -219,6 +237,7 @@ impl ReadCustomPolygon {
             alpha,
             alpha_angle,
             vertices,
+            holes,
         }
     }
     /// Default ReadReactangle
@@ -238,6 +257,354 @@ fn default_custom_polygon() -> ReadCustomPolygon {
             x: vec![0.5, 0.5, -0.5, -0.5],
             y: vec![0.5, -0.5, -0.5, 0.5],
         },
+        holes: Vec::new(),
+    }
+}
+
+/// Stores input properties of a 2D elliptical magnet.
+///
+/// `size` is [a, b], the semi-axes along the local x and y axes. As for a
+/// rectangle, `alpha` rotates the ellipse clockwise, and the angle of the
+/// magnetisation is measured from the local x-axis.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default = "default_ellipse")]
+pub struct ReadEllipse {
+    size: [f64; 2],
+    center: [f64; 2],
+    magnetisation: [f64; 2],
+    mag_angle: String,
+    alpha: f64,
+    alpha_angle: String,
+}
+
+impl ReadEllipse {
+    /// Constructor function to generate a ReadEllipse
+    pub fn new(
+        size: [f64; 2],
+        center: [f64; 2],
+        magnetisation: [f64; 2],
+        mag_angle: String,
+        alpha: f64,
+        alpha_angle: String,
+    ) -> Self {
+        ReadEllipse {
+            size,
+            center,
+            magnetisation,
+            mag_angle,
+            alpha,
+            alpha_angle,
+        }
+    }
+}
+
+impl Default for ReadEllipse {
+    /// Default ReadEllipse
+    fn default() -> Self {
+        default_ellipse()
+    }
+}
+
+fn default_ellipse() -> ReadEllipse {
+    ReadEllipse {
+        size: [1.0, 0.5],
+        center: [0.0, 0.0],
+        magnetisation: [1.0, 90.0],
+        mag_angle: "degrees".to_string(),
+        alpha: 0.0,
+        alpha_angle: "degrees".to_string(),
+    }
+}
+
+/// Stores input properties of a 2D ring magnet.
+///
+/// `size` is [inner radius, outer radius], and `orientation` is one of
+/// "diametric", "radial" or "azimuthal". The angle of the magnetisation is only
+/// used for diametric rings.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default = "default_ring")]
+pub struct ReadRing {
+    size: [f64; 2],
+    center: [f64; 2],
+    magnetisation: [f64; 2],
+    mag_angle: String,
+    orientation: String,
+    alpha: f64,
+    alpha_angle: String,
+}
+
+impl ReadRing {
+    /// Constructor function to generate a ReadRing
+    pub fn new(
+        size: [f64; 2],
+        center: [f64; 2],
+        magnetisation: [f64; 2],
+        mag_angle: String,
+        orientation: String,
+        alpha: f64,
+        alpha_angle: String,
+    ) -> Self {
+        ReadRing {
+            size,
+            center,
+            magnetisation,
+            mag_angle,
+            orientation,
+            alpha,
+            alpha_angle,
+        }
+    }
+}
+
+impl Default for ReadRing {
+    /// Default ReadRing
+    fn default() -> Self {
+        default_ring()
+    }
+}
+
+fn default_ring() -> ReadRing {
+    ReadRing {
+        size: [0.5, 1.0],
+        center: [0.0, 0.0],
+        magnetisation: [1.0, 90.0],
+        mag_angle: "degrees".to_string(),
+        orientation: "diametric".to_string(),
+        alpha: 0.0,
+        alpha_angle: "degrees".to_string(),
+    }
+}
+
+/// Stores input properties of a 2D radially magnetised arc segment.
+///
+/// `size` is [inner radius, outer radius], `alpha` is the angle of the
+/// mid-line of the segment, and `span` the angle subtended at its center.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default = "default_arc_segment")]
+pub struct ReadArcSegment {
+    size: [f64; 2],
+    center: [f64; 2],
+    magnetisation: f64,
+    alpha: f64,
+    alpha_angle: String,
+    span: f64,
+    span_angle: String,
+}
+
+impl ReadArcSegment {
+    /// Constructor function to generate a ReadArcSegment
+    pub fn new(
+        size: [f64; 2],
+        center: [f64; 2],
+        magnetisation: f64,
+        alpha: f64,
+        alpha_angle: String,
+        span: f64,
+        span_angle: String,
+    ) -> Self {
+        ReadArcSegment {
+            size,
+            center,
+            magnetisation,
+            alpha,
+            alpha_angle,
+            span,
+            span_angle,
+        }
+    }
+}
+
+impl Default for ReadArcSegment {
+    /// Default ReadArcSegment
+    fn default() -> Self {
+        default_arc_segment()
+    }
+}
+
+fn default_arc_segment() -> ReadArcSegment {
+    ReadArcSegment {
+        size: [0.5, 1.0],
+        center: [0.0, 0.0],
+        magnetisation: 1.0,
+        alpha: 90.0,
+        alpha_angle: "degrees".to_string(),
+        span: 60.0,
+        span_angle: "degrees".to_string(),
+    }
+}
+
+/// Stores input properties of a 2D current carrying wire.
+///
+/// `section` is one of "filament", "rectangle" or "circle". A rectangular
+/// section has a `size` of [width, height], while a circular section only uses
+/// the first element, its radius.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default = "default_wire")]
+pub struct ReadWire {
+    center: [f64; 2],
+    current: f64,
+    section: String,
+    size: [f64; 2],
+    alpha: f64,
+    alpha_angle: String,
+}
+
+impl ReadWire {
+    /// Constructor function to generate a ReadWire
+    pub fn new(
+        center: [f64; 2],
+        current: f64,
+        section: String,
+        size: [f64; 2],
+        alpha: f64,
+        alpha_angle: String,
+    ) -> Self {
+        ReadWire {
+            center,
+            current,
+            section,
+            size,
+            alpha,
+            alpha_angle,
+        }
+    }
+}
+
+impl Default for ReadWire {
+    /// Default ReadWire
+    fn default() -> Self {
+        default_wire()
+    }
+}
+
+fn default_wire() -> ReadWire {
+    ReadWire {
+        center: [0.0, 0.0],
+        current: 1.0,
+        section: "filament".to_string(),
+        size: [0.0, 0.0],
+        alpha: 0.0,
+        alpha_angle: "degrees".to_string(),
+    }
+}
+
+/// Stores input properties of a patterned array of 2D magnets.
+///
+/// `pattern` is one of "linearHalbach", "alternating" or "circularHalbach".
+/// Linear arrays are spaced by `pitch` along an axis at an angle `alpha`, while
+/// circular Halbach cylinders are centred on a circle of `radius`, with
+/// segments of `shape` "polygon" or "rectangle". `sense` is either
+/// "anticlockwise" or "clockwise".
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default = "default_array")]
+pub struct ReadArray {
+    pattern: String,
+    count: usize,
+    segments: usize,
+    pole_pairs: usize,
+    pitch: f64,
+    radius: f64,
+    size: [f64; 2],
+    center: [f64; 2],
+    magnetisation: f64,
+    sense: String,
+    shape: String,
+    alpha: f64,
+    alpha_angle: String,
+}
+
+impl ReadArray {
+    /// Generates the magnets of the array
+    pub fn generate(&self) -> Result<MagnetVec2D, MagnetError> {
+        let center = (self.center[0], self.center[1]);
+        let alpha = match self.alpha_angle.to_lowercase().as_str() {
+            "degrees" => Angle::Degrees(self.alpha),
+            "radians" => Angle::Radians(self.alpha),
+            _ => Angle::Degrees(self.alpha),
+        };
+        let sense = match self.sense.to_lowercase().as_str() {
+            "clockwise" => RotationSense::Clockwise,
+            "anticlockwise" => RotationSense::AntiClockwise,
+            _ => {
+                return Err(MagnetError::ConfigValueError(
+                    "sense".to_string(),
+                    self.sense.clone(),
+                ))
+            }
+        };
+        let shape = match self.shape.to_lowercase().as_str() {
+            "rectangle" => SegmentShape::Rectangle,
+            "polygon" => SegmentShape::Polygon,
+            _ => {
+                return Err(MagnetError::ConfigValueError(
+                    "shape".to_string(),
+                    self.shape.clone(),
+                ))
+            }
+        };
+
+        Ok(match self.pattern.to_lowercase().as_str() {
+            "alternating" => alternating_array_2d(
+                center,
+                alpha,
+                self.count,
+                self.pitch,
+                self.size,
+                self.magnetisation,
+                sense,
+            ),
+            "circularhalbach" => circular_halbach_2d(
+                center,
+                self.count,
+                self.pole_pairs,
+                self.radius,
+                self.size,
+                self.magnetisation,
+                sense,
+                shape,
+            )?,
+            "linearhalbach" => linear_halbach_2d(
+                center,
+                alpha,
+                self.count,
+                self.segments,
+                self.pitch,
+                self.size,
+                self.magnetisation,
+                sense,
+            ),
+            _ => {
+                return Err(MagnetError::ConfigValueError(
+                    "pattern".to_string(),
+                    self.pattern.clone(),
+                ))
+            }
+        })
+    }
+}
+
+impl Default for ReadArray {
+    /// Default ReadArray
+    fn default() -> Self {
+        default_array()
+    }
+}
+
+fn default_array() -> ReadArray {
+    ReadArray {
+        pattern: "linearHalbach".to_string(),
+        count: 8,
+        segments: 4,
+        pole_pairs: 1,
+        pitch: 1.0,
+        radius: 1.0,
+        size: [1.0, 1.0],
+        center: [0.0, 0.0],
+        magnetisation: 1.0,
+        sense: "anticlockwise".to_string(),
+        shape: "polygon".to_string(),
+        alpha: 0.0,
+        alpha_angle: "degrees".to_string(),
     }
 }
 
@@ -367,7 +734,7 @@ pub fn generate_points(grid: GridKind2D) -> Result<PointVec2, MagnetError> {
 pub fn generate_magnets(magnets: Vec<MagnetKind>) -> Result<MagnetVec2D, MagnetError> {
     let mut magnet_list = MagnetVec2D::new();
     for mag in magnets {
-        magnet_list.push(match mag {
+        let magnet = match mag {
             MagnetKind::Circle(val) => Magnet2D::Circle(Circle::new(
                 val.size,
                 (val.center[0], val.center[1]),
@@ -418,8 +785,8 @@ pub fn generate_magnets(magnets: Vec<MagnetKind>) -> Result<MagnetVec2D, MagnetE
                     };
                     Vertices::Regular(val.num_sides, polydim)
                 },
-            )),
-            MagnetKind::CustomPolygon(val) => Magnet2D::Polygon(Polygon::new(
+            )?),
+            MagnetKind::CustomPolygon(val) => Magnet2D::Polygon(Polygon::with_holes(
                 (val.center[0], val.center[1]),
                 match val.alpha_angle.to_lowercase().as_str() {
                     "degrees" => Angle::Degrees(val.alpha),
@@ -433,8 +800,93 @@ pub fn generate_magnets(magnets: Vec<MagnetKind>) -> Result<MagnetVec2D, MagnetE
                     _ => Angle::Degrees(val.magnetisation[1]),
                 },
                 Vertices::Custom(val.vertices),
+                val.holes,
+            )?),
+            MagnetKind::Wire(val) => Magnet2D::Wire(Wire::new(
+                (val.center[0], val.center[1]),
+                match val.alpha_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.alpha),
+                    "radians" => Angle::Radians(val.alpha),
+                    _ => Angle::Degrees(val.alpha),
+                },
+                val.current,
+                match val.section.to_lowercase().as_str() {
+                    "rectangle" => WireSection::Rectangle(val.size[0], val.size[1]),
+                    "circle" => WireSection::Circle(val.size[0]),
+                    "filament" => WireSection::Filament,
+                    _ => {
+                        return Err(MagnetError::ConfigValueError(
+                            "section".to_string(),
+                            val.section,
+                        ))
+                    }
+                },
             )),
-        });
+            MagnetKind::Ring(val) => Magnet2D::Ring(Ring::new(
+                val.size[0],
+                val.size[1],
+                (val.center[0], val.center[1]),
+                match val.alpha_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.alpha),
+                    "radians" => Angle::Radians(val.alpha),
+                    _ => Angle::Degrees(val.alpha),
+                },
+                val.magnetisation[0],
+                match val.mag_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.magnetisation[1]),
+                    "radians" => Angle::Radians(val.magnetisation[1]),
+                    _ => Angle::Degrees(val.magnetisation[1]),
+                },
+                match val.orientation.to_lowercase().as_str() {
+                    "radial" => RingMagnetisation::Radial,
+                    "azimuthal" => RingMagnetisation::Azimuthal,
+                    "diametric" => RingMagnetisation::Diametric,
+                    _ => {
+                        return Err(MagnetError::ConfigValueError(
+                            "orientation".to_string(),
+                            val.orientation,
+                        ))
+                    }
+                },
+            )),
+            MagnetKind::ArcSegment(val) => Magnet2D::ArcSegment(ArcSegment::new(
+                val.size[0],
+                val.size[1],
+                (val.center[0], val.center[1]),
+                match val.alpha_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.alpha),
+                    "radians" => Angle::Radians(val.alpha),
+                    _ => Angle::Degrees(val.alpha),
+                },
+                match val.span_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.span),
+                    "radians" => Angle::Radians(val.span),
+                    _ => Angle::Degrees(val.span),
+                },
+                val.magnetisation,
+            )),
+            MagnetKind::Ellipse(val) => Magnet2D::Ellipse(Ellipse::new(
+                val.size[0],
+                val.size[1],
+                (val.center[0], val.center[1]),
+                match val.alpha_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.alpha),
+                    "radians" => Angle::Radians(val.alpha),
+                    _ => Angle::Degrees(val.alpha),
+                },
+                val.magnetisation[0],
+                match val.mag_angle.to_lowercase().as_str() {
+                    "degrees" => Angle::Degrees(val.magnetisation[1]),
+                    "radians" => Angle::Radians(val.magnetisation[1]),
+                    _ => Angle::Degrees(val.magnetisation[1]),
+                },
+            )),
+            MagnetKind::Array(val) => {
+                magnet_list.extend(val.generate()?);
+                continue;
+            }
+        };
+        magnet_list.push(magnet);
     }
 
     Ok(magnet_list)
@@ -534,7 +986,8 @@ numPoints = 2"#;
             1.0,
             Angle::Degrees(90.0),
             Vertices::Regular(4, PolyDimension::Side(2.0)),
-        );
+        )
+        .unwrap();
         magnet_list_vec.push(Magnet2D::Polygon(m1));
 
         let point_vec = PointVec2::new(vec![0.0, 0.0], vec![-1.01, 0.010000000000000009]);
@@ -572,7 +1025,8 @@ numPoints = 2"#;
             1.0,
             Angle::Degrees(90.0),
             Vertices::Regular(4, PolyDimension::Side(2.0)),
-        );
+        )
+        .unwrap();
 
         magnet_list_vec.push(Magnet2D::Polygon(m1));
 
@@ -608,11 +1062,139 @@ numPoints = 2"#;
             2.0,
             Angle::Radians(0.0),
             Vertices::Regular(6, PolyDimension::Apothem(3.0)),
-        );
+        )
+        .unwrap();
 
         magnet_list_vec.push(Magnet2D::Polygon(m1));
         // assert!(true);
 
         assert_eq!(magnet_list[0], magnet_list_vec[0]);
     }
+
+    #[test]
+    fn test_toml_arc_segment() {
+        let config_text = r#"[[magnet]]
+kind = "arcSegment"
+size = [0.4, 0.6]
+magnetisation = -1.2
+alpha = 0.5
+alphaAngle = "radians"
+span = 30.0
+
+[grid]
+kind = "none""#;
+        let config: Configure = toml::from_str(config_text).unwrap();
+        let magnet_list = generate_magnets(config.magnet).unwrap();
+        assert_eq!(
+            magnet_list[0],
+            Magnet2D::ArcSegment(ArcSegment::new(
+                0.4,
+                0.6,
+                (0.0, 0.0),
+                Angle::Radians(0.5),
+                Angle::Degrees(30.0),
+                -1.2,
+            ))
+        );
+    }
+
+    #[test]
+    fn test_toml_array() {
+        let config_text = r#"[[magnet]]
+kind = "array"
+pattern = "circularHalbach"
+count = 12
+polePairs = 2
+radius = 1.5
+size = [0.5, 0.4]
+magnetisation = 1.2
+sense = "clockwise"
+shape = "rectangle"
+
+[[magnet]]
+kind = "array"
+pattern = "alternating"
+count = 3
+pitch = 0.5
+size = [0.4, 0.4]
+alpha = 45.0
+
+[grid]
+kind = "none""#;
+        let config: Configure = toml::from_str(config_text).unwrap();
+        let magnet_list = generate_magnets(config.magnet).unwrap();
+
+        let mut magnet_list_vec = circular_halbach_2d(
+            (0.0, 0.0),
+            12,
+            2,
+            1.5,
+            [0.5, 0.4],
+            1.2,
+            RotationSense::Clockwise,
+            SegmentShape::Rectangle,
+        )
+        .unwrap();
+        magnet_list_vec.extend(alternating_array_2d(
+            (0.0, 0.0),
+            Angle::Degrees(45.0),
+            3,
+            0.5,
+            [0.4, 0.4],
+            1.0,
+            RotationSense::AntiClockwise,
+        ));
+
+        assert_eq!(magnet_list.len(), 15);
+        assert_eq!(magnet_list, magnet_list_vec);
+    }
+
+    #[test]
+    fn test_toml_wire_rejects_unknown_section() {
+        let config_text = r#"[[magnet]]
+kind = "wire"
+section = "hexagon"
+
+[grid]
+kind = "none""#;
+        let config: Configure = toml::from_str(config_text).unwrap();
+        assert!(matches!(
+            generate_magnets(config.magnet),
+            Err(MagnetError::ConfigValueError(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_toml_ring_rejects_unknown_orientation() {
+        let config_text = r#"[[magnet]]
+kind = "ring"
+orientation = "axial"
+
+[grid]
+kind = "none""#;
+        let config: Configure = toml::from_str(config_text).unwrap();
+        assert!(matches!(
+            generate_magnets(config.magnet),
+            Err(MagnetError::ConfigValueError(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_toml_array_rejects_unknown_values() {
+        for option in &[
+            "pattern = \"linearHalback\"",
+            "sense = \"widdershins\"",
+            "shape = \"square\"",
+        ] {
+            let config_text = format!(
+                "[[magnet]]\nkind = \"array\"\n{}\n\n[grid]\nkind = \"none\"",
+                option
+            );
+            let config: Configure = toml::from_str(&config_text).unwrap();
+            assert!(matches!(
+                generate_magnets(config.magnet),
+                Err(MagnetError::ConfigValueError(_, _))
+            ));
+        }
+    }
 }
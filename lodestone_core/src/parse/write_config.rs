@@ -1,6 +1,9 @@
-use super::{MagnetKind, ReadCircle, ReadCustomPolygon, ReadRectangle};
+use super::{
+    MagnetKind, ReadArcSegment, ReadCircle, ReadCustomPolygon, ReadEllipse, ReadRectangle,
+    ReadRing, ReadWire,
+};
 use crate::{
-    magnets::{Magnet2D, MagnetTrait},
+    magnets::{Energy2D, FieldKind, Magnet2D, MagnetTrait, RingMagnetisation, WireSection},
     points::PointVec2,
     MagnetError,
 };
@@ -19,8 +22,14 @@ pub struct SimResult {
     pub points: PointVec2,
     /// Unit of length for points
     pub units: String,
+    /// Which field was calculated, B in tesla, or H and M in A/m
+    #[serde(default)]
+    pub field_kind: FieldKind,
     /// Array of calculated magnetic field
     pub field: PointVec2,
+    /// Self, pairwise, and total energies of the magnets
+    #[serde(default)]
+    pub energy: Energy2D,
 }
 
 impl SimResult {
@@ -29,13 +38,17 @@ impl SimResult {
         magnets: Vec<MagnetKind>,
         points: PointVec2,
         units: String,
+        field_kind: FieldKind,
         field: PointVec2,
+        energy: Energy2D,
     ) -> Self {
         SimResult {
             magnets,
             points,
             units,
+            field_kind,
             field,
+            energy,
         }
     }
 }
@@ -67,6 +80,53 @@ pub fn magnet2d_to_toml(magnet: &Magnet2D) -> Result<MagnetKind, MagnetError> {
             mag.alpha.to_degrees(),
             "Degrees".to_string(),
             mag.vertices.clone(), //TODO: Fix clone ownership
+            mag.holes.clone(),
+        )),
+        Magnet2D::Wire(mag) => {
+            let (section, size) = match mag.section {
+                WireSection::Filament => ("filament", [0.0, 0.0]),
+                WireSection::Rectangle(width, height) => ("rectangle", [width, height]),
+                WireSection::Circle(radius) => ("circle", [radius, radius]),
+            };
+            MagnetKind::Wire(ReadWire::new(
+                [mag.center.x, mag.center.y],
+                mag.current,
+                section.to_string(),
+                size,
+                mag.alpha.to_degrees(),
+                "degrees".to_string(),
+            ))
+        }
+        Magnet2D::Ring(mag) => MagnetKind::Ring(ReadRing::new(
+            [mag.inner_radius, mag.outer_radius],
+            [mag.center.x, mag.center.y],
+            [mag.jr, mag.phi.to_degrees()],
+            "degrees".to_string(),
+            match mag.magnetisation {
+                RingMagnetisation::Diametric => "diametric",
+                RingMagnetisation::Radial => "radial",
+                RingMagnetisation::Azimuthal => "azimuthal",
+            }
+            .to_string(),
+            mag.alpha.to_degrees(),
+            "degrees".to_string(),
+        )),
+        Magnet2D::Ellipse(mag) => MagnetKind::Ellipse(ReadEllipse::new(
+            [mag.a, mag.b],
+            [mag.center.x, mag.center.y],
+            [mag.jr, mag.phi.to_degrees()],
+            "degrees".to_string(),
+            mag.alpha.to_degrees(),
+            "degrees".to_string(),
+        )),
+        Magnet2D::ArcSegment(mag) => MagnetKind::ArcSegment(ReadArcSegment::new(
+            [mag.inner_radius, mag.outer_radius],
+            [mag.center.x, mag.center.y],
+            mag.jr,
+            mag.alpha.to_degrees(),
+            "degrees".to_string(),
+            mag.span.to_degrees(),
+            "degrees".to_string(),
         )),
     })
 }
@@ -92,12 +152,25 @@ pub fn save_results(sim_result: &SimResult, outfile: &str) -> Result<(), MagnetE
 #[cfg(test)]
 mod tests {
     use crate::{
-        magnets::Rectangle,
-        parse::{Configure, GridKind2D},
+        magnets::{get_energy_2d, ArcSegment, Ellipse, Polygon, Rectangle, Ring, Vertices, Wire},
+        parse::{generate_magnets, Configure, GridKind2D},
+        utils::conversions::Angle,
     };
 
     use super::*;
 
+    /// Writes `magnet_list` to a TOML config and reads it back, returning the config string
+    fn assert_toml_round_trip(magnet_list: &[Magnet2D]) -> String {
+        let config = Configure {
+            magnet: gen_magnet_toml_2d(magnet_list).unwrap(),
+            grid: GridKind2D::None,
+        };
+        let config_string = toml::to_string(&config).unwrap();
+        let config: Configure = toml::from_str(&config_string).unwrap();
+        assert_eq!(generate_magnets(config.magnet).unwrap(), magnet_list);
+        config_string
+    }
+
     #[test]
     pub fn test_magnet_to_toml_struct() {
         let m1 = Magnet2D::Rectangle(Rectangle::default());
@@ -129,4 +202,124 @@ mod tests {
         let comp_string = "[grid]\nkind = \"none\"\n\n[[magnet]]\nkind = \"rectangle\"\nsize = [1.0, 1.0]\ncenter = [0.0, 0.0]\nmagnetisation = [1.0, 90.0]\nmagAngle = \"degrees\"\nalpha = 0.0\nalphaAngle = \"degrees\"\n\n[[magnet]]\nkind = \"rectangle\"\nsize = [1.0, 1.0]\ncenter = [0.0, 0.0]\nmagnetisation = [1.0, 90.0]\nmagAngle = \"degrees\"\nalpha = 0.0\nalphaAngle = \"degrees\"\n".to_string();
         assert_eq!(config_string, comp_string);
     }
+
+    #[test]
+    pub fn test_wire_toml_round_trip() {
+        let magnet_list = vec![
+            Magnet2D::Wire(Wire::default()),
+            Magnet2D::Wire(Wire::new(
+                (1.0, -2.0),
+                Angle::Degrees(30.0),
+                -5.0,
+                WireSection::Rectangle(0.1, 0.3),
+            )),
+            Magnet2D::Wire(Wire::new(
+                (0.0, 0.5),
+                Angle::Degrees(0.0),
+                2.0,
+                WireSection::Circle(0.2),
+            )),
+        ];
+
+        let config_string = assert_toml_round_trip(&magnet_list);
+        assert!(config_string.contains("kind = \"wire\"\n"));
+    }
+
+    #[test]
+    pub fn test_ring_toml_round_trip() {
+        let magnet_list: Vec<Magnet2D> = [
+            RingMagnetisation::Diametric,
+            RingMagnetisation::Radial,
+            RingMagnetisation::Azimuthal,
+        ]
+        .iter()
+        .map(|magnetisation| {
+            Magnet2D::Ring(Ring::new(
+                0.25,
+                0.75,
+                (1.0, -0.5),
+                Angle::Degrees(15.0),
+                1.1,
+                Angle::Degrees(60.0),
+                *magnetisation,
+            ))
+        })
+        .collect();
+
+        assert_toml_round_trip(&magnet_list);
+    }
+
+    #[test]
+    pub fn test_arc_segment_toml_round_trip() {
+        let magnet_list = vec![Magnet2D::ArcSegment(ArcSegment::new(
+            0.4,
+            0.6,
+            (0.1, 0.0),
+            Angle::Degrees(45.0),
+            Angle::Degrees(30.0),
+            -1.2,
+        ))];
+
+        assert_toml_round_trip(&magnet_list);
+    }
+
+    #[test]
+    pub fn test_ellipse_toml_round_trip() {
+        let magnet_list = vec![Magnet2D::Ellipse(Ellipse::new(
+            0.8,
+            0.2,
+            (0.5, -1.0),
+            Angle::Degrees(20.0),
+            1.3,
+            Angle::Degrees(110.0),
+        ))];
+
+        assert_toml_round_trip(&magnet_list);
+    }
+
+    #[test]
+    pub fn test_polygon_with_holes_toml_round_trip() {
+        let magnet_list = vec![Magnet2D::Polygon(
+            Polygon::with_holes(
+                (0.0, 0.0),
+                Angle::Degrees(0.0),
+                1.1,
+                Angle::Degrees(60.0),
+                Vertices::Custom(PointVec2::new(
+                    vec![-1.0, -1.0, 2.0, 2.0],
+                    vec![-1.0, 1.0, 1.0, -1.0],
+                )),
+                vec![
+                    PointVec2::new(vec![-0.5, 0.0, -0.25], vec![-0.5, -0.5, 0.5]),
+                    PointVec2::new(vec![1.0, 1.5, 1.5, 1.0], vec![-0.2, -0.2, 0.2, 0.2]),
+                ],
+            )
+            .unwrap(),
+        )];
+
+        let config_string = assert_toml_round_trip(&magnet_list);
+        assert!(config_string.contains("holes"));
+    }
+
+    #[test]
+    pub fn test_sim_result_field_kind() {
+        let magnet_list = vec![Magnet2D::Rectangle(Rectangle::default())];
+        let points = PointVec2::new(vec![0.0, 2.0], vec![0.0, 0.0]);
+        let field = points.get_field(&magnet_list, FieldKind::H);
+        let sim_res = SimResult::new(
+            gen_magnet_toml_2d(&magnet_list).unwrap(),
+            points,
+            "mm".to_string(),
+            FieldKind::H,
+            field,
+            get_energy_2d(&magnet_list).unwrap(),
+        );
+
+        let json = serde_json::to_string(&sim_res).unwrap();
+        assert!(json.contains("\"fieldKind\":\"h\""));
+        assert!(json.contains("\"selfEnergy\""));
+
+        // H opposes the magnetisation inside the magnet
+        assert!(sim_res.field.y[0] < 0.0);
+    }
 }
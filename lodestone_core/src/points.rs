@@ -11,6 +11,7 @@ Copyright 2021 Peter Dunne */
 
 // mod point_array2;
 mod point_vec2;
+mod point_vec3;
 mod points2;
 mod points3;
 mod polarpoints;
@@ -19,6 +20,7 @@ mod rotation_2d;
 // make subroutines available from this module
 // pub use point_array2::*;
 pub use point_vec2::*;
+pub use point_vec3::*;
 pub use points2::*;
 pub use points3::*;
 pub use polarpoints::*;
@@ -7,7 +7,7 @@ Copyright 2021 Peter Dunne */
 //! an array of structs, but not as efficient as struct of arrays
 //!//!
 
-use crate::magnets::{get_field_2d, Magnet2D};
+use crate::magnets::{get_field_kind_2d, get_gradient_2d, get_potential_2d, FieldKind, Magnet2D};
 use crate::points::rotation_2d::rotate_tuple2;
 use crate::points::{internal_norm, Point2, Points};
 use rayon::prelude::*;
@@ -490,8 +490,10 @@ impl PointVec2 {
         PointVec2::new(x_local, y_local)
     }
 
-    /// Returns the magnetic field for a series of points due to all magnets
-    pub fn get_field(&self, magnet_list: &[Magnet2D]) -> PointVec2 {
+    /// Returns the magnetic field for a series of points due to all magnets.
+    ///
+    /// `field_kind` selects between B in tesla, or H and M in A/m.
+    pub fn get_field(&self, magnet_list: &[Magnet2D], field_kind: FieldKind) -> PointVec2 {
         let pb = ProgressBar::new(self.x.len() as u64);
 
         pb.set_style(ProgressStyle::default_bar().template(
@@ -503,12 +505,42 @@ impl PointVec2 {
             .par_iter()
             .zip(self.y.par_iter())
             .progress_with(pb)
-            .map(|(x, y)| get_field_2d(magnet_list, (x, y)).unwrap())
+            .map(|(x, y)| get_field_kind_2d(magnet_list, (x, y), field_kind).unwrap())
             .collect::<(Vec<f64>, Vec<f64>)>();
 
         PointVec2::new(x_local, y_local)
     }
 
+    /// Returns the field gradient tensor for a series of points due to all
+    /// magnets, as the four components (xx, xy, yx, yy), where e.g. xy is
+    /// $`\partial B_x / \partial y`$
+    pub fn get_gradient(
+        &self,
+        magnet_list: &[Magnet2D],
+    ) -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
+        let ((xx, xy), (yx, yy)) = self
+            .x
+            .par_iter()
+            .zip(self.y.par_iter())
+            .map(|(x, y)| {
+                let (xx, xy, yx, yy) = get_gradient_2d(magnet_list, (x, y)).unwrap();
+                ((xx, xy), (yx, yy))
+            })
+            .collect::<((Vec<f64>, Vec<f64>), (Vec<f64>, Vec<f64>))>();
+
+        (xx, xy, yx, yy)
+    }
+
+    /// Returns the out-of-plane vector potential $`A_z`$ for a series of points
+    /// due to all magnets. Contours of $`A_z`$ are field lines.
+    pub fn get_potential(&self, magnet_list: &[Magnet2D]) -> Vec<f64> {
+        self.x
+            .par_iter()
+            .zip(self.y.par_iter())
+            .map(|(x, y)| get_potential_2d(magnet_list, (x, y)).unwrap())
+            .collect()
+    }
+
     /// Returns Point2 for a given index of a PointVec2 struct
     pub fn to_point(&self, index: usize) -> Point2 {
         Point2::new(self.x[index], self.y[index])
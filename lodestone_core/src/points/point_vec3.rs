@@ -0,0 +1,189 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+License, v. 2.0. If a copy of the MPL was not distributed with this
+file, You can obtain one at https://mozilla.org/MPL/2.0/.
+Copyright 2021 Peter Dunne */
+//! # PointVec3
+//! Structs of vectors to store x, y, and z values, the 3D counterpart of
+//! `PointVec2`
+//!
+
+use crate::magnets::{get_field_3d, Magnet3D};
+use crate::points::Point3;
+use rayon::prelude::*;
+
+use indicatif::{ParallelProgressIterator, ProgressBar, ProgressStyle};
+use serde_derive::{Deserialize, Serialize};
+
+/// Point3 Vector struct
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PointVec3 {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub z: Vec<f64>,
+}
+
+impl Default for PointVec3 {
+    fn default() -> Self {
+        Self {
+            x: vec![0.0],
+            y: vec![0.0],
+            z: vec![0.0],
+        }
+    }
+}
+
+impl PointVec3 {
+    /// Constructor for PointVec3
+    pub fn new(x: Vec<f64>, y: Vec<f64>, z: Vec<f64>) -> Self {
+        assert!(
+            x.len() == y.len() && x.len() == z.len(),
+            "Input vectors must be the same length!"
+        );
+        Self { x, y, z }
+    }
+
+    /// Returns a point/vector of zeros
+    pub fn zero(num_elements: &usize) -> Self {
+        Self {
+            x: vec![0.0; *num_elements],
+            y: vec![0.0; *num_elements],
+            z: vec![0.0; *num_elements],
+        }
+    }
+
+    /// Returns the number of points
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    /// Returns true if there are no points
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Returns the magnetic field for a series of points due to all magnets
+    pub fn get_field(&self, magnet_list: &[Magnet3D]) -> PointVec3 {
+        let pb = ProgressBar::new(self.x.len() as u64);
+
+        pb.set_style(ProgressStyle::default_bar().template(
+            "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] ({percent}%, ETA {eta})",
+        ));
+
+        let (xy_local, z_local): (Vec<(f64, f64)>, Vec<f64>) = self
+            .x
+            .par_iter()
+            .zip(self.y.par_iter())
+            .zip(self.z.par_iter())
+            .progress_with(pb)
+            .map(|((x, y), z)| {
+                let field = get_field_3d(magnet_list, (x, y, z)).unwrap();
+                ((field.0, field.1), field.2)
+            })
+            .unzip();
+        let (x_local, y_local) = xy_local.into_iter().unzip();
+
+        PointVec3::new(x_local, y_local, z_local)
+    }
+
+    /// Returns Point3 for a given index of a PointVec3 struct
+    pub fn to_point(&self, index: usize) -> Point3 {
+        Point3::new(self.x[index], self.y[index], self.z[index])
+    }
+}
+
+/// Generates a line of points in 3D from a Point3 start point to a Point3 end point
+/// which is stored in a PointVec3 struct
+pub fn gen_line_3d(start: &Point3, stop: &Point3, num_points: &usize) -> PointVec3 {
+    let distance = *stop - *start;
+    let step_x = distance.x / (num_points - 1) as f64;
+    let step_y = distance.y / (num_points - 1) as f64;
+    let step_z = distance.z / (num_points - 1) as f64;
+
+    PointVec3 {
+        x: (0..*num_points)
+            .map(|i| start.x + i as f64 * step_x)
+            .collect(),
+        y: (0..*num_points)
+            .map(|i| start.y + i as f64 * step_y)
+            .collect(),
+        z: (0..*num_points)
+            .map(|i| start.z + i as f64 * step_z)
+            .collect(),
+    }
+}
+
+/// Generates a 3D grid of points (x_min:x_max, y_min:y_max, z_min:z_max),
+/// stored in a PointVec3 struct
+pub fn cart_prod_3d_vec(start: &Point3, stop: &Point3, num_points: &usize) -> PointVec3 {
+    let distance = *stop - *start;
+    let step_x = distance.x / (num_points - 1) as f64;
+    let step_y = distance.y / (num_points - 1) as f64;
+    let step_z = distance.z / (num_points - 1) as f64;
+
+    let n = *num_points;
+    let mut result = PointVec3::zero(&(n * n * n));
+    for i in 0..n {
+        for j in 0..n {
+            for k in 0..n {
+                let index = (i * n + j) * n + k;
+                result.x[index] = start.x + i as f64 * step_x;
+                result.y[index] = start.y + j as f64 * step_y;
+                result.z[index] = start.z + k as f64 * step_z;
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::magnets::{Prism, Sphere};
+    use crate::points::Points3;
+    use crate::utils::comparison::nearly_equal;
+
+    #[test]
+    fn test_new() {
+        let vec = PointVec3::new(vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]);
+        assert_eq!(vec.to_point(1), Point3::new(2.0, 4.0, 6.0));
+        assert_eq!(vec.len(), 2);
+    }
+
+    #[test]
+    fn test_gen_line_3d() {
+        let output = gen_line_3d(&Point3::zero(), &Point3::new(2.0, 4.0, -4.0), &5);
+        let comp_array = PointVec3 {
+            x: vec![0.0, 0.5, 1.0, 1.5, 2.0],
+            y: vec![0.0, 1.0, 2.0, 3.0, 4.0],
+            z: vec![0.0, -1.0, -2.0, -3.0, -4.0],
+        };
+        assert_eq!(output, comp_array);
+    }
+
+    #[test]
+    fn test_cart_prod_3d() {
+        let output = cart_prod_3d_vec(&Point3::zero(), &Point3::new(1.0, 2.0, 3.0), &3);
+        assert_eq!(output.len(), 27);
+        assert_eq!(output.to_point(5), Point3::new(0.0, 1.0, 3.0));
+    }
+
+    #[test]
+    fn test_get_field() {
+        let magnet_list = vec![
+            Magnet3D::Prism(Prism::default()),
+            Magnet3D::Sphere(Sphere::new(
+                1.0,
+                (0.0, 0.0, 10.0),
+                0.0,
+                crate::utils::conversions::Angle::Degrees(0.0),
+                crate::utils::conversions::Angle::Degrees(0.0),
+            )),
+        ];
+        let points = gen_line_3d(&Point3::zero(), &Point3::new(0.0, 0.0, 0.1), &3);
+        let field = points.get_field(&magnet_list);
+
+        assert_eq!(field.len(), 3);
+        assert!(nearly_equal(field.z[0], 2.0 / 3.0));
+        assert!(nearly_equal(field.x[2], 0.0));
+    }
+}
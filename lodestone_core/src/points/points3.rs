@@ -1,6 +1,6 @@
 use serde_derive::{Deserialize, Serialize};
 use std::fmt;
-use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
 
 /// 3D point, with fields x,y,z
 #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Deserialize, Serialize)]
@@ -246,6 +246,16 @@ impl Add for Point3 {
     }
 }
 
+impl AddAssign for Point3 {
+    fn add_assign(&mut self, other: Self) {
+        *self = Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        };
+    }
+}
+
 impl Sub for Point3 {
     type Output = Self;
 
@@ -5,4 +5,256 @@ Copyright 2021 Peter Dunne */
 //! Quaternions
 //! Methods for rotating vectors and points using quaternions
 //!
+//! Orientations of 3D magnets are given by three Euler angles (alpha, beta, gamma)
+//! using the intrinsic z-y'-x'' (yaw, pitch, roll) convention. That is, the
+//! magnet is first rotated by alpha about the z-axis, then by beta about the new
+//! y-axis, and finally by gamma about the new x-axis, which is equivalent to the
+//! rotation matrix $`R = R_z(\alpha) R_y(\beta) R_x(\gamma)`$.
 //!
+//! All rotations are right handed (anti-clockwise when looking down the axis
+//! towards the origin).
+
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Mul;
+
+use crate::points::{Point3, Points3};
+use crate::utils::conversions::Angle;
+use crate::{FP_CUTOFF, PI_2};
+
+/// Quaternion struct, $`q = w + x\mathbf{i} + y\mathbf{j} + z\mathbf{k}`$
+#[derive(Copy, Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Quaternion {
+    /// scalar part
+    pub w: f64,
+    /// i component
+    pub x: f64,
+    /// j component
+    pub y: f64,
+    /// k component
+    pub z: f64,
+}
+
+impl Default for Quaternion {
+    /// Returns the identity quaternion, i.e. no rotation
+    fn default() -> Self {
+        Quaternion::identity()
+    }
+}
+
+impl fmt::Display for Quaternion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}, {}, {}]", self.w, self.x, self.y, self.z)
+    }
+}
+
+impl Quaternion {
+    /// Constructor for a Quaternion
+    pub fn new(w: f64, x: f64, y: f64, z: f64) -> Quaternion {
+        Quaternion { w, x, y, z }
+    }
+
+    /// Returns the identity quaternion, i.e. no rotation
+    pub fn identity() -> Quaternion {
+        Quaternion::new(1.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Returns the unit quaternion for a rotation by `angle` about `axis`.
+    ///
+    /// The axis need not be normalised. A zero axis returns the identity.
+    pub fn from_axis_angle(axis: &Point3, angle: &Angle) -> Quaternion {
+        let norm = axis.magnitude();
+        if norm < FP_CUTOFF {
+            return Quaternion::identity();
+        }
+        let half_angle = angle.to_radians() / 2.0;
+        let s = half_angle.sin() / norm;
+        Quaternion::new(half_angle.cos(), axis.x * s, axis.y * s, axis.z * s)
+    }
+
+    /// Returns the unit axis and angle of rotation of a unit quaternion.
+    ///
+    /// For the identity, the axis is returned as the z-axis.
+    pub fn to_axis_angle(&self) -> (Point3, Angle) {
+        let q = self.unit();
+        let sin_half = (q.x.powi(2) + q.y.powi(2) + q.z.powi(2)).sqrt();
+        if sin_half < FP_CUTOFF * FP_CUTOFF {
+            return (Point3::k_hat(), Angle::Radians(0.0));
+        }
+        let angle = 2.0 * sin_half.atan2(q.w);
+        let axis = Point3::new(q.x / sin_half, q.y / sin_half, q.z / sin_half);
+        (axis, Angle::Radians(angle))
+    }
+
+    /// Returns the unit quaternion for the Euler angles alpha (about z),
+    /// beta (about y'), and gamma (about x''), i.e.
+    /// $`q = q_z(\alpha) q_y(\beta) q_x(\gamma)`$
+    pub fn from_euler(alpha: &Angle, beta: &Angle, gamma: &Angle) -> Quaternion {
+        let q_z = Quaternion::from_axis_angle(&Point3::k_hat(), alpha);
+        let q_y = Quaternion::from_axis_angle(&Point3::j_hat(), beta);
+        let q_x = Quaternion::from_axis_angle(&Point3::i_hat(), gamma);
+        q_z * q_y * q_x
+    }
+
+    /// Returns the Euler angles (alpha, beta, gamma) of a unit quaternion, in
+    /// the z-y'-x'' convention used by `from_euler`.
+    ///
+    /// beta is in the range [-90°, 90°]. At beta = ±90° (gimbal lock), gamma is
+    /// set to zero.
+    pub fn to_euler(&self) -> (Angle, Angle, Angle) {
+        let q = self.unit();
+        let sin_beta = (2.0 * (q.w * q.y - q.z * q.x)).clamp(-1.0, 1.0);
+        let beta = sin_beta.asin();
+
+        if (1.0 - sin_beta.abs()) < FP_CUTOFF * FP_CUTOFF {
+            let alpha = -sin_beta.signum() * 2.0 * q.x.atan2(q.w);
+            return (
+                Angle::Radians(alpha),
+                Angle::Radians(sin_beta.signum() * PI_2),
+                Angle::Radians(0.0),
+            );
+        }
+
+        let alpha = (2.0 * (q.w * q.z + q.x * q.y)).atan2(1.0 - 2.0 * (q.y.powi(2) + q.z.powi(2)));
+        let gamma = (2.0 * (q.w * q.x + q.y * q.z)).atan2(1.0 - 2.0 * (q.x.powi(2) + q.y.powi(2)));
+        (
+            Angle::Radians(alpha),
+            Angle::Radians(beta),
+            Angle::Radians(gamma),
+        )
+    }
+
+    /// Returns the conjugate, which for a unit quaternion is the inverse rotation
+    pub fn conjugate(&self) -> Quaternion {
+        Quaternion::new(self.w, -self.x, -self.y, -self.z)
+    }
+
+    /// Returns the norm of the quaternion
+    pub fn norm(&self) -> f64 {
+        (self.w.powi(2) + self.x.powi(2) + self.y.powi(2) + self.z.powi(2)).sqrt()
+    }
+
+    /// Returns the normalised quaternion
+    pub fn unit(&self) -> Quaternion {
+        let norm = self.norm();
+        Quaternion::new(self.w / norm, self.x / norm, self.y / norm, self.z / norm)
+    }
+
+    /// Returns the inverse of the quaternion
+    pub fn inverse(&self) -> Quaternion {
+        let norm_sq = self.norm().powi(2);
+        let conj = self.conjugate();
+        Quaternion::new(
+            conj.w / norm_sq,
+            conj.x / norm_sq,
+            conj.y / norm_sq,
+            conj.z / norm_sq,
+        )
+    }
+
+    /// Returns true if this is the identity rotation
+    pub fn is_identity(&self) -> bool {
+        (self.x.abs() + self.y.abs() + self.z.abs()) < FP_CUTOFF * FP_CUTOFF && self.w > 0.0
+    }
+
+    /// Rotates a vector by a unit quaternion, $`q \mathbf{v} q^*`$
+    pub fn rotate(&self, vector: &Point3) -> Point3 {
+        // Uses v' = v + 2w (u x v) + 2 u x (u x v), where u is the vector part
+        let u = Point3::new(self.x, self.y, self.z);
+        let t = u.cross(vector).scale(2.0);
+        *vector + t.scale(self.w) + u.cross(&t)
+    }
+
+    /// Rotates a vector by the inverse of a unit quaternion, $`q^* \mathbf{v} q`$
+    pub fn rotate_inverse(&self, vector: &Point3) -> Point3 {
+        self.conjugate().rotate(vector)
+    }
+}
+
+impl Mul for Quaternion {
+    type Output = Self;
+
+    /// Hamilton product, `a * b` is the rotation `b` followed by `a`
+    fn mul(self, other: Self) -> Self::Output {
+        Quaternion::new(
+            self.w * other.w - self.x * other.x - self.y * other.y - self.z * other.z,
+            self.w * other.x + self.x * other.w + self.y * other.z - self.z * other.y,
+            self.w * other.y - self.x * other.z + self.y * other.w + self.z * other.x,
+            self.w * other.z + self.x * other.y - self.y * other.x + self.z * other.w,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::comparison::nearly_equal;
+
+    fn points_equal(a: &Point3, b: &Point3) -> bool {
+        (a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12 && (a.z - b.z).abs() < 1e-12
+    }
+
+    #[test]
+    fn test_rotate_z_90() {
+        let q = Quaternion::from_axis_angle(&Point3::k_hat(), &Angle::Degrees(90.0));
+        let rotated = q.rotate(&Point3::i_hat());
+        assert!(points_equal(&rotated, &Point3::j_hat()));
+        assert!(points_equal(&q.rotate_inverse(&rotated), &Point3::i_hat()));
+    }
+
+    #[test]
+    fn test_composition() {
+        // Rotating x by 90° about z, then by 90° about x, gives z
+        let q_z = Quaternion::from_axis_angle(&Point3::k_hat(), &Angle::Degrees(90.0));
+        let q_x = Quaternion::from_axis_angle(&Point3::i_hat(), &Angle::Degrees(90.0));
+        let rotated = (q_x * q_z).rotate(&Point3::i_hat());
+        assert!(points_equal(&rotated, &Point3::k_hat()));
+    }
+
+    #[test]
+    fn test_euler_round_trip() {
+        let q = Quaternion::from_euler(
+            &Angle::Degrees(30.0),
+            &Angle::Degrees(-45.0),
+            &Angle::Degrees(120.0),
+        );
+        let (alpha, beta, gamma) = q.to_euler();
+        assert!(nearly_equal(alpha.to_degrees(), 30.0));
+        assert!(nearly_equal(beta.to_degrees(), -45.0));
+        assert!(nearly_equal(gamma.to_degrees(), 120.0));
+    }
+
+    #[test]
+    fn test_euler_gimbal_lock() {
+        let q = Quaternion::from_euler(
+            &Angle::Degrees(20.0),
+            &Angle::Degrees(90.0),
+            &Angle::Degrees(0.0),
+        );
+        let (alpha, beta, gamma) = q.to_euler();
+        let q_2 = Quaternion::from_euler(&alpha, &beta, &gamma);
+        assert!(nearly_equal(beta.to_radians(), PI_2));
+        let v = Point3::new(0.3, -0.2, 0.7);
+        assert!(points_equal(&q.rotate(&v), &q_2.rotate(&v)));
+    }
+
+    #[test]
+    fn test_axis_angle_round_trip() {
+        let axis = Point3::new(1.0, 2.0, -2.0);
+        let q = Quaternion::from_axis_angle(&axis, &Angle::Degrees(75.0));
+        let (axis_2, angle) = q.to_axis_angle();
+        assert!(nearly_equal(angle.to_degrees(), 75.0));
+        assert!(points_equal(&axis_2, &axis.unit()));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let q = Quaternion::from_euler(
+            &Angle::Degrees(10.0),
+            &Angle::Degrees(20.0),
+            &Angle::Degrees(30.0),
+        );
+        let product = q * q.inverse();
+        assert!(product.is_identity() || nearly_equal(product.w, 1.0));
+    }
+}
@@ -4,7 +4,9 @@
 ///! - infile TOML configuration file
 ///! - outfile: simulation result
 ///! - silent: boolean
+///! - field: which field to calculate, B, H, or M
 use clap::{App, Arg};
+use lodestone_core::magnets::FieldKind;
 
 /// Command line arguments struct, infile, outfile, and silent (i.e. emit to stdout)
 pub struct Args {
@@ -16,6 +18,8 @@ pub struct Args {
     pub silent: bool,
 
     pub demo: bool,
+    /// Which field to calculate, B in tesla, or H and M in A/m
+    pub field_kind: FieldKind,
 }
 
 impl Args {
@@ -46,6 +50,15 @@ impl Args {
                     .long("demo")
                     .help("Runs an example calculation of two square magnets and saves it to example_out.json."),
             )
+            .arg(
+                Arg::with_name("field")
+                    .short("f")
+                    .long("field")
+                    .takes_value(true)
+                    .possible_values(&["B", "H", "M"])
+                    .default_value("B")
+                    .help("Field to calculate, B in tesla, or H and M in A/m"),
+            )
             .get_matches();
 
         let infile = matches.value_of("infile").unwrap_or_default().to_string();
@@ -59,11 +72,19 @@ impl Args {
         let demo = matches.is_present("demo");
 
         let silent = matches.is_present("silent");
+
+        let field_kind = match matches.value_of("field") {
+            Some("H") => FieldKind::H,
+            Some("M") => FieldKind::M,
+            _ => FieldKind::B,
+        };
+
         Self {
             infile,
             outfile,
             silent,
             demo,
+            field_kind,
         }
     }
 }
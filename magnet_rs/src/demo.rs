@@ -1,12 +1,12 @@
 use lodestone_core::{
-    magnets::{Magnet2D, Rectangle},
+    magnets::{get_energy_2d, FieldKind, Magnet2D, Rectangle},
     parse::{gen_magnet_toml_2d, save_results, SimResult},
     points::{cart_prod_2d_vec, Point2},
     utils::conversions::Angle,
     MagnetError,
 };
 
-pub fn run_demo() -> Result<(), MagnetError> {
+pub fn run_demo(field_kind: FieldKind) -> Result<(), MagnetError> {
     println!("Running Demo Calculation, two square magnets over a grid of 100 points");
 
     let outfile = "example_out.json";
@@ -35,12 +35,13 @@ pub fn run_demo() -> Result<(), MagnetError> {
     magnet_list.push(Magnet2D::Rectangle(m2));
 
     // Calculate the magnetic field
-    let field = points.get_field(&magnet_list);
+    let field = points.get_field(&magnet_list, field_kind);
+    let energy = get_energy_2d(&magnet_list)?;
     let units = "mm".to_string();
 
     let mag_toml = gen_magnet_toml_2d(&magnet_list)?;
 
-    let sim_res = SimResult::new(mag_toml, points, units, field);
+    let sim_res = SimResult::new(mag_toml, points, units, field_kind, field, energy);
     println!("Saving to {:#?}", outfile);
     save_results(&sim_res, outfile)?;
     println!("Done");
@@ -20,10 +20,11 @@ fn main() -> Result<()> {
         outfile,
         silent,
         demo,
+        field_kind,
     } = args;
 
     if demo {
-        demo::run_demo()?
+        demo::run_demo(field_kind)?
     } else {
         let (magnet_list, points) = lodestone_core::parse::parse_config_file(&infile)?;
         if !silent {
@@ -32,13 +33,14 @@ fn main() -> Result<()> {
         }
 
         // Calculate the magnetic field
-        let field = points.get_field(&magnet_list);
+        let field = points.get_field(&magnet_list, field_kind);
         let units = "mm".to_string();
 
         let mag_toml = lodestone_core::parse::gen_magnet_toml_2d(&magnet_list)?;
 
         if outfile.is_some() {
-            let sim_res = SimResult::new(mag_toml, points, units, field);
+            let energy = lodestone_core::magnets::get_energy_2d(&magnet_list)?;
+            let sim_res = SimResult::new(mag_toml, points, units, field_kind, field, energy);
             println!("Saving to {:#?}", outfile.as_ref().unwrap());
             lodestone_core::parse::save_results(&sim_res, &outfile.unwrap())?;
             println!("Done")